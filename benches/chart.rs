@@ -0,0 +1,66 @@
+//! building a chart axis & laying out a `LineChart`, including its path, at various series
+//! lengths.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use idle_dungeoneer::frontend::chart::{
+    grid::Axis,
+    line::{LineChartModel, LineChartProps},
+    types::{Rect, Series},
+};
+
+const POINT_COUNTS: [usize; 3] = [100, 1_000, 10_000];
+
+/// a wavy, rising line of `len` points, roughly what the idle time graph looks like.
+fn series(len: usize) -> Series {
+    vec![
+        (0..len)
+            .map(|i| {
+                let x = i as f32 / len as f32;
+
+                x * 30.0 + (x * 40.0).sin() * 5.0
+            })
+            .collect(),
+    ]
+}
+
+fn axis_build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("axis_build");
+    let view = Rect::new(0.0, 0.0, 600.0, 400.0);
+
+    for len in POINT_COUNTS {
+        let series = series(len);
+
+        group.bench_with_input(BenchmarkId::from_parameter(len), &series, |b, series| {
+            b.iter(|| {
+                black_box(
+                    Axis::builder()
+                        .with_view(view)
+                        .with_max_ticks(8)
+                        .with_series(series)
+                        .build(),
+                )
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn line_chart(c: &mut Criterion) {
+    let mut group = c.benchmark_group("line_chart_model");
+
+    for len in POINT_COUNTS {
+        let props = LineChartProps::builder().series(series(len)).build();
+
+        group.bench_with_input(BenchmarkId::from_parameter(len), &props, |b, props| {
+            b.iter(|| black_box(LineChartModel::new(props)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, axis_build, line_chart);
+criterion_main!(benches);
@@ -0,0 +1,70 @@
+//! `step_idle_time` with the input history holding a second of inputs at various rates.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use bevy::prelude::*;
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use idle_dungeoneer::backend::{
+    CurrentIdleTimeSeconds, LongestIdleTimeSeconds,
+    idle_time_plugin::{KeyCount, TIME_WINDOW, input_history::InputHistory, step_idle_time},
+    modifier::Rates,
+};
+
+/// inputs per second.
+const INPUT_RATES: [usize; 5] = [0, 1, 10, 50, 200];
+const FRAME_TIME: Duration = Duration::from_nanos(16_666_667);
+
+fn world(input_rate: usize) -> (World, Schedule) {
+    let now = Instant::now();
+    let mut history = InputHistory::default();
+
+    for i in (0..input_rate).rev() {
+        history.push(now - Duration::from_secs_f64(TIME_WINDOW) * i as u32 / input_rate as u32);
+    }
+
+    let mut time = Time::<()>::default();
+    time.advance_by(FRAME_TIME);
+
+    let mut world = World::new();
+    world.insert_resource(KeyCount(history.len()));
+    world.insert_resource(history);
+    world.insert_resource(CurrentIdleTimeSeconds(0.0));
+    world.insert_resource(LongestIdleTimeSeconds(0.0));
+    world.init_resource::<Rates>();
+    world.insert_resource(time);
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(step_idle_time);
+    schedule.run(&mut world);
+
+    (world, schedule)
+}
+
+fn idle_time(c: &mut Criterion) {
+    let mut group = c.benchmark_group("step_idle_time");
+
+    for input_rate in INPUT_RATES {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(input_rate),
+            &input_rate,
+            |b, &input_rate| {
+                b.iter_batched_ref(
+                    || world(input_rate),
+                    |(world, schedule)| {
+                        schedule.run(world);
+                        black_box(world.resource::<CurrentIdleTimeSeconds>().0)
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, idle_time);
+criterion_main!(benches);
@@ -0,0 +1,167 @@
+//! compares `InputHistory` against the `KeyPress` entity per input design it replaced. each
+//! iteration is one frame: an input comes in, the inputs older than the window are dropped, then
+//! the windowed count & average interval are worked out.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use bevy::prelude::*;
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use idle_dungeoneer::backend::idle_time_plugin::{TIME_WINDOW, input_history::InputHistory};
+
+/// how many inputs are already in the window when the frame runs.
+const INPUT_COUNTS: [usize; 4] = [1, 10, 100, 250];
+
+#[derive(Component)]
+struct KeyPress(Instant);
+
+#[derive(Resource, Default)]
+struct KeyCount(usize);
+
+#[derive(Resource, Default)]
+struct AvgPressTimeDelta(f64);
+
+/// timestamps for `count` inputs spread evenly over the last half of the window.
+fn timestamps(count: usize) -> Vec<Instant> {
+    let now = Instant::now();
+    let spacing = Duration::from_secs_f64(TIME_WINDOW * 0.5) / count as u32;
+
+    (0..count).rev().map(|i| now - spacing * i as u32).collect()
+}
+
+fn legacy_gather(mut cmds: Commands) {
+    cmds.spawn(KeyPress(Instant::now()));
+}
+
+fn legacy_step_inputs(
+    mut cmds: Commands,
+    presses: Query<(Entity, &KeyPress)>,
+    mut key_count: ResMut<KeyCount>,
+) {
+    let mut presses: Vec<(Entity, &KeyPress)> = presses.into_iter().collect();
+    presses.retain(|(entity, press)| {
+        let is_old = press.0.elapsed() >= Duration::from_secs_f64(TIME_WINDOW);
+
+        if is_old {
+            cmds.entity(*entity).despawn();
+        }
+
+        !is_old
+    });
+
+    key_count.0 = presses.len();
+}
+
+fn legacy_step_idle_time(
+    key_count: Res<KeyCount>,
+    presses: Query<&KeyPress>,
+    mut avg: ResMut<AvgPressTimeDelta>,
+) {
+    if key_count.0 == 0 {
+        return;
+    }
+
+    let mut presses: Vec<&KeyPress> = presses
+        .iter()
+        .sort_by::<&KeyPress>(|val1, val2| {
+            val1.0
+                .elapsed()
+                .as_secs_f64()
+                .total_cmp(&val2.0.elapsed().as_secs_f64())
+                .then(std::cmp::Ordering::Less)
+        })
+        .collect();
+    let now = KeyPress(Instant::now());
+    presses.push(&now);
+    let total_time_delta: Duration = presses
+        .windows(2)
+        .map(|presses| presses[0].0 - presses[1].0)
+        .sum();
+
+    avg.0 = total_time_delta.as_secs_f64() / presses.len() as f64;
+}
+
+fn ring_buffer_frame(mut history: ResMut<InputHistory>, mut avg: ResMut<AvgPressTimeDelta>) {
+    let now = Instant::now();
+    let window = Duration::from_secs_f64(TIME_WINDOW);
+
+    history.push(now);
+    history.expire(now, window);
+    avg.0 = history
+        .average_interval(now, window)
+        .unwrap_or_default()
+        .as_secs_f64();
+}
+
+fn legacy_world(count: usize) -> (World, Schedule) {
+    let mut world = World::new();
+    world.init_resource::<KeyCount>();
+    world.init_resource::<AvgPressTimeDelta>();
+    world.spawn_batch(timestamps(count).into_iter().map(KeyPress));
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems((legacy_gather, legacy_step_inputs, legacy_step_idle_time).chain());
+    schedule.run(&mut world);
+
+    (world, schedule)
+}
+
+fn ring_buffer_world(count: usize) -> (World, Schedule) {
+    let mut history = InputHistory::default();
+
+    for when in timestamps(count) {
+        history.push(when);
+    }
+
+    let mut world = World::new();
+    world.insert_resource(history);
+    world.init_resource::<AvgPressTimeDelta>();
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(ring_buffer_frame);
+    schedule.run(&mut world);
+
+    (world, schedule)
+}
+
+fn input_history(c: &mut Criterion) {
+    let mut group = c.benchmark_group("input_history");
+
+    for count in INPUT_COUNTS {
+        group.bench_with_input(
+            BenchmarkId::new("key_press_entities", count),
+            &count,
+            |b, &count| {
+                b.iter_batched_ref(
+                    || legacy_world(count),
+                    |(world, schedule)| {
+                        schedule.run(world);
+                        black_box(world.resource::<AvgPressTimeDelta>().0)
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("ring_buffer", count),
+            &count,
+            |b, &count| {
+                b.iter_batched_ref(
+                    || ring_buffer_world(count),
+                    |(world, schedule)| {
+                        schedule.run(world);
+                        black_box(world.resource::<AvgPressTimeDelta>().0)
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, input_history);
+criterion_main!(benches);
@@ -0,0 +1,62 @@
+//! `undulate_sphere` at several icosphere subdivision levels, the game uses 5.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use idle_dungeoneer::backend::sphere::{
+    PerlinWrapper, SphereDeformation, SphereMood, UndulateSphere, Zoom, undulate_sphere,
+};
+use noise::Perlin;
+
+const SUBDIVISIONS: [u32; 5] = [2, 4, 5, 6, 8];
+const FRAME_TIME: Duration = Duration::from_nanos(16_666_667);
+
+fn world(subdivisions: u32) -> (World, Schedule, usize) {
+    let mesh = Sphere::default()
+        .mesh()
+        .ico(subdivisions)
+        .expect("too many subdivisions");
+    let vertices = mesh.count_vertices();
+    let deformation = SphereDeformation::new(&mesh).expect("icospheres have positions & indices");
+
+    let mut meshes = Assets::<Mesh>::default();
+    let handle = meshes.add(mesh);
+    let mut time = Time::<()>::default();
+    time.advance_by(FRAME_TIME);
+
+    let mut world = World::new();
+    world.insert_resource(meshes);
+    world.insert_resource(PerlinWrapper(Perlin::new(0)));
+    world.insert_resource(Zoom::default());
+    world.insert_resource(SphereMood {
+        turbulence: 0.5,
+        heat: 0.0,
+    });
+    world.insert_resource(time);
+    world.spawn((Mesh3d(handle), deformation, UndulateSphere));
+
+    let mut schedule = Schedule::default();
+    schedule.add_systems(undulate_sphere);
+    schedule.run(&mut world);
+
+    (world, schedule, vertices)
+}
+
+fn sphere(c: &mut Criterion) {
+    let mut group = c.benchmark_group("undulate_sphere");
+
+    for subdivisions in SUBDIVISIONS {
+        let (mut world, mut schedule, vertices) = world(subdivisions);
+
+        group.throughput(Throughput::Elements(vertices as u64));
+        group.bench_function(BenchmarkId::from_parameter(subdivisions), |b| {
+            b.iter(|| schedule.run(&mut world))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, sphere);
+criterion_main!(benches);
@@ -0,0 +1,621 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::{self, Display},
+    time::{Duration, SystemTime},
+};
+
+use bevy::{
+    asset::{AssetLoadFailedEvent, LoadedFolder},
+    ecs::system::SystemParam,
+    prelude::*,
+    time::common_conditions::on_timer,
+};
+use bevy_common_assets::ron::RonAssetPlugin;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    AutomationSpeed, LongestIdleTimeSeconds,
+    base_plugin::UiCommand,
+    bestiary_plugin::{Bestiary, EnemyKilled},
+    big_num::BigNum,
+    dungeon_plugin::{DungeonFloor, RoomEntered},
+    economy_plugin::{Currency, ledger::Ledger, unix_secs},
+    party_plugin::{Experience, LeveledUp, PartyMember},
+    prestige_plugin::{PrestigeState, Prestiged},
+    save_file::{SaveFile, SavePath},
+};
+
+/// where the achievement definitions are loaded from, relative to the asset folder.
+pub const ACHIEVEMENTS_FOLDER: &str = "achievements";
+/// the extension of achievement list files, each one maps achievement ids to their definitions.
+pub const ACHIEVEMENT_EXTENSION: &str = "achievements.ron";
+/// a backstop for the unlocked achievements, which are already saved the moment one unlocks & on
+/// exit.
+pub const ACHIEVEMENTS_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
+/// how often goals that track a resource (like the automation speed) are checked, goals are
+/// also checked whenever something happens in game that could complete them.
+pub const CHECK_INTERVAL: Duration = Duration::from_millis(500);
+/// the biggest bonus a single achievement can grant, as a fraction.
+pub const MAX_BONUS: f64 = 0.25;
+/// how long a toast stays up unless it's dismissed.
+pub const TOAST_DURATION: Duration = Duration::from_secs(6);
+
+/// what it takes to unlock an achievement.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Goal {
+    /// stay idle for this many seconds straight.
+    IdleTime(f64),
+    /// reach this automation speed, in Hz.
+    AutomationSpeed(f64),
+    /// clear every room of this floor of the dungeon.
+    Floor(usize),
+    /// kill this many enemies, over every run.
+    Kills(u64),
+    /// get a party member to this level.
+    Level(u32),
+    /// earn this much of the currency in a single run.
+    Earned(Currency, f64),
+    /// prestige this many times.
+    Prestiges(u64),
+}
+
+impl Display for Goal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IdleTime(secs) => write!(f, "stay idle for {secs} sec straight"),
+            Self::AutomationSpeed(speed) => write!(f, "automate at {speed} Hz"),
+            Self::Floor(depth) => write!(f, "clear floor {depth}"),
+            Self::Kills(kills) => write!(f, "kill {kills} enemies"),
+            Self::Level(level) => write!(f, "get a party member to level {level}"),
+            Self::Earned(currency, amount) => {
+                write!(f, "earn {} {currency} in one run", BigNum::from(*amount))
+            }
+            Self::Prestiges(prestiges) => write!(f, "prestige {prestiges} times"),
+        }
+    }
+}
+
+/// a small permanent bonus an achievement grants when it's unlocked, as a fraction.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AchievementBonus {
+    Income(f64),
+    Xp(f64),
+}
+
+impl AchievementBonus {
+    fn amount(&self) -> f64 {
+        match self {
+            Self::Income(amount) | Self::Xp(amount) => *amount,
+        }
+    }
+}
+
+impl Display for AchievementBonus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Income(amount) => write!(f, "+{}% income", amount * 100.0),
+            Self::Xp(amount) => write!(f, "+{}% party xp", amount * 100.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AchievementDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub goal: Goal,
+    #[serde(default)]
+    pub bonus: Option<AchievementBonus>,
+}
+
+/// something wrong with an `AchievementDefinition` that parsed fine.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AchievementDefinitionError {
+    EmptyName,
+    Goal(Goal),
+    Bonus(AchievementBonus),
+}
+
+impl Display for AchievementDefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyName => write!(f, "the name is empty"),
+            Self::Goal(goal) => write!(f, "the goal {goal:?} can never be reached"),
+            Self::Bonus(bonus) => write!(
+                f,
+                "the bonus {bonus:?} has to be more than 0 & at most {MAX_BONUS}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AchievementDefinitionError {}
+
+impl AchievementDefinition {
+    /// checks the definition makes sense, returning everything wrong with it.
+    pub fn validate(&self) -> Result<(), Vec<AchievementDefinitionError>> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            errors.push(AchievementDefinitionError::EmptyName);
+        }
+
+        let positive = |value: f64| value.is_finite() && value > 0.0;
+        let reachable = match self.goal {
+            Goal::IdleTime(value) | Goal::AutomationSpeed(value) | Goal::Earned(_, value) => {
+                positive(value)
+            }
+            Goal::Floor(depth) => depth > 0,
+            Goal::Level(level) => level > 0,
+            Goal::Kills(_) | Goal::Prestiges(_) => true,
+        };
+
+        if !reachable {
+            errors.push(AchievementDefinitionError::Goal(self.goal));
+        }
+
+        if let Some(bonus) = self.bonus
+            && !(positive(bonus.amount()) && bonus.amount() <= MAX_BONUS)
+        {
+            errors.push(AchievementDefinitionError::Bonus(bonus));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// a file of achievement definitions, by id.
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Deref, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AchievementList(pub BTreeMap<String, AchievementDefinition>);
+
+/// the valid achievement definitions, by id.
+#[derive(Resource, Debug, Clone, PartialEq, Default, Deref)]
+pub struct AchievementCatalog(pub BTreeMap<String, AchievementDefinition>);
+
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct AchievementFolder(pub Handle<LoadedFolder>);
+
+/// an unlocked achievement.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Unlock {
+    /// when the achievement was unlocked, in seconds since the unix epoch.
+    pub unlocked_at: u64,
+    /// the bonus it was unlocked with, kept here so it applies before the definitions load.
+    pub bonus: Option<AchievementBonus>,
+}
+
+/// every achievement that's been unlocked, by id. kept through every prestige.
+#[derive(Resource, Debug, Clone, PartialEq, Default, Deref, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Achievements(pub BTreeMap<String, Unlock>);
+
+impl Achievements {
+    fn bonus_multiplier(&self, bonus_of: impl Fn(AchievementBonus) -> Option<f64>) -> f64 {
+        1.0 + self
+            .values()
+            .filter_map(|unlock| unlock.bonus.and_then(&bonus_of))
+            .sum::<f64>()
+    }
+
+    pub fn income_multiplier(&self) -> f64 {
+        self.bonus_multiplier(|bonus| match bonus {
+            AchievementBonus::Income(amount) => Some(amount),
+            AchievementBonus::Xp(_) => None,
+        })
+    }
+
+    pub fn xp_multiplier(&self) -> f64 {
+        self.bonus_multiplier(|bonus| match bonus {
+            AchievementBonus::Xp(amount) => Some(amount),
+            AchievementBonus::Income(_) => None,
+        })
+    }
+}
+
+impl SaveFile for Achievements {
+    const NAME: &'static str = "the unlocked achievements";
+    const PATH: &'static str = "saves/achievements.json";
+}
+
+/// sent when an achievement is unlocked.
+#[derive(Message, Debug, Clone, PartialEq)]
+pub struct AchievementUnlocked {
+    pub id: String,
+    pub name: String,
+    pub bonus: Option<AchievementBonus>,
+}
+
+/// a notification popped up in the corner of the UI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    pub id: u64,
+    pub title: String,
+    pub body: String,
+    /// when the toast goes away, in seconds of real time since startup.
+    pub expires_at: f64,
+}
+
+/// the toasts currently up, oldest first.
+#[derive(Resource, Debug, Clone, PartialEq, Default, Deref)]
+pub struct Toasts {
+    #[deref]
+    toasts: Vec<Toast>,
+    next_id: u64,
+}
+
+impl Toasts {
+    pub fn push(&mut self, title: String, body: String, now: f64) {
+        self.toasts.push(Toast {
+            id: self.next_id,
+            title,
+            body,
+            expires_at: now + TOAST_DURATION.as_secs_f64(),
+        });
+        self.next_id += 1;
+    }
+}
+
+/// the game state achievement goals are checked against.
+#[derive(SystemParam)]
+struct Milestones<'w, 's> {
+    longest_idle_time: Res<'w, LongestIdleTimeSeconds>,
+    automation_speed: Res<'w, AutomationSpeed>,
+    floor: Res<'w, DungeonFloor>,
+    bestiary: Res<'w, Bestiary>,
+    ledger: Res<'w, Ledger>,
+    prestige: Res<'w, PrestigeState>,
+    members: Query<'w, 's, &'static Experience, With<PartyMember>>,
+}
+
+impl Milestones<'_, '_> {
+    fn reached(&self, goal: Goal) -> bool {
+        match goal {
+            Goal::IdleTime(secs) => **self.longest_idle_time >= secs,
+            Goal::AutomationSpeed(speed) => **self.automation_speed >= speed,
+            // the party only heads down once the last room of a floor is cleared.
+            Goal::Floor(depth) => self.floor.depth > depth,
+            Goal::Kills(kills) => {
+                self.bestiary
+                    .values()
+                    .map(|entry| entry.killed)
+                    .sum::<u64>()
+                    >= kills
+            }
+            Goal::Level(level) => self
+                .members
+                .iter()
+                .any(|experience| experience.level >= level),
+            Goal::Earned(currency, amount) => self
+                .ledger
+                .earned
+                .get(&currency)
+                .is_some_and(|earned| *earned >= BigNum::from(amount)),
+            Goal::Prestiges(prestiges) => self.prestige.prestiges >= prestiges,
+        }
+    }
+}
+
+pub struct AchievementPlugin;
+
+impl Plugin for AchievementPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<AchievementList>::new(&[
+            ACHIEVEMENT_EXTENSION,
+        ]));
+        app.init_resource::<AchievementCatalog>();
+        app.init_resource::<SavePath<Achievements>>();
+        app.init_resource::<Toasts>();
+        app.add_message::<AchievementUnlocked>();
+        app.add_systems(PreStartup, load_achievements);
+        app.add_systems(Startup, load_definitions);
+        app.add_systems(
+            Update,
+            (
+                report_load_failures.run_if(on_message::<AssetLoadFailedEvent<AchievementList>>),
+                update_catalog.run_if(on_message::<AssetEvent<AchievementList>>),
+                check_achievements.run_if(
+                    on_timer(CHECK_INTERVAL)
+                        .or(resource_changed::<AchievementCatalog>)
+                        .or(on_message::<RoomEntered>)
+                        .or(on_message::<EnemyKilled>)
+                        .or(on_message::<LeveledUp>)
+                        .or(on_message::<Prestiged>),
+                ),
+                show_toasts.run_if(on_message::<AchievementUnlocked>),
+                dismiss_toasts.run_if(on_message::<UiCommand>),
+                expire_toasts,
+            )
+                .chain(),
+        );
+        app.add_systems(
+            Last,
+            save_achievements.run_if(
+                on_timer(ACHIEVEMENTS_AUTOSAVE_INTERVAL)
+                    .or(on_message::<AppExit>)
+                    .or(on_message::<AchievementUnlocked>),
+            ),
+        );
+    }
+}
+
+fn load_achievements(mut cmds: Commands, save_path: Res<SavePath<Achievements>>) {
+    let achievements = Achievements::load_or_default(&save_path.path);
+
+    cmds.insert_resource(achievements);
+}
+
+fn load_definitions(mut cmds: Commands, asset_server: Res<AssetServer>) {
+    cmds.insert_resource(AchievementFolder(
+        asset_server.load_folder(ACHIEVEMENTS_FOLDER),
+    ));
+}
+
+fn report_load_failures(mut failures: MessageReader<AssetLoadFailedEvent<AchievementList>>) {
+    for failure in failures.read() {
+        error!(
+            "failed to load the achievements {}: {}",
+            failure.path, failure.error
+        );
+    }
+}
+
+/// rebuilds the catalog whenever an achievement list is loaded, (hot) reloaded or removed,
+/// reporting what's wrong with the achievements in the lists that changed.
+fn update_catalog(
+    mut events: MessageReader<AssetEvent<AchievementList>>,
+    lists: Res<Assets<AchievementList>>,
+    asset_server: Res<AssetServer>,
+    mut catalog: ResMut<AchievementCatalog>,
+) {
+    for event in events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = event else {
+            continue;
+        };
+        let (Some(list), Some(path)) = (lists.get(*id), asset_server.get_path(*id)) else {
+            continue;
+        };
+
+        for (achievement, definition) in list.iter() {
+            if let Err(errors) = definition.validate() {
+                for e in errors {
+                    error!(
+                        "the achievement \"{achievement}\" in {path} is invalid, it will be left \
+                         out: {e}"
+                    );
+                }
+            }
+        }
+    }
+
+    let mut achievements = BTreeMap::new();
+    let mut names = HashSet::new();
+
+    for (id, list) in lists.iter() {
+        for (achievement, definition) in list.iter() {
+            if definition.validate().is_err() {
+                continue;
+            }
+
+            if achievements.contains_key(achievement) {
+                let path = asset_server.get_path(id);
+                error!(
+                    "more than one achievement is defined with the id \"{achievement}\", skipping \
+                     the one in {}",
+                    path.map(|path| path.to_string()).unwrap_or_default()
+                );
+                continue;
+            }
+
+            if !names.insert(definition.name.clone()) {
+                warn!(
+                    "more than one achievement is called \"{}\", they'll be hard to tell apart",
+                    definition.name
+                );
+            }
+
+            achievements.insert(achievement.clone(), definition.clone());
+        }
+    }
+
+    if catalog.0 != achievements {
+        info!("{} achievements in the catalog", achievements.len());
+        catalog.0 = achievements;
+    }
+}
+
+/// unlocks every achievement whose goal has been reached.
+fn check_achievements(
+    catalog: Res<AchievementCatalog>,
+    milestones: Milestones,
+    mut achievements: ResMut<Achievements>,
+    mut unlocked: MessageWriter<AchievementUnlocked>,
+) {
+    for (id, definition) in catalog.iter() {
+        if achievements.contains_key(id) || !milestones.reached(definition.goal) {
+            continue;
+        }
+
+        achievements.0.insert(
+            id.clone(),
+            Unlock {
+                unlocked_at: unix_secs(SystemTime::now()),
+                bonus: definition.bonus,
+            },
+        );
+
+        info!("achievement unlocked: {}", definition.name);
+        unlocked.write(AchievementUnlocked {
+            id: id.clone(),
+            name: definition.name.clone(),
+            bonus: definition.bonus,
+        });
+    }
+}
+
+fn show_toasts(
+    mut unlocked: MessageReader<AchievementUnlocked>,
+    catalog: Res<AchievementCatalog>,
+    mut toasts: ResMut<Toasts>,
+    time: Res<Time<Real>>,
+) {
+    for achievement in unlocked.read() {
+        let mut body = catalog
+            .get(&achievement.id)
+            .map(|definition| definition.description.clone())
+            .unwrap_or_default();
+
+        if let Some(bonus) = achievement.bonus {
+            if !body.is_empty() {
+                body.push_str(", ");
+            }
+
+            body.push_str(&bonus.to_string());
+        }
+
+        toasts.push(
+            format!("achievement unlocked: {}", achievement.name),
+            body,
+            time.elapsed_secs_f64(),
+        );
+    }
+}
+
+fn dismiss_toasts(mut commands: MessageReader<UiCommand>, mut toasts: ResMut<Toasts>) {
+    for command in commands.read() {
+        if let UiCommand::DismissToast(id) = *command
+            && toasts.iter().any(|toast| toast.id == id)
+        {
+            toasts.toasts.retain(|toast| toast.id != id);
+        }
+    }
+}
+
+fn expire_toasts(mut toasts: ResMut<Toasts>, time: Res<Time<Real>>) {
+    let now = time.elapsed_secs_f64();
+
+    // only touched when one expires, so the UI isn't told the toasts changed every frame.
+    if toasts.iter().any(|toast| toast.expires_at <= now) {
+        toasts.toasts.retain(|toast| toast.expires_at > now);
+    }
+}
+
+fn save_achievements(achievements: Res<Achievements>, save_path: Res<SavePath<Achievements>>) {
+    achievements.save_or_log(&save_path.path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::shipped_assets;
+
+    fn delver() -> AchievementDefinition {
+        AchievementDefinition {
+            name: "delver".to_string(),
+            description: String::new(),
+            goal: Goal::Floor(10),
+            bonus: Some(AchievementBonus::Xp(0.05)),
+        }
+    }
+
+    fn unlock(bonus: AchievementBonus) -> Unlock {
+        Unlock {
+            unlocked_at: 0,
+            bonus: Some(bonus),
+        }
+    }
+
+    #[test]
+    fn valid_achievement_passes() {
+        assert_eq!(delver().validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_reports_everything_wrong() {
+        let achievement = AchievementDefinition {
+            name: " ".to_string(),
+            goal: Goal::Floor(0),
+            bonus: Some(AchievementBonus::Income(MAX_BONUS * 2.0)),
+            ..delver()
+        };
+
+        assert_eq!(
+            achievement.validate(),
+            Err(vec![
+                AchievementDefinitionError::EmptyName,
+                AchievementDefinitionError::Goal(Goal::Floor(0)),
+                AchievementDefinitionError::Bonus(AchievementBonus::Income(MAX_BONUS * 2.0)),
+            ])
+        );
+    }
+
+    #[test]
+    fn goals_have_to_be_reachable() {
+        for goal in [
+            Goal::IdleTime(f64::NAN),
+            Goal::AutomationSpeed(0.0),
+            Goal::Earned(Currency::Gold, -1.0),
+            Goal::Level(0),
+        ] {
+            let achievement = AchievementDefinition {
+                goal,
+                bonus: None,
+                ..delver()
+            };
+
+            assert!(achievement.validate().is_err(), "{goal:?}");
+        }
+
+        let achievement = AchievementDefinition {
+            goal: Goal::Kills(0),
+            bonus: Some(AchievementBonus::Xp(0.0)),
+            ..delver()
+        };
+
+        assert_eq!(
+            achievement.validate(),
+            Err(vec![AchievementDefinitionError::Bonus(
+                AchievementBonus::Xp(0.0)
+            )])
+        );
+    }
+
+    #[test]
+    fn bonuses_add_up_by_kind() {
+        let achievements = Achievements(BTreeMap::from([
+            ("a".to_string(), unlock(AchievementBonus::Income(0.1))),
+            ("b".to_string(), unlock(AchievementBonus::Income(0.05))),
+            ("c".to_string(), unlock(AchievementBonus::Xp(0.2))),
+            (
+                "d".to_string(),
+                Unlock {
+                    unlocked_at: 0,
+                    bonus: None,
+                },
+            ),
+        ]));
+
+        assert!((achievements.income_multiplier() - 1.15).abs() < 1e-9);
+        assert!((achievements.xp_multiplier() - 1.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn shipped_definitions_are_valid() {
+        for (path, source) in shipped_assets(ACHIEVEMENTS_FOLDER, ACHIEVEMENT_EXTENSION) {
+            let list: AchievementList =
+                ron::from_str(&source).unwrap_or_else(|e| panic!("{path}: {e}"));
+
+            for (id, achievement) in list.iter() {
+                assert_eq!(achievement.validate(), Ok(()), "{path}: {id}");
+            }
+        }
+    }
+}
@@ -0,0 +1,670 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    ecs::system::SystemParam,
+    input::{
+        ButtonState, InputSystems,
+        gamepad::{GamepadAxisChangedEvent, GamepadButtonStateChangedEvent},
+        keyboard::KeyboardInput,
+        mouse::MouseButtonInput,
+    },
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{base_plugin::UiCommand, save_file::SaveFile};
+
+/// how far a stick has to be pushed before it counts as pressed, so resting sticks that drift
+/// a little don't register as activity.
+pub const STICK_DEADZONE: f32 = 0.25;
+
+/// something the player can do, independent of what it's bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    AdvanceTurn,
+    SwapSkills,
+    ToggleAutomation,
+    Pause,
+    /// held to orbit the camera.
+    OrbitCamera,
+    ResetCamera,
+    /// shows or hides the performance diagnostics overlay.
+    ToggleDiagnostics,
+}
+
+impl Action {
+    pub const ALL: [Self; 7] = [
+        Self::AdvanceTurn,
+        Self::SwapSkills,
+        Self::ToggleAutomation,
+        Self::Pause,
+        Self::OrbitCamera,
+        Self::ResetCamera,
+        Self::ToggleDiagnostics,
+    ];
+
+    pub fn default_binding(&self) -> ActionBinding {
+        let (inputs, counts_as_activity) = match self {
+            Self::AdvanceTurn => (
+                vec![
+                    Input::Key(KeyCode::Space),
+                    Input::GamepadButton(GamepadButton::South),
+                ],
+                true,
+            ),
+            Self::SwapSkills => (
+                vec![
+                    Input::Key(KeyCode::Tab),
+                    Input::GamepadButton(GamepadButton::West),
+                ],
+                true,
+            ),
+            Self::ToggleAutomation => (
+                vec![
+                    Input::Key(KeyCode::KeyA),
+                    Input::GamepadButton(GamepadButton::North),
+                ],
+                false,
+            ),
+            Self::Pause => (
+                vec![
+                    Input::Key(KeyCode::KeyP),
+                    Input::GamepadButton(GamepadButton::Start),
+                ],
+                false,
+            ),
+            Self::OrbitCamera => (vec![Input::Mouse(MouseButton::Right)], false),
+            Self::ResetCamera => (
+                vec![
+                    Input::Key(KeyCode::Home),
+                    Input::GamepadButton(GamepadButton::RightThumb),
+                ],
+                false,
+            ),
+            Self::ToggleDiagnostics => (vec![Input::Key(KeyCode::F3)], false),
+        };
+
+        ActionBinding {
+            inputs,
+            counts_as_activity,
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::AdvanceTurn => "advance turn",
+            Self::SwapSkills => "swap skills",
+            Self::ToggleAutomation => "toggle automation",
+            Self::Pause => "pause",
+            Self::OrbitCamera => "orbit camera",
+            Self::ResetCamera => "reset camera",
+            Self::ToggleDiagnostics => "toggle diagnostics",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// a physical input an action can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Input {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    GamepadButton(GamepadButton),
+    /// a stick (or other axis) pushed past the deadzone, in the positive or negative direction.
+    Stick {
+        axis: GamepadAxis,
+        positive: bool,
+    },
+}
+
+impl Input {
+    pub fn is_gamepad(&self) -> bool {
+        matches!(self, Self::GamepadButton(_) | Self::Stick { .. })
+    }
+}
+
+impl Display for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Key(key) => write!(f, "{key:?}"),
+            Self::Mouse(button) => write!(f, "mouse {button:?}"),
+            Self::GamepadButton(button) => write!(f, "gamepad {button:?}"),
+            Self::Stick { axis, positive } => {
+                write!(f, "gamepad {axis:?}{}", if *positive { "+" } else { "-" })
+            }
+        }
+    }
+}
+
+/// the state of every input device, to check bindings against.
+#[derive(SystemParam)]
+struct InputDevices<'w, 's> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+    settings: Res<'w, ActionSettings>,
+}
+
+impl InputDevices<'_, '_> {
+    fn pressed(&self, input: Input) -> bool {
+        match input {
+            Input::Key(key) => self.keys.pressed(key),
+            Input::Mouse(button) => self.mouse.pressed(button),
+            Input::GamepadButton(button) => {
+                self.gamepads.iter().any(|gamepad| gamepad.pressed(button))
+            }
+            Input::Stick { axis, positive } => self.gamepads.iter().any(|gamepad| {
+                gamepad.get(axis).is_some_and(|value| {
+                    value.abs() > self.settings.stick_deadzone && (value > 0.0) == positive
+                })
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ActionBinding {
+    pub inputs: Vec<Input>,
+    /// whether using the action counts as activity towards idle time.
+    pub counts_as_activity: bool,
+}
+
+/// the bindings of every action. inputs that aren't bound to an action still count as activity,
+/// so the game can be played by mashing any key.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Deref, DerefMut)]
+#[serde(transparent)]
+pub struct ActionMap(pub BTreeMap<Action, ActionBinding>);
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        Self(
+            Action::ALL
+                .into_iter()
+                .map(|action| (action, action.default_binding()))
+                .collect(),
+        )
+    }
+}
+
+impl ActionMap {
+    /// the actions bound to `input`.
+    pub fn actions(&self, input: Input) -> impl Iterator<Item = Action> + '_ {
+        self.iter()
+            .filter(move |(_, binding)| binding.inputs.contains(&input))
+            .map(|(action, _)| *action)
+    }
+
+    /// inputs bound to more than one action, with the actions they're bound to.
+    pub fn conflicts(&self) -> Vec<(Input, Vec<Action>)> {
+        let mut inputs: Vec<(Input, Vec<Action>)> = Vec::new();
+
+        for (action, binding) in self.iter() {
+            for input in &binding.inputs {
+                match inputs.iter_mut().find(|(bound, _)| bound == input) {
+                    Some((_, actions)) => actions.push(*action),
+                    None => inputs.push((*input, vec![*action])),
+                }
+            }
+        }
+
+        inputs
+            .into_iter()
+            .filter(|(_, actions)| actions.len() > 1)
+            .collect()
+    }
+
+    /// whether `input` counts as activity towards idle time. it counts if it's unbound, or if any
+    /// of the actions it's bound to count.
+    pub fn counts_as_activity(&self, input: Input) -> bool {
+        let mut actions = self.actions(input).peekable();
+
+        actions.peek().is_none() || actions.any(|action| self[&action].counts_as_activity)
+    }
+}
+
+impl SaveFile for ActionMap {
+    const NAME: &'static str = "the key bindings";
+    // kept with the config rather than the saves, & as TOML, so it's easy to edit by hand.
+    const PATH: &'static str = "config/keybindings.toml";
+
+    /// loads the bindings from a TOML file, any action missing from the file gets its default
+    /// binding.
+    fn load(path: &Path) -> io::Result<Self> {
+        let mut map: Self = toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        for action in Action::ALL {
+            map.entry(action)
+                .or_insert_with(|| action.default_binding());
+        }
+
+        Ok(map)
+    }
+
+    fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let toml = toml::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        fs::write(path, toml)
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct ActionSettings {
+    pub path: PathBuf,
+    pub stick_deadzone: f32,
+}
+
+impl Default for ActionSettings {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from(ActionMap::PATH),
+            stick_deadzone: STICK_DEADZONE,
+        }
+    }
+}
+
+/// the action waiting for the next input to be bound to it, set from the settings UI.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deref, DerefMut)]
+pub struct Rebinding(pub Option<Action>);
+
+/// sent when a stick is pushed past the deadzone, making it act like a button press.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StickDeflected(pub Input);
+
+/// sent for every input that counts towards idle time.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Activity(pub Input);
+
+pub struct ActionPlugin;
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionSettings>();
+        app.init_resource::<Rebinding>();
+        app.init_resource::<ButtonInput<Action>>();
+        app.add_message::<StickDeflected>();
+        app.add_message::<Activity>();
+        app.add_systems(PreStartup, load_action_map);
+        app.add_systems(
+            PreUpdate,
+            (update_actions, detect_stick_deflections).after(InputSystems),
+        );
+        app.add_systems(
+            Update,
+            (
+                edit_action_map.run_if(on_message::<UiCommand>),
+                (
+                    rebind.run_if(|rebinding: Res<Rebinding>| rebinding.is_some()),
+                    gather_activity.run_if(|rebinding: Res<Rebinding>| rebinding.is_none()),
+                ),
+            )
+                .chain(),
+        );
+        app.add_systems(
+            PostUpdate,
+            (save_action_map, report_conflicts).run_if(resource_changed::<ActionMap>),
+        );
+    }
+}
+
+fn load_action_map(mut cmds: Commands, settings: Res<ActionSettings>) {
+    let map = ActionMap::load_or_default(&settings.path);
+
+    cmds.insert_resource(map);
+}
+
+fn update_actions(
+    devices: InputDevices,
+    map: Res<ActionMap>,
+    mut actions: ResMut<ButtonInput<Action>>,
+) {
+    actions.clear();
+
+    for (action, binding) in map.iter() {
+        let pressed = binding.inputs.iter().any(|input| devices.pressed(*input));
+
+        if pressed && !actions.pressed(*action) {
+            actions.press(*action);
+        } else if !pressed && actions.pressed(*action) {
+            actions.release(*action);
+        }
+    }
+}
+
+fn edit_action_map(
+    mut commands: MessageReader<UiCommand>,
+    mut map: ResMut<ActionMap>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    for command in commands.read() {
+        match command {
+            UiCommand::Rebind(action) => **rebinding = *action,
+            UiCommand::SetCountsAsActivity(action, counts) => {
+                if let Some(binding) = map.get_mut(action) {
+                    binding.counts_as_activity = *counts;
+                }
+            }
+            UiCommand::ResetBindings => *map = ActionMap::default(),
+            _ => {}
+        }
+    }
+}
+
+/// sends a `StickDeflected` each time an axis leaves the deadzone, or flips direction without
+/// passing back through it.
+fn detect_stick_deflections(
+    mut axis_inputs: MessageReader<GamepadAxisChangedEvent>,
+    settings: Res<ActionSettings>,
+    mut deflected: Local<HashMap<(Entity, GamepadAxis), bool>>,
+    mut deflections: MessageWriter<StickDeflected>,
+) {
+    for input in axis_inputs.read() {
+        let key = (input.entity, input.axis);
+
+        if input.value.abs() <= settings.stick_deadzone {
+            deflected.remove(&key);
+            continue;
+        }
+
+        let positive = input.value > 0.0;
+
+        if deflected.insert(key, positive) != Some(positive) {
+            deflections.write(StickDeflected(Input::Stick {
+                axis: input.axis,
+                positive,
+            }));
+        }
+    }
+}
+
+/// binds the next key, button or stick pressed to the action being rebound, in place of its
+/// binding on the same kind of device. the left mouse button is left out since it's how the UI is
+/// clicked.
+fn rebind(
+    mut keyboard_inputs: MessageReader<KeyboardInput>,
+    mut mouse_inputs: MessageReader<MouseButtonInput>,
+    mut gamepad_inputs: MessageReader<GamepadButtonStateChangedEvent>,
+    mut stick_inputs: MessageReader<StickDeflected>,
+    mut map: ResMut<ActionMap>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    let keys = keyboard_inputs
+        .read()
+        .filter(|input| input.state == ButtonState::Pressed)
+        .map(|input| Input::Key(input.key_code));
+    let buttons = mouse_inputs
+        .read()
+        .filter(|input| input.state == ButtonState::Pressed && input.button != MouseButton::Left)
+        .map(|input| Input::Mouse(input.button));
+    let gamepad_buttons = gamepad_inputs
+        .read()
+        .filter(|input| input.state == ButtonState::Pressed)
+        .map(|input| Input::GamepadButton(input.button));
+    let sticks = stick_inputs.read().map(|input| input.0);
+
+    if let Some(input) = keys
+        .chain(buttons)
+        .chain(gamepad_buttons)
+        .chain(sticks)
+        .next()
+        && let Some(action) = rebinding.take()
+        && let Some(binding) = map.get_mut(&action)
+    {
+        // keyboard & mouse bindings replace each other, so do gamepad bindings.
+        binding
+            .inputs
+            .retain(|bound| bound.is_gamepad() != input.is_gamepad());
+        binding.inputs.push(input);
+    }
+}
+
+fn gather_activity(
+    mut keyboard_inputs: MessageReader<KeyboardInput>,
+    mut mouse_inputs: MessageReader<MouseButtonInput>,
+    mut gamepad_inputs: MessageReader<GamepadButtonStateChangedEvent>,
+    mut stick_inputs: MessageReader<StickDeflected>,
+    map: Res<ActionMap>,
+    mut activity: MessageWriter<Activity>,
+) {
+    let keys = keyboard_inputs
+        .read()
+        .map(|input| Input::Key(input.key_code));
+    let buttons = mouse_inputs.read().map(|input| Input::Mouse(input.button));
+    let gamepad_buttons = gamepad_inputs
+        .read()
+        .map(|input| Input::GamepadButton(input.button));
+    let sticks = stick_inputs.read().map(|input| input.0);
+
+    activity.write_batch(
+        keys.chain(buttons)
+            .chain(gamepad_buttons)
+            .chain(sticks)
+            .filter(|input| map.counts_as_activity(*input))
+            .map(Activity),
+    );
+}
+
+fn save_action_map(map: Res<ActionMap>, settings: Res<ActionSettings>) {
+    // nothing was edited yet, don't write the defaults out on every launch.
+    if map.is_added() {
+        return;
+    }
+
+    map.save_or_log(&settings.path);
+}
+
+fn report_conflicts(map: Res<ActionMap>) {
+    for (input, actions) in map.conflicts() {
+        warn!("{input} is bound to more than one action: {actions:?}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::keyboard::Key;
+
+    use super::*;
+    use crate::backend::temp_path;
+
+    #[test]
+    fn the_defaults_dont_conflict() {
+        assert!(ActionMap::default().conflicts().is_empty());
+    }
+
+    #[test]
+    fn conflicts_list_every_action_sharing_an_input() {
+        let mut map = ActionMap::default();
+        let space = Input::Key(KeyCode::Space);
+
+        map.get_mut(&Action::Pause).unwrap().inputs.push(space);
+        map.get_mut(&Action::ResetCamera)
+            .unwrap()
+            .inputs
+            .push(space);
+
+        assert_eq!(
+            map.conflicts(),
+            vec![(
+                space,
+                vec![Action::AdvanceTurn, Action::Pause, Action::ResetCamera]
+            )]
+        );
+    }
+
+    #[test]
+    fn unbound_inputs_count_as_activity() {
+        let map = ActionMap::default();
+
+        assert!(map.counts_as_activity(Input::Key(KeyCode::KeyZ)));
+        assert!(map.counts_as_activity(Input::Key(KeyCode::Space)));
+        assert!(!map.counts_as_activity(Input::Key(KeyCode::KeyP)));
+    }
+
+    #[test]
+    fn inputs_count_if_any_of_their_actions_do() {
+        let mut map = ActionMap::default();
+        let pause = Input::Key(KeyCode::KeyP);
+
+        map.get_mut(&Action::AdvanceTurn)
+            .unwrap()
+            .inputs
+            .push(pause);
+
+        assert!(map.counts_as_activity(pause));
+
+        map.get_mut(&Action::AdvanceTurn)
+            .unwrap()
+            .counts_as_activity = false;
+
+        assert!(!map.counts_as_activity(pause));
+    }
+
+    #[test]
+    fn load_fills_in_missing_actions() {
+        let path = temp_path("keybindings/partial.toml");
+        let pause = ActionBinding {
+            inputs: vec![Input::Key(KeyCode::Escape)],
+            counts_as_activity: true,
+        };
+
+        ActionMap(BTreeMap::from([(Action::Pause, pause.clone())]))
+            .save(&path)
+            .unwrap();
+
+        let map = ActionMap::load(&path).unwrap();
+
+        assert_eq!(map.len(), Action::ALL.len());
+        assert_eq!(map[&Action::Pause], pause);
+
+        for action in Action::ALL
+            .into_iter()
+            .filter(|action| *action != Action::Pause)
+        {
+            assert_eq!(map[&action], action.default_binding(), "{action}");
+        }
+
+        fs::remove_file(path).unwrap();
+    }
+
+    fn rebind_app() -> App {
+        let mut app = App::new();
+
+        app.add_message::<KeyboardInput>();
+        app.add_message::<MouseButtonInput>();
+        app.add_message::<GamepadButtonStateChangedEvent>();
+        app.add_message::<StickDeflected>();
+        app.init_resource::<ActionMap>();
+        app.insert_resource(Rebinding(Some(Action::AdvanceTurn)));
+        app.add_systems(Update, rebind);
+
+        app
+    }
+
+    #[test]
+    fn rebind_replaces_the_binding_on_the_same_kind_of_device() {
+        let mut app = rebind_app();
+
+        app.world_mut().write_message(KeyboardInput {
+            key_code: KeyCode::Enter,
+            logical_key: Key::Enter,
+            state: ButtonState::Pressed,
+            text: None,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+        app.update();
+
+        assert_eq!(
+            app.world().resource::<ActionMap>()[&Action::AdvanceTurn].inputs,
+            vec![
+                Input::GamepadButton(GamepadButton::South),
+                Input::Key(KeyCode::Enter)
+            ]
+        );
+        assert_eq!(**app.world().resource::<Rebinding>(), None);
+
+        let stick = Input::Stick {
+            axis: GamepadAxis::LeftStickY,
+            positive: false,
+        };
+
+        **app.world_mut().resource_mut::<Rebinding>() = Some(Action::AdvanceTurn);
+        app.world_mut().write_message(StickDeflected(stick));
+        app.update();
+
+        assert_eq!(
+            app.world().resource::<ActionMap>()[&Action::AdvanceTurn].inputs,
+            vec![Input::Key(KeyCode::Enter), stick]
+        );
+    }
+
+    #[test]
+    fn rebind_ignores_the_left_mouse_button() {
+        let mut app = rebind_app();
+
+        app.world_mut().write_message(MouseButtonInput {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+            window: Entity::PLACEHOLDER,
+        });
+        app.update();
+
+        assert_eq!(
+            app.world().resource::<ActionMap>()[&Action::AdvanceTurn],
+            Action::AdvanceTurn.default_binding()
+        );
+        assert_eq!(
+            **app.world().resource::<Rebinding>(),
+            Some(Action::AdvanceTurn)
+        );
+    }
+
+    #[test]
+    fn sticks_only_deflect_past_the_deadzone() {
+        let mut app = App::new();
+
+        app.add_message::<GamepadAxisChangedEvent>();
+        app.add_message::<StickDeflected>();
+        app.init_resource::<ActionSettings>();
+        app.add_systems(Update, detect_stick_deflections);
+
+        let gamepad = app.world_mut().spawn_empty().id();
+        let axis = GamepadAxis::LeftStickX;
+        let mut deflections = Vec::new();
+
+        for value in [0.1, -STICK_DEADZONE, 0.5, 0.9, -0.5, 0.2, 0.6] {
+            app.world_mut()
+                .write_message(GamepadAxisChangedEvent::new(gamepad, axis, value));
+            app.update();
+
+            let messages = app.world().resource::<Messages<StickDeflected>>();
+
+            deflections.extend(messages.iter_current_update_messages().map(|deflected| {
+                let Input::Stick { positive, .. } = deflected.0 else {
+                    unreachable!()
+                };
+
+                positive
+            }));
+        }
+
+        // in & out of the deadzone don't count, holding a stick over only counts once, & flipping
+        // it over or letting it go & pushing it again count again.
+        assert_eq!(deflections, vec![true, false, true]);
+    }
+}
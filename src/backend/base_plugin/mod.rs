@@ -1,6 +1,16 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use bevy::prelude::*;
+use crossbeam::channel::Receiver;
+
+use crate::backend::{
+    action_plugin::Action,
+    economy_plugin::{Generator, Upgrade},
+    export_plugin::ExportFormat,
+    inventory_plugin::EquipSlot,
+    prestige_plugin::MetaUpgrade,
+    theme::Palette,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, States)]
 pub enum MainGameStates {
@@ -18,17 +28,153 @@ pub enum AutomationStates {
     Automation,
 }
 
+/// freezes idle time & combat while `Paused`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, States)]
+pub enum PauseStates {
+    #[default]
+    Running,
+    Paused,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Resource)]
 pub struct StartTime(pub Instant);
 
-pub struct BasePlugin;
+/// a copy of the game's states for the UI, which can only read resources.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GameStatus {
+    pub paused: bool,
+    pub automating: bool,
+}
+
+/// commands sent from the Dioxus UI to the Bevy backend. they arrive over a channel & are
+/// re-sent as messages so any system can react to them with a `MessageReader`.
+///
+/// this is the one place the UI talks to the backend through, so it names types from the
+/// plugins that handle its variants. a plugin that wants a command from the UI adds a variant
+/// here & reads it itself, base_plugin only forwards them & never handles another plugin's.
+#[derive(Debug, Clone, PartialEq, Message)]
+pub enum UiCommand {
+    Export(ExportFormat),
+    /// the format to export the session in when the app exits, `None` to not export.
+    SetExportOnExit(Option<ExportFormat>),
+    SetPalette(Palette),
+    ToggleDungeonView,
+    /// binds the next input pressed to the action, `None` cancels rebinding.
+    Rebind(Option<Action>),
+    SetCountsAsActivity(Action, bool),
+    ResetBindings,
+    TogglePause,
+    ToggleAutomation,
+    SetAutomateOnFocusLoss(bool),
+    ToggleDiagnostics,
+    /// how long the UI took to build its element tree, for the diagnostics overlay.
+    ReportBuildTime(Duration),
+    /// equips the item in the inventory stack at `stack` on `member`. `item` is the id of the
+    /// item the UI showed there, the command is dropped if the stack has moved since.
+    Equip {
+        stack: usize,
+        item: String,
+        member: Entity,
+    },
+    /// uses up one of the item in the inventory stack at `stack`, if it still holds `item`.
+    UseItem {
+        stack: usize,
+        item: String,
+    },
+    /// puts what `member` has in `slot` back in the inventory.
+    Unequip {
+        member: Entity,
+        slot: EquipSlot,
+    },
+    /// adds the `skill`th skill `member` knows to the end of their battle order, if it's still
+    /// the one called `name`.
+    SlotSkill {
+        member: Entity,
+        skill: usize,
+        name: String,
+    },
+    /// takes the skill at `slot` out of `member`'s battle order.
+    UnslotSkill {
+        member: Entity,
+        slot: usize,
+    },
+    /// moves a skill in `member`'s battle order from one slot to another.
+    MoveSkill {
+        member: Entity,
+        from: usize,
+        to: usize,
+    },
+    BuyGenerator(Generator),
+    BuyUpgrade(Upgrade),
+    TogglePrestigeScreen,
+    /// trades the run for renown, sent from the confirmation screen.
+    Prestige,
+    BuyMetaUpgrade(MetaUpgrade),
+    /// takes down the toast with the id before it expires.
+    DismissToast(u64),
+}
+
+#[derive(Resource, Debug, Clone, Deref, DerefMut)]
+pub struct UiCommandRx(pub Receiver<UiCommand>);
+
+pub struct BasePlugin {
+    pub ui_rx: Receiver<UiCommand>,
+}
 
 impl Plugin for BasePlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<MainGameStates>();
         app.init_state::<AutomationStates>();
+        app.init_state::<PauseStates>();
+        app.init_resource::<GameStatus>();
+        app.add_message::<UiCommand>();
+        app.insert_resource(UiCommandRx(self.ui_rx.clone()));
         app.add_systems(Startup, |mut cmds: Commands| {
             cmds.insert_resource(StartTime(Instant::now()))
         });
+        app.add_systems(PreUpdate, forward_ui_commands);
+        app.add_systems(
+            Update,
+            (
+                toggle_pause,
+                update_game_status
+                    .run_if(state_changed::<PauseStates>.or(state_changed::<AutomationStates>)),
+            ),
+        );
+    }
+}
+
+fn toggle_pause(
+    mut commands: MessageReader<UiCommand>,
+    actions: Res<ButtonInput<Action>>,
+    pause_state: Res<State<PauseStates>>,
+    mut next_pause_state: ResMut<NextState<PauseStates>>,
+) {
+    let toggles = commands
+        .read()
+        .filter(|command| **command == UiCommand::TogglePause)
+        .count()
+        + usize::from(actions.just_pressed(Action::Pause));
+
+    if toggles % 2 == 1 {
+        next_pause_state.set(match pause_state.get() {
+            PauseStates::Running => PauseStates::Paused,
+            PauseStates::Paused => PauseStates::Running,
+        });
     }
 }
+
+fn update_game_status(
+    pause_state: Res<State<PauseStates>>,
+    automation_state: Res<State<AutomationStates>>,
+    mut status: ResMut<GameStatus>,
+) {
+    *status = GameStatus {
+        paused: *pause_state.get() == PauseStates::Paused,
+        automating: *automation_state.get() == AutomationStates::Automation,
+    };
+}
+
+fn forward_ui_commands(ui_rx: Res<UiCommandRx>, mut commands: MessageWriter<UiCommand>) {
+    commands.write_batch(ui_rx.try_iter());
+}
@@ -0,0 +1,512 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::{self, Display},
+    path::Path,
+    time::Duration,
+};
+
+use bevy::{
+    asset::{AssetLoadFailedEvent, LoadedFolder},
+    prelude::*,
+    time::common_conditions::on_timer,
+};
+use bevy_common_assets::{ron::RonAssetPlugin, toml::TomlAssetPlugin};
+use rand::seq::IndexedRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    dungeon_plugin::{DungeonRng, RoomCleared, RoomEntered},
+    inventory_plugin::loot::LootTable,
+    save_file::{SaveFile, SavePath},
+};
+
+/// where the enemy definitions are loaded from, relative to the asset folder.
+pub const ENEMIES_FOLDER: &str = "enemies";
+/// the extensions of enemy definition files, the rest of the file name is the enemy's id.
+pub const ENEMY_EXTENSIONS: [&str; 2] = ["enemy.ron", "enemy.toml"];
+/// how often the bestiary is saved while exploring, besides on exit. encounters pile up slowly so
+/// a crash doesn't lose much.
+pub const BESTIARY_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DamageType {
+    Physical,
+    Fire,
+    Ice,
+    Lightning,
+    Poison,
+    Arcane,
+}
+
+impl Display for DamageType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Physical => "physical",
+            Self::Fire => "fire",
+            Self::Ice => "ice",
+            Self::Lightning => "lightning",
+            Self::Poison => "poison",
+            Self::Arcane => "arcane",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnemyStats {
+    pub hp: f64,
+    pub attack: f64,
+    pub defense: f64,
+    pub speed: f64,
+}
+
+/// how the enemy should look, for whoever draws it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArtHint {
+    /// the enemy's main color, as hex.
+    pub color: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// a status effect an enemy might inflict on a party member when an encounter starts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Inflict {
+    /// the id of the status effect.
+    pub effect: String,
+    /// the chance of it being inflicted each encounter, between 0 & 1.
+    pub chance: f64,
+}
+
+/// a kind of enemy, loaded from `ENEMIES_FOLDER`.
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnemyDefinition {
+    pub name: String,
+    /// the first floor the enemy can show up on.
+    #[serde(default = "first_floor")]
+    pub min_depth: usize,
+    pub stats: EnemyStats,
+    /// the fraction of each damage type the enemy shrugs off, negative for a weakness.
+    #[serde(default)]
+    pub resistances: BTreeMap<DamageType, f64>,
+    #[serde(default)]
+    pub skills: Vec<String>,
+    #[serde(default)]
+    pub loot_table: LootTable,
+    #[serde(default)]
+    pub inflicts: Vec<Inflict>,
+    pub art_hint: ArtHint,
+}
+
+fn first_floor() -> usize {
+    1
+}
+
+/// something wrong with an `EnemyDefinition` that parsed fine.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnemyDefinitionError {
+    EmptyName,
+    MinDepth,
+    Stat { stat: &'static str, value: f64 },
+    Resistance { damage_type: DamageType, value: f64 },
+    EmptySkill,
+    DuplicateSkill(String),
+    EmptyLootItem,
+    LootWeight(String),
+    LootCount(String),
+    EmptyInflictEffect,
+    InflictChance { effect: String, chance: f64 },
+    Color(String),
+}
+
+impl Display for EnemyDefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyName => write!(f, "the name is empty"),
+            Self::MinDepth => write!(f, "min_depth must be at least 1"),
+            Self::Stat { stat, value } => write!(f, "{stat} can't be {value}"),
+            Self::Resistance { damage_type, value } => write!(
+                f,
+                "the {damage_type} resistance is {value}, it has to be between -1.0 & 1.0"
+            ),
+            Self::EmptySkill => write!(f, "a skill name is empty"),
+            Self::DuplicateSkill(skill) => write!(f, "the skill \"{skill}\" is listed twice"),
+            Self::EmptyLootItem => write!(f, "a loot table item name is empty"),
+            Self::LootWeight(item) => write!(f, "the loot table weight of \"{item}\" is 0"),
+            Self::LootCount(item) => write!(f, "the loot table count of \"{item}\" is 0"),
+            Self::EmptyInflictEffect => write!(f, "an inflicted status effect is empty"),
+            Self::InflictChance { effect, chance } => write!(
+                f,
+                "the chance of inflicting \"{effect}\" is {chance}, it has to be between 0.0 & 1.0"
+            ),
+            Self::Color(color) => write!(f, "the art hint color \"{color}\" isn't a hex color"),
+        }
+    }
+}
+
+impl std::error::Error for EnemyDefinitionError {}
+
+impl EnemyDefinition {
+    /// checks the definition makes sense, returning everything wrong with it.
+    pub fn validate(&self) -> Result<(), Vec<EnemyDefinitionError>> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            errors.push(EnemyDefinitionError::EmptyName);
+        }
+
+        if self.min_depth == 0 {
+            errors.push(EnemyDefinitionError::MinDepth);
+        }
+
+        let stats = [
+            ("hp", self.stats.hp, false),
+            ("attack", self.stats.attack, true),
+            ("defense", self.stats.defense, true),
+            ("speed", self.stats.speed, false),
+        ];
+
+        for (stat, value, can_be_zero) in stats {
+            if !value.is_finite() || value < 0.0 || (value == 0.0 && !can_be_zero) {
+                errors.push(EnemyDefinitionError::Stat { stat, value });
+            }
+        }
+
+        for (damage_type, value) in &self.resistances {
+            if !(-1.0..=1.0).contains(value) {
+                errors.push(EnemyDefinitionError::Resistance {
+                    damage_type: *damage_type,
+                    value: *value,
+                });
+            }
+        }
+
+        let mut skills = HashSet::new();
+
+        for skill in &self.skills {
+            if skill.trim().is_empty() {
+                errors.push(EnemyDefinitionError::EmptySkill);
+            } else if !skills.insert(skill) {
+                errors.push(EnemyDefinitionError::DuplicateSkill(skill.clone()));
+            }
+        }
+
+        for drop in self.loot_table.iter() {
+            if drop.item.trim().is_empty() {
+                errors.push(EnemyDefinitionError::EmptyLootItem);
+            } else if drop.weight == 0 {
+                errors.push(EnemyDefinitionError::LootWeight(drop.item.clone()));
+            } else if drop.count == 0 {
+                errors.push(EnemyDefinitionError::LootCount(drop.item.clone()));
+            }
+        }
+
+        for inflict in &self.inflicts {
+            if inflict.effect.trim().is_empty() {
+                errors.push(EnemyDefinitionError::EmptyInflictEffect);
+            } else if !(0.0..=1.0).contains(&inflict.chance) {
+                errors.push(EnemyDefinitionError::InflictChance {
+                    effect: inflict.effect.clone(),
+                    chance: inflict.chance,
+                });
+            }
+        }
+
+        if Srgba::hex(&self.art_hint.color).is_err() {
+            errors.push(EnemyDefinitionError::Color(self.art_hint.color.clone()));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// the valid enemy definitions, by id.
+#[derive(Resource, Debug, Clone, PartialEq, Default, Deref)]
+pub struct EnemyCatalog(pub BTreeMap<String, EnemyDefinition>);
+
+impl EnemyCatalog {
+    /// the ids of the enemies that can show up on the floor at `depth`.
+    pub fn available_at(&self, depth: usize) -> Vec<&String> {
+        self.iter()
+            .filter(|(_, enemy)| enemy.min_depth <= depth)
+            .map(|(id, _)| id)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BestiaryEntry {
+    pub encountered: u64,
+    pub killed: u64,
+}
+
+/// every enemy the player has come across, by id.
+#[derive(
+    Resource, Debug, Clone, PartialEq, Eq, Default, Deref, DerefMut, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Bestiary(pub BTreeMap<String, BestiaryEntry>);
+
+impl SaveFile for Bestiary {
+    const NAME: &'static str = "the bestiary";
+    const PATH: &'static str = "saves/bestiary.json";
+}
+
+/// the ids of the enemies in the room the party is clearing.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Default, Deref, DerefMut)]
+pub struct Encounter(pub Vec<String>);
+
+/// sent for each enemy the party kills, with its id.
+#[derive(Message, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnemyKilled(pub String);
+
+/// keeps the enemy definitions loaded.
+#[derive(Resource, Debug, Clone, Deref)]
+pub struct EnemyFolder(pub Handle<LoadedFolder>);
+
+/// the id of an enemy definition at `path`, its file name without the extension.
+fn enemy_id(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+
+    ENEMY_EXTENSIONS
+        .iter()
+        .find_map(|extension| name.strip_suffix(extension)?.strip_suffix('.'))
+        .map(str::to_string)
+}
+
+pub struct BestiaryPlugin;
+
+impl Plugin for BestiaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            RonAssetPlugin::<EnemyDefinition>::new(&[ENEMY_EXTENSIONS[0]]),
+            TomlAssetPlugin::<EnemyDefinition>::new(&[ENEMY_EXTENSIONS[1]]),
+        ));
+        app.init_resource::<EnemyCatalog>();
+        app.init_resource::<SavePath<Bestiary>>();
+        app.init_resource::<Encounter>();
+        app.add_message::<EnemyKilled>();
+        app.add_systems(PreStartup, load_bestiary);
+        app.add_systems(Startup, load_enemies);
+        app.add_systems(
+            Update,
+            (
+                report_load_failures.run_if(on_message::<AssetLoadFailedEvent<EnemyDefinition>>),
+                update_catalog.run_if(on_message::<AssetEvent<EnemyDefinition>>),
+                (record_kills, record_encounters).chain(),
+            ),
+        );
+        app.add_systems(
+            Last,
+            save_bestiary.run_if(on_timer(BESTIARY_AUTOSAVE_INTERVAL).or(on_message::<AppExit>)),
+        );
+    }
+}
+
+fn load_bestiary(mut cmds: Commands, save_path: Res<SavePath<Bestiary>>) {
+    let bestiary = Bestiary::load_or_default(&save_path.path);
+
+    cmds.insert_resource(bestiary);
+}
+
+fn load_enemies(mut cmds: Commands, asset_server: Res<AssetServer>) {
+    cmds.insert_resource(EnemyFolder(asset_server.load_folder(ENEMIES_FOLDER)));
+}
+
+fn report_load_failures(mut failures: MessageReader<AssetLoadFailedEvent<EnemyDefinition>>) {
+    for failure in failures.read() {
+        error!(
+            "failed to load the enemy {}: {}",
+            failure.path, failure.error
+        );
+    }
+}
+
+/// rebuilds the catalog whenever an enemy definition is loaded, (hot) reloaded or removed,
+/// reporting what's wrong with the definitions that changed.
+fn update_catalog(
+    mut events: MessageReader<AssetEvent<EnemyDefinition>>,
+    definitions: Res<Assets<EnemyDefinition>>,
+    asset_server: Res<AssetServer>,
+    mut catalog: ResMut<EnemyCatalog>,
+) {
+    for event in events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = event else {
+            continue;
+        };
+        let (Some(enemy), Some(path)) = (definitions.get(*id), asset_server.get_path(*id)) else {
+            continue;
+        };
+
+        if let Err(errors) = enemy.validate() {
+            for e in errors {
+                error!("the enemy {path} is invalid, it will be left out: {e}");
+            }
+        }
+    }
+
+    let mut enemies = BTreeMap::new();
+
+    for (id, enemy) in definitions.iter() {
+        let Some(path) = asset_server.get_path(id) else {
+            continue;
+        };
+        let Some(enemy_id) = enemy_id(path.path()) else {
+            warn!("{path} isn't named like an enemy definition, skipping it");
+            continue;
+        };
+
+        if enemy.validate().is_err() {
+            continue;
+        }
+
+        if enemies.contains_key(&enemy_id) {
+            error!("more than one enemy is defined with the id \"{enemy_id}\", skipping {path}");
+            continue;
+        }
+
+        enemies.insert(enemy_id, enemy.clone());
+    }
+
+    if catalog.0 != enemies {
+        info!("{} enemies in the catalog", enemies.len());
+        catalog.0 = enemies;
+    }
+}
+
+/// picks the kinds of the enemies in each room the party walks into.
+fn record_encounters(
+    mut entered: MessageReader<RoomEntered>,
+    catalog: Res<EnemyCatalog>,
+    mut rng: ResMut<DungeonRng>,
+    mut encounter: ResMut<Encounter>,
+    mut bestiary: ResMut<Bestiary>,
+) {
+    for room in entered.read() {
+        let available = catalog.available_at(room.depth);
+
+        encounter.clear();
+
+        for _ in 0..room.enemies {
+            let Some(enemy) = available.choose(&mut **rng) else {
+                break;
+            };
+
+            bestiary.entry((*enemy).clone()).or_default().encountered += 1;
+            encounter.push((*enemy).clone());
+        }
+    }
+}
+
+fn record_kills(
+    mut cleared: MessageReader<RoomCleared>,
+    mut encounter: ResMut<Encounter>,
+    mut bestiary: ResMut<Bestiary>,
+    mut killed: MessageWriter<EnemyKilled>,
+) {
+    for _room in cleared.read() {
+        for enemy in encounter.drain(..) {
+            bestiary.entry(enemy.clone()).or_default().killed += 1;
+            killed.write(EnemyKilled(enemy));
+        }
+    }
+}
+
+fn save_bestiary(bestiary: Res<Bestiary>, save_path: Res<SavePath<Bestiary>>) {
+    bestiary.save_or_log(&save_path.path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{inventory_plugin::loot::LootDrop, shipped_assets};
+
+    fn skeleton() -> EnemyDefinition {
+        toml::from_str(include_str!("../../../assets/enemies/skeleton.enemy.toml")).unwrap()
+    }
+
+    #[test]
+    fn shipped_definitions_are_valid() {
+        let ron = shipped_assets(ENEMIES_FOLDER, ENEMY_EXTENSIONS[0])
+            .into_iter()
+            .map(|(path, source)| (ron::from_str(&source).map_err(|e| e.to_string()), path));
+        let toml = shipped_assets(ENEMIES_FOLDER, ENEMY_EXTENSIONS[1])
+            .into_iter()
+            .map(|(path, source)| (toml::from_str(&source).map_err(|e| e.to_string()), path));
+
+        for (enemy, path) in ron.chain(toml) {
+            let enemy: EnemyDefinition = enemy.unwrap_or_else(|e| panic!("{path}: {e}"));
+
+            assert_eq!(enemy.validate(), Ok(()), "{path}");
+        }
+    }
+
+    #[test]
+    fn validate_reports_everything_wrong() {
+        let mut enemy = skeleton();
+        enemy.name = " ".to_string();
+        enemy.min_depth = 0;
+        enemy.stats.hp = 0.0;
+        enemy.stats.attack = f64::NAN;
+        enemy.resistances.insert(DamageType::Fire, 1.5);
+        enemy.skills = vec![
+            "bone club".to_string(),
+            "bone club".to_string(),
+            String::new(),
+        ];
+        enemy.loot_table = LootTable(vec![
+            LootDrop {
+                item: "bone".to_string(),
+                weight: 0,
+                count: 1,
+            },
+            LootDrop {
+                item: "skull".to_string(),
+                weight: 1,
+                count: 0,
+            },
+        ]);
+        enemy.inflicts = vec![Inflict {
+            effect: "poisoned".to_string(),
+            chance: 2.0,
+        }];
+        enemy.art_hint.color = "bone white".to_string();
+
+        let errors = enemy.validate().unwrap_err();
+
+        assert_eq!(errors.len(), 11);
+        assert!(errors.contains(&EnemyDefinitionError::EmptyName));
+        assert!(errors.contains(&EnemyDefinitionError::MinDepth));
+        assert!(errors.contains(&EnemyDefinitionError::Stat {
+            stat: "hp",
+            value: 0.0
+        }));
+        assert!(errors.contains(&EnemyDefinitionError::DuplicateSkill(
+            "bone club".to_string()
+        )));
+        assert!(errors.contains(&EnemyDefinitionError::EmptySkill));
+        assert!(errors.contains(&EnemyDefinitionError::LootWeight("bone".to_string())));
+        assert!(errors.contains(&EnemyDefinitionError::LootCount("skull".to_string())));
+        assert!(errors.contains(&EnemyDefinitionError::Color("bone white".to_string())));
+    }
+
+    #[test]
+    fn attack_and_defense_can_be_zero() {
+        let mut enemy = skeleton();
+        enemy.stats.attack = 0.0;
+        enemy.stats.defense = 0.0;
+
+        assert_eq!(enemy.validate(), Ok(()));
+    }
+}
@@ -1,26 +1,83 @@
-// use bevy::input::mouse::{MouseButton, MouseMotion};
-use bevy::prelude::*;
+use std::f32::consts::FRAC_PI_2;
 
-use crate::backend::*;
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll, MouseScrollUnit},
+    prelude::*,
+};
 
-#[derive(Component)]
+use crate::backend::{action_plugin::Action, *};
+
+/// how close to straight up/down the camera can look, keeps it from flipping over the poles.
+pub const MAX_PITCH: f32 = FRAC_PI_2 - 0.05;
+/// how many pixels of a pixel based scroll (touchpads) count as one line.
+const PIXELS_PER_LINE: f32 = 16.0;
+
+/// orbits the camera around `focus`. `focus`, `distance`, `yaw` & `pitch` are where the camera is
+/// headed, the camera eases towards them at a rate set by `damping`.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
 pub struct OrbitCamera {
+    /// the point being orbited.
+    pub focus: Vec3,
     pub distance: f32,
     pub yaw: f32,
     pub pitch: f32,
+    /// radians per pixel of mouse movement.
     pub sensitivity: f32,
+    /// the fraction of the distance one line of scrolling zooms by.
+    pub zoom_sensitivity: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    /// how quickly the camera catches up with where it's headed, higher is snappier.
+    pub damping: f32,
+    /// the eased `(distance, yaw, pitch)` the camera is currently at.
+    current: (f32, f32, f32),
+    current_focus: Vec3,
 }
 
 impl Default for OrbitCamera {
     fn default() -> Self {
+        let (distance, yaw, pitch) = (4.0, 0.0, 0.0);
+
         Self {
-            distance: 3.0,
-            yaw: 0.0,
-            pitch: 0.0,
+            focus: Vec3::ZERO,
+            distance,
+            yaw,
+            pitch,
             sensitivity: 0.01,
+            zoom_sensitivity: 0.1,
+            min_distance: 1.5,
+            max_distance: 50.0,
+            damping: 8.0,
+            current: (distance, yaw, pitch),
+            current_focus: Vec3::ZERO,
+        }
+    }
+}
+
+impl OrbitCamera {
+    /// sends the camera back to the default orbit, keeping the focus, sensitivity & limits.
+    pub fn reset(&mut self) {
+        let default = Self::default();
+
+        self.distance = default.distance;
+        self.yaw = default.yaw;
+        self.pitch = default.pitch;
+    }
+
+    /// the transform of the camera at its current, eased, position.
+    pub fn transform(&self) -> Transform {
+        let (distance, yaw, pitch) = self.current;
+        let rotation = Quat::from_euler(EulerRot::YXZ, yaw, -pitch, 0.0);
+
+        Transform {
+            translation: self.current_focus + rotation * Vec3::Z * distance,
+            rotation,
+            ..default()
         }
     }
 }
+
 pub struct BevyScenePlugin;
 
 impl Plugin for BevyScenePlugin {
@@ -30,6 +87,10 @@ impl Plugin for BevyScenePlugin {
         // app.insert_resource(CurrentIdleTimeSeconds(25.125));
         // app.insert_resource(LongestIdleTimeSeconds(30.0));
         app.add_systems(Update, sync_with_ui);
+        app.add_systems(
+            Update,
+            ((orbit_camera_input, reset_orbit_camera), ease_orbit_camera).chain(),
+        );
         // app.add_systems(Update, sync_with_ui_2);
     }
 }
@@ -51,9 +112,68 @@ impl Plugin for BevyScenePlugin {
 //     commands.spawn(TestComponent(7));
 // }
 
-fn sync_with_ui(mut fps: ResMut<FPS>, time: Res<Time>) {
-    let new_fps = 1000.0 / time.delta().as_millis() as f32;
-    *fps = FPS(new_fps);
+fn sync_with_ui(mut fps: ResMut<FPS>, diagnostics: Res<DiagnosticsStore>) {
+    if let Some(new_fps) = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(Diagnostic::smoothed)
+    {
+        *fps = FPS(new_fps as f32);
+    }
+}
+
+fn orbit_camera_input(
+    mut cameras: Query<&mut OrbitCamera>,
+    actions: Res<ButtonInput<Action>>,
+    motion: Res<AccumulatedMouseMotion>,
+    scroll: Res<AccumulatedMouseScroll>,
+) {
+    let drag = if actions.pressed(Action::OrbitCamera) {
+        motion.delta
+    } else {
+        Vec2::ZERO
+    };
+    let lines = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / PIXELS_PER_LINE,
+    };
+
+    if drag == Vec2::ZERO && lines == 0.0 {
+        return;
+    }
+
+    for mut camera in &mut cameras {
+        camera.yaw -= drag.x * camera.sensitivity;
+        camera.pitch = (camera.pitch + drag.y * camera.sensitivity).clamp(-MAX_PITCH, MAX_PITCH);
+        camera.distance = (camera.distance * (-lines * camera.zoom_sensitivity).exp())
+            .clamp(camera.min_distance, camera.max_distance);
+    }
+}
+
+fn reset_orbit_camera(mut cameras: Query<&mut OrbitCamera>, actions: Res<ButtonInput<Action>>) {
+    if actions.just_pressed(Action::ResetCamera) {
+        cameras.iter_mut().for_each(|mut camera| camera.reset());
+    }
+}
+
+/// moves orbit cameras towards where they're headed.
+fn ease_orbit_camera(mut cameras: Query<(&mut OrbitCamera, &mut Transform)>, time: Res<Time>) {
+    for (mut camera, mut transform) in &mut cameras {
+        let ease = 1.0 - (-camera.damping * time.delta_secs()).exp();
+        let (distance, yaw, pitch) = camera.current;
+        let current = (
+            distance + (camera.distance - distance) * ease,
+            yaw + (camera.yaw - yaw) * ease,
+            pitch + (camera.pitch - pitch) * ease,
+        );
+
+        let focus = camera.current_focus.lerp(camera.focus, ease);
+
+        if current != camera.current || focus != camera.current_focus {
+            camera.current = current;
+            camera.current_focus = focus;
+            *transform = camera.transform();
+        }
+    }
 }
 
 // fn sync_with_ui_2(
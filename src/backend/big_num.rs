@@ -0,0 +1,535 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// past this many orders of magnitude apart, adding the smaller number to the larger one can't
+/// change the larger one's mantissa (an f64 only holds ~17 significant digits).
+const MAX_PRECISION_DIGITS: i64 = 17;
+
+/// the suffixes used when formatting numbers smaller than `SCIENTIFIC_THRESHOLD`.
+const SUFFIXES: [&str; 6] = ["", "K", "M", "B", "T", "Qa"];
+
+/// numbers with an exponent at or above this are formatted in scientific notation.
+const SCIENTIFIC_THRESHOLD: i64 = 3 * SUFFIXES.len() as i64;
+
+/// the largest f64 below 10, the biggest a normalized mantissa can be.
+const MAX_MANTISSA: f64 = 9.999_999_999_999_998;
+
+/// an arbitrarily large number stored as `mantissa * 10^exponent`. used for currencies & stats
+/// that would overflow, or lose all precision in, an f64 once multipliers start to stack.
+///
+/// the number is always kept normalized, meaning `1.0 <= |mantissa| < 10.0`, or the mantissa &
+/// exponent are both zero.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(from = "RawBigNum", into = "RawBigNum")]
+pub struct BigNum {
+    mantissa: f64,
+    exponent: i64,
+}
+
+/// the on disk representation of a `BigNum`. kept seperate so that hand edited or old save files
+/// are normalized when loaded.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct RawBigNum {
+    mantissa: f64,
+    exponent: i64,
+}
+
+impl From<RawBigNum> for BigNum {
+    fn from(value: RawBigNum) -> Self {
+        Self::new(value.mantissa, value.exponent)
+    }
+}
+
+impl From<BigNum> for RawBigNum {
+    fn from(value: BigNum) -> Self {
+        Self {
+            mantissa: value.mantissa,
+            exponent: value.exponent,
+        }
+    }
+}
+
+impl BigNum {
+    pub const ZERO: Self = Self {
+        mantissa: 0.0,
+        exponent: 0,
+    };
+    pub const ONE: Self = Self {
+        mantissa: 1.0,
+        exponent: 0,
+    };
+
+    /// makes a new `BigNum` equal to `mantissa * 10^exponent`. the mantissa doesn't need to be
+    /// normalized.
+    pub fn new(mantissa: f64, exponent: i64) -> Self {
+        Self { mantissa, exponent }.normalized()
+    }
+
+    pub fn mantissa(&self) -> f64 {
+        self.mantissa
+    }
+
+    pub fn exponent(&self) -> i64 {
+        self.exponent
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0.0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa < 0.0
+    }
+
+    pub fn abs(self) -> Self {
+        Self {
+            mantissa: self.mantissa.abs(),
+            exponent: self.exponent,
+        }
+    }
+
+    /// base 10 logarithm of the number. returns negative infinity for zero and NaN for negative
+    /// numbers, same as `f64::log10`.
+    pub fn log10(&self) -> f64 {
+        self.mantissa.log10() + self.exponent as f64
+    }
+
+    /// raises the number to a (possibly fractional) power.
+    pub fn powf(self, power: f64) -> Self {
+        if self.is_zero() {
+            return if power == 0.0 { Self::ONE } else { Self::ZERO };
+        }
+
+        let sign = if self.is_negative() && power.fract() == 0.0 && power % 2.0 != 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+        let log = self.abs().log10() * power;
+        let exponent = log.floor();
+
+        Self::new(sign * 10_f64.powf(log - exponent), exponent as i64)
+    }
+
+    /// converts to an f64, saturating to +/- infinity when the number is out of range.
+    pub fn to_f64(&self) -> f64 {
+        if self.exponent > f64::MAX_10_EXP as i64 {
+            self.mantissa * f64::INFINITY
+        } else if self.exponent < f64::MIN_10_EXP as i64 {
+            0.0
+        } else {
+            self.mantissa * 10_f64.powi(self.exponent as i32)
+        }
+    }
+
+    /// converts to an f32, saturating to +/- `f32::MAX` so the result is always finite.
+    pub fn to_f32(&self) -> f32 {
+        (self.to_f64() as f32).clamp(f32::MIN, f32::MAX)
+    }
+
+    /// the number with the mantissa in `[1, 10)`. NaN becomes zero & infinities saturate to the
+    /// largest number with the same sign.
+    fn normalized(self) -> Self {
+        if self.mantissa == 0.0 || self.mantissa.is_nan() {
+            return Self::ZERO;
+        } else if self.mantissa.is_infinite() {
+            return Self {
+                mantissa: MAX_MANTISSA.copysign(self.mantissa),
+                exponent: i64::MAX,
+            };
+        }
+
+        let shift = self.mantissa.abs().log10().floor();
+        let mut mantissa = self.mantissa / 10_f64.powf(shift);
+        let mut exponent = self.exponent.saturating_add(shift as i64);
+
+        // float rounding can leave the mantissa just outside of [1, 10).
+        if mantissa.abs() >= 10.0 {
+            mantissa /= 10.0;
+            exponent = exponent.saturating_add(1);
+        } else if mantissa.abs() < 1.0 {
+            mantissa *= 10.0;
+            exponent = exponent.saturating_sub(1);
+        }
+
+        Self { mantissa, exponent }
+    }
+}
+
+impl From<f64> for BigNum {
+    fn from(value: f64) -> Self {
+        Self::new(value, 0)
+    }
+}
+
+impl From<f32> for BigNum {
+    fn from(value: f32) -> Self {
+        Self::new(value as f64, 0)
+    }
+}
+
+impl From<u64> for BigNum {
+    fn from(value: u64) -> Self {
+        Self::new(value as f64, 0)
+    }
+}
+
+impl From<BigNum> for f64 {
+    fn from(value: BigNum) -> Self {
+        value.to_f64()
+    }
+}
+
+impl From<BigNum> for f32 {
+    fn from(value: BigNum) -> Self {
+        value.to_f32()
+    }
+}
+
+impl PartialEq for BigNum {
+    fn eq(&self, other: &Self) -> bool {
+        self.mantissa == other.mantissa && (self.exponent == other.exponent || self.is_zero())
+    }
+}
+
+impl PartialOrd for BigNum {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let sign = |n: &Self| n.mantissa.partial_cmp(&0.0);
+        let (self_sign, other_sign) = (sign(self)?, sign(other)?);
+
+        if self_sign != other_sign || self_sign == Ordering::Equal {
+            return Some(self_sign.cmp(&other_sign));
+        }
+
+        // both numbers have the same sign & are non-zero.
+        let magnitude = self
+            .exponent
+            .cmp(&other.exponent)
+            .then(self.mantissa.abs().partial_cmp(&other.mantissa.abs())?);
+
+        Some(if self_sign == Ordering::Less {
+            magnitude.reverse()
+        } else {
+            magnitude
+        })
+    }
+}
+
+impl Neg for BigNum {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            mantissa: -self.mantissa,
+            exponent: self.exponent,
+        }
+    }
+}
+
+impl Add for BigNum {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        if self.is_zero() {
+            return rhs;
+        } else if rhs.is_zero() {
+            return self;
+        }
+
+        let (big, small) = if self.exponent >= rhs.exponent {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        let diff = big.exponent - small.exponent;
+
+        if diff > MAX_PRECISION_DIGITS {
+            return big;
+        }
+
+        Self::new(
+            big.mantissa + small.mantissa / 10_f64.powi(diff as i32),
+            big.exponent,
+        )
+    }
+}
+
+impl Sub for BigNum {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for BigNum {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::new(
+            self.mantissa * rhs.mantissa,
+            self.exponent.saturating_add(rhs.exponent),
+        )
+    }
+}
+
+impl Div for BigNum {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Self::new(
+            self.mantissa / rhs.mantissa,
+            self.exponent.saturating_sub(rhs.exponent),
+        )
+    }
+}
+
+impl Mul<f64> for BigNum {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        self * Self::from(rhs)
+    }
+}
+
+impl Div<f64> for BigNum {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        self / Self::from(rhs)
+    }
+}
+
+impl AddAssign for BigNum {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for BigNum {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for BigNum {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for BigNum {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl MulAssign<f64> for BigNum {
+    fn mul_assign(&mut self, rhs: f64) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign<f64> for BigNum {
+    fn div_assign(&mut self, rhs: f64) {
+        *self = *self / rhs;
+    }
+}
+
+impl Sum for BigNum {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl Display for BigNum {
+    /// formats small numbers with a short suffix (`1.50K`, `12.00M`) and anything larger in
+    /// scientific notation (`1.23e45`). the precision defaults to 2 decimal places.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let precision = f.precision().unwrap_or(2);
+
+        if self.is_zero() || (0..SCIENTIFIC_THRESHOLD).contains(&self.exponent) {
+            let group = self.exponent.max(0) / 3;
+            let value = self.mantissa * 10_f64.powi((self.exponent - group * 3) as i32);
+            let rounded = format!("{value:.precision$}");
+
+            // rounding can carry over into the next suffix, e.g. 999.999 -> 1000.00.
+            match rounded.parse::<f64>() {
+                Ok(rounded) if rounded.abs() >= 1000.0 => Self::new(rounded, group * 3).fmt(f),
+                _ => write!(f, "{rounded}{}", SUFFIXES[group as usize]),
+            }
+        } else if self.exponent < 0 && self.exponent > -3 {
+            write!(f, "{:.precision$}", self.to_f64())
+        } else {
+            let mantissa = format!("{:.precision$}", self.mantissa);
+
+            // same as above, 9.999e5 -> 10.00e5.
+            match mantissa.parse::<f64>() {
+                Ok(rounded) if rounded.abs() >= 10.0 && self.exponent < i64::MAX => {
+                    Self::new(rounded, self.exponent).fmt(f)
+                }
+                _ => write!(f, "{mantissa}e{}", self.exponent),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_normalizes() {
+        let n = BigNum::new(12_345.0, 2);
+
+        assert_eq!(n.mantissa(), 1.2345);
+        assert_eq!(n.exponent(), 6);
+        assert_eq!(BigNum::new(0.05, 0), BigNum::new(5.0, -2));
+        assert_eq!(BigNum::new(0.0, 40), BigNum::ZERO);
+        assert_eq!(BigNum::new(0.0, 40).exponent(), 0);
+    }
+
+    #[test]
+    fn non_finite_values() {
+        assert_eq!(BigNum::from(f64::NAN), BigNum::ZERO);
+
+        let infinite = BigNum::from(f64::INFINITY);
+
+        assert!((1.0..10.0).contains(&infinite.mantissa()));
+        assert_eq!(infinite.exponent(), i64::MAX);
+        assert!(infinite > BigNum::new(1.0, 1_000_000));
+
+        let negative = BigNum::from(f64::NEG_INFINITY);
+
+        assert!(negative.is_negative());
+        assert_eq!(negative, -infinite);
+        assert_eq!(BigNum::ONE / BigNum::ZERO, infinite);
+    }
+
+    #[test]
+    fn add_sub_across_exponents() {
+        assert_eq!(BigNum::from(1.5) + BigNum::from(250.0), BigNum::from(251.5));
+        assert_eq!(
+            BigNum::new(1.0, 20) + BigNum::new(5.0, 18),
+            BigNum::new(1.05, 20)
+        );
+        assert_eq!(
+            BigNum::new(5.0, 18) + BigNum::new(1.0, 20),
+            BigNum::new(1.05, 20)
+        );
+        // too far apart to change the larger number.
+        assert_eq!(
+            BigNum::new(1.0, 40) + BigNum::new(9.0, 10),
+            BigNum::new(1.0, 40)
+        );
+        assert_eq!(BigNum::from(250.0) - BigNum::from(1.5), BigNum::from(248.5));
+        assert_eq!(
+            BigNum::from(1.5) - BigNum::from(250.0),
+            BigNum::from(-248.5)
+        );
+        assert_eq!(BigNum::new(3.0, 30) - BigNum::new(3.0, 30), BigNum::ZERO);
+        assert_eq!(
+            BigNum::new(1.0, 20) - BigNum::new(5.0, 19),
+            BigNum::new(5.0, 19)
+        );
+    }
+
+    #[test]
+    fn mul_div() {
+        assert_eq!(
+            BigNum::new(5.0, 10) * BigNum::new(4.0, 20),
+            BigNum::new(2.0, 31)
+        );
+        assert_eq!(
+            BigNum::new(2.0, 31) / BigNum::new(4.0, 20),
+            BigNum::new(5.0, 10)
+        );
+        assert_eq!(BigNum::from(3.0) * 0.5, BigNum::from(1.5));
+    }
+
+    #[test]
+    fn compare() {
+        let mut numbers = [
+            BigNum::new(-2.0, 5),
+            BigNum::new(3.0, 1),
+            BigNum::ZERO,
+            BigNum::new(-1.0, 1),
+            BigNum::new(1.0, 100),
+            BigNum::new(9.0, 2),
+        ];
+
+        numbers.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        assert_eq!(
+            numbers,
+            [
+                BigNum::new(-2.0, 5),
+                BigNum::new(-1.0, 1),
+                BigNum::ZERO,
+                BigNum::new(3.0, 1),
+                BigNum::new(9.0, 2),
+                BigNum::new(1.0, 100),
+            ]
+        );
+        assert!(BigNum::new(9.9, 4) < BigNum::new(1.0, 5));
+        assert!(BigNum::new(-9.9, 4) > BigNum::new(-1.0, 5));
+    }
+
+    #[test]
+    fn powf() {
+        assert_eq!(BigNum::from(2.0).powf(10.0).to_f64().round(), 1024.0);
+        assert!((BigNum::new(1.0, 100).powf(0.5).log10() - 50.0).abs() < 1e-9);
+        assert!(
+            (BigNum::new(1.15, 0).powf(1_000.0).log10() - 1_000.0 * 1.15_f64.log10()).abs() < 1e-9
+        );
+        assert_eq!(BigNum::from(-2.0).powf(3.0).to_f64().round(), -8.0);
+        assert_eq!(BigNum::from(-2.0).powf(2.0).to_f64().round(), 4.0);
+        assert_eq!(BigNum::ZERO.powf(0.0), BigNum::ONE);
+        assert_eq!(BigNum::ZERO.powf(2.0), BigNum::ZERO);
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let n = BigNum::new(4.2, 123);
+        let json = serde_json::to_string(&n).unwrap();
+
+        assert_eq!(serde_json::from_str::<BigNum>(&json).unwrap(), n);
+
+        // hand edited saves are normalized.
+        let edited: BigNum = serde_json::from_str(r#"{"mantissa":420.0,"exponent":121}"#).unwrap();
+
+        assert_eq!(edited.mantissa(), 4.2);
+        assert_eq!(edited.exponent(), 123);
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(BigNum::ZERO.to_string(), "0.00");
+        assert_eq!(BigNum::from(12.5).to_string(), "12.50");
+        assert_eq!(BigNum::from(1_500.0).to_string(), "1.50K");
+        assert_eq!(BigNum::new(1.2, 7).to_string(), "12.00M");
+        assert_eq!(BigNum::from(-1_500.0).to_string(), "-1.50K");
+        assert_eq!(BigNum::from(0.05).to_string(), "0.05");
+        assert_eq!(BigNum::new(1.23, 45).to_string(), "1.23e45");
+        assert_eq!(BigNum::new(1.5, -7).to_string(), "1.50e-7");
+        assert_eq!(format!("{:.0}", BigNum::from(1_500.0)), "2K");
+    }
+
+    #[test]
+    fn format_rounding_boundaries() {
+        assert_eq!(BigNum::from(999.994).to_string(), "999.99");
+        assert_eq!(BigNum::from(999.999).to_string(), "1.00K");
+        assert_eq!(BigNum::from(-999.999).to_string(), "-1.00K");
+        assert_eq!(BigNum::from(999_999.0).to_string(), "1.00M");
+        assert_eq!(format!("{:.0}", BigNum::from(999.6)), "1K");
+        // the last suffix rounds up into scientific notation.
+        assert_eq!(BigNum::new(9.999_999, 17).to_string(), "1.00e18");
+        assert_eq!(BigNum::new(9.999, 45).to_string(), "1.00e46");
+    }
+}
@@ -0,0 +1,174 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::modifier::{Modifier, fmt_modifier, modify, no_scaling};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stat {
+    Hp,
+    Attack,
+    Defense,
+    Speed,
+}
+
+impl Stat {
+    pub const ALL: [Self; 4] = [Self::Hp, Self::Attack, Self::Defense, Self::Speed];
+}
+
+impl Display for Stat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Hp => "hp",
+            Self::Attack => "attack",
+            Self::Defense => "defense",
+            Self::Speed => "speed",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct CombatStats {
+    pub hp: f64,
+    pub attack: f64,
+    pub defense: f64,
+    pub speed: f64,
+}
+
+impl CombatStats {
+    pub fn get(&self, stat: Stat) -> f64 {
+        match stat {
+            Stat::Hp => self.hp,
+            Stat::Attack => self.attack,
+            Stat::Defense => self.defense,
+            Stat::Speed => self.speed,
+        }
+    }
+
+    pub fn get_mut(&mut self, stat: Stat) -> &mut f64 {
+        match stat {
+            Stat::Hp => &mut self.hp,
+            Stat::Attack => &mut self.attack,
+            Stat::Defense => &mut self.defense,
+            Stat::Speed => &mut self.speed,
+        }
+    }
+
+    /// these stats with `modifiers` applied. every flat bonus is added before anything is
+    /// multiplied, so the order the modifiers come in doesn't matter. stats don't go below 0.
+    pub fn with_modifiers<'a>(
+        &self,
+        modifiers: impl IntoIterator<Item = &'a StatModifier> + Clone,
+    ) -> Self {
+        let mut stats = *self;
+
+        for stat in Stat::ALL {
+            *stats.get_mut(stat) = modify(self.get(stat), stat, modifiers.clone()).max(0.0);
+        }
+
+        stats
+    }
+}
+
+/// a change to one stat, from an item, a status effect, etc.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatModifier {
+    pub stat: Stat,
+    /// a flat bonus, can be negative.
+    #[serde(default)]
+    pub add: f64,
+    /// scales the stat after every flat bonus is added.
+    #[serde(default = "no_scaling")]
+    pub multiply: f64,
+}
+
+impl Modifier for StatModifier {
+    type Target = Stat;
+
+    fn target(&self) -> Stat {
+        self.stat
+    }
+
+    fn add(&self) -> f64 {
+        self.add
+    }
+
+    fn multiply(&self) -> f64 {
+        self.multiply
+    }
+
+    fn stacked(&self, stacks: u32) -> Self {
+        Self {
+            stat: self.stat,
+            add: self.add * stacks as f64,
+            multiply: self.multiply.powi(stacks as i32),
+        }
+    }
+}
+
+impl Display for StatModifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt_modifier(self, f)
+    }
+}
+
+/// the stats of a combatant before any modifiers.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Deref, DerefMut)]
+pub struct BaseStats(pub CombatStats);
+
+/// the stats a combatant actually fights with, its `BaseStats` with every modifier applied.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Deref, DerefMut)]
+pub struct EffectiveStats(pub CombatStats);
+
+/// where a combatant's stat modifiers come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ModifierSource {
+    Equipment,
+    StatusEffects,
+}
+
+/// every stat modifier on a combatant, by where it comes from. each source replaces its own
+/// modifiers & `EffectiveStats` is worked out from all of them.
+#[derive(Component, Debug, Clone, PartialEq, Default, Deref, DerefMut)]
+pub struct StatModifiers(pub BTreeMap<ModifierSource, Vec<StatModifier>>);
+
+/// the hp a combatant has left, out of its effective hp.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Deref, DerefMut)]
+pub struct Health(pub f64);
+
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (apply_modifiers, cap_health).chain());
+    }
+}
+
+/// works out everyone's effective stats whenever their base stats or modifiers change.
+fn apply_modifiers(
+    mut combatants: Query<(Ref<BaseStats>, Ref<StatModifiers>, &mut EffectiveStats)>,
+) {
+    for (base, modifiers, mut effective) in &mut combatants {
+        if !base.is_changed() && !modifiers.is_changed() {
+            continue;
+        }
+
+        effective.set_if_neq(EffectiveStats(
+            base.with_modifiers(modifiers.values().flatten()),
+        ));
+    }
+}
+
+/// keeps everyone's hp within their effective hp, & above 0.
+fn cap_health(mut combatants: Query<(&EffectiveStats, &mut Health), Changed<EffectiveStats>>) {
+    for (stats, mut health) in &mut combatants {
+        let capped = health.clamp(0.0, stats.hp);
+
+        health.set_if_neq(Health(capped));
+    }
+}
@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use bevy::{
+    diagnostic::{
+        Diagnostic, DiagnosticPath, Diagnostics, DiagnosticsStore, EntityCountDiagnosticsPlugin,
+        FrameTimeDiagnosticsPlugin, RegisterDiagnostic,
+    },
+    prelude::*,
+    time::common_conditions::on_timer,
+};
+
+use crate::backend::{
+    action_plugin::Action,
+    base_plugin::{UiCommand, UiCommandRx},
+    idle_time_plugin::{AutomationSpeedHistory, IdleTimeHistory, input_history::InputHistory},
+};
+
+/// how many inputs the `InputHistory` is holding.
+pub const INPUT_HISTORY_LEN: DiagnosticPath =
+    DiagnosticPath::const_new("idle_dungeoneer/input_history_len");
+/// how many samples the idle time history is holding.
+pub const IDLE_TIME_HISTORY_LEN: DiagnosticPath =
+    DiagnosticPath::const_new("idle_dungeoneer/idle_time_history_len");
+/// how many samples the automation speed history is holding.
+pub const AUTOMATION_SPEED_HISTORY_LEN: DiagnosticPath =
+    DiagnosticPath::const_new("idle_dungeoneer/automation_speed_history_len");
+pub const UI_COMMAND_QUEUE: DiagnosticPath =
+    DiagnosticPath::const_new("idle_dungeoneer/ui_command_queue");
+/// how long the Dioxus UI took to build its element tree, as reported by the UI. this doesn't
+/// include diffing & painting it, which happen after the component returns.
+pub const UI_BUILD_TIME: DiagnosticPath =
+    DiagnosticPath::const_new("idle_dungeoneer/ui_build_time");
+
+/// how often the `PerfReport` is refreshed.
+pub const REPORT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// whether the diagnostics overlay is shown.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DiagnosticsOverlay {
+    pub visible: bool,
+}
+
+/// a summary of the diagnostics for the UI. times are in milliseconds.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct PerfReport {
+    pub fps: f64,
+    pub frame_time_p50: f64,
+    pub frame_time_p95: f64,
+    pub frame_time_p99: f64,
+    pub entities: f64,
+    pub input_history_len: f64,
+    pub idle_time_history_len: f64,
+    pub automation_speed_history_len: f64,
+    pub ui_command_queue: f64,
+    pub ui_build_time: f64,
+}
+
+/// the `p`th percentile (0.0 to 1.0) of `sorted`, 0.0 if it's empty.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+
+    sorted[((sorted.len() - 1) as f64 * p).round() as usize]
+}
+
+pub struct DiagnosticsPlugin;
+
+impl Plugin for DiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(EntityCountDiagnosticsPlugin::default());
+        app.register_diagnostic(Diagnostic::new(INPUT_HISTORY_LEN));
+        app.register_diagnostic(Diagnostic::new(IDLE_TIME_HISTORY_LEN));
+        app.register_diagnostic(Diagnostic::new(AUTOMATION_SPEED_HISTORY_LEN));
+        app.register_diagnostic(Diagnostic::new(UI_COMMAND_QUEUE));
+        app.register_diagnostic(Diagnostic::new(UI_BUILD_TIME).with_suffix("ms"));
+        app.init_resource::<DiagnosticsOverlay>();
+        app.init_resource::<PerfReport>();
+        app.add_systems(
+            Update,
+            (
+                toggle_overlay,
+                measure_game_diagnostics,
+                measure_ui_build_time.run_if(on_message::<UiCommand>),
+                update_perf_report
+                    .run_if(|overlay: Res<DiagnosticsOverlay>| overlay.visible)
+                    .run_if(on_timer(REPORT_INTERVAL)),
+            ),
+        );
+    }
+}
+
+fn toggle_overlay(
+    mut commands: MessageReader<UiCommand>,
+    actions: Res<ButtonInput<Action>>,
+    mut overlay: ResMut<DiagnosticsOverlay>,
+) {
+    let toggles = commands
+        .read()
+        .filter(|command| **command == UiCommand::ToggleDiagnostics)
+        .count()
+        + usize::from(actions.just_pressed(Action::ToggleDiagnostics));
+
+    if toggles % 2 == 1 {
+        overlay.visible = !overlay.visible;
+    }
+}
+
+fn measure_game_diagnostics(
+    mut diagnostics: Diagnostics,
+    input_history: Res<InputHistory>,
+    idle_time_history: Res<IdleTimeHistory>,
+    automation_speed_history: Res<AutomationSpeedHistory>,
+    ui_rx: Res<UiCommandRx>,
+) {
+    diagnostics.add_measurement(&INPUT_HISTORY_LEN, || input_history.len() as f64);
+    diagnostics.add_measurement(&IDLE_TIME_HISTORY_LEN, || idle_time_history.len() as f64);
+    diagnostics.add_measurement(&AUTOMATION_SPEED_HISTORY_LEN, || {
+        automation_speed_history.len() as f64
+    });
+    diagnostics.add_measurement(&UI_COMMAND_QUEUE, || ui_rx.len() as f64);
+}
+
+fn measure_ui_build_time(mut commands: MessageReader<UiCommand>, mut diagnostics: Diagnostics) {
+    for command in commands.read() {
+        if let UiCommand::ReportBuildTime(build_time) = command {
+            diagnostics.add_measurement(&UI_BUILD_TIME, || build_time.as_secs_f64() * 1000.0);
+        }
+    }
+}
+
+fn update_perf_report(store: Res<DiagnosticsStore>, mut report: ResMut<PerfReport>) {
+    let smoothed = |path: &DiagnosticPath| {
+        store
+            .get(path)
+            .and_then(Diagnostic::smoothed)
+            .unwrap_or_default()
+    };
+    let mut frame_times: Vec<f64> = store
+        .get(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        .map(|frame_time| frame_time.values().copied().collect())
+        .unwrap_or_default();
+    frame_times.sort_by(f64::total_cmp);
+
+    *report = PerfReport {
+        fps: smoothed(&FrameTimeDiagnosticsPlugin::FPS),
+        frame_time_p50: percentile(&frame_times, 0.5),
+        frame_time_p95: percentile(&frame_times, 0.95),
+        frame_time_p99: percentile(&frame_times, 0.99),
+        entities: smoothed(&EntityCountDiagnosticsPlugin::ENTITY_COUNT),
+        input_history_len: smoothed(&INPUT_HISTORY_LEN),
+        idle_time_history_len: smoothed(&IDLE_TIME_HISTORY_LEN),
+        automation_speed_history_len: smoothed(&AUTOMATION_SPEED_HISTORY_LEN),
+        ui_command_queue: smoothed(&UI_COMMAND_QUEUE),
+        ui_build_time: smoothed(&UI_BUILD_TIME),
+    };
+}
@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::backend::{
+    AutomationSpeed,
+    base_plugin::{AutomationStates, PauseStates},
+    dungeon_plugin::view::DungeonViewPlugin,
+    idle_time_plugin::should_automate,
+};
+
+pub mod view;
+
+/// the most rooms a floor is generated with, floors can come out shorter if the layout walks
+/// itself into a corner.
+pub const ROOMS_PER_FLOOR: usize = 8;
+pub const MAX_ENEMIES_PER_ROOM: usize = 3;
+/// how many automation steps it takes the party to clear a room.
+pub const ROOM_CLEAR_STEPS: f64 = 10.0;
+
+/// mixed into the run's seed, so the dungeon doesn't draw the same numbers as the `LootRng`.
+const DUNGEON_SEED_SALT: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// the rng dungeon generation, encounters & inflicted effects draw from.
+#[derive(Resource, Debug, Clone, Deref, DerefMut)]
+pub struct DungeonRng(pub StdRng);
+
+impl DungeonRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed ^ DUNGEON_SEED_SALT))
+    }
+}
+
+impl Default for DungeonRng {
+    fn default() -> Self {
+        Self(StdRng::from_rng(&mut rand::rng()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Room {
+    /// where the room sits on the floor's grid.
+    pub cell: IVec2,
+    /// enemies left in the room.
+    pub enemies: usize,
+}
+
+/// the floor the party is currently on. the rooms are in the order the party walks through them,
+/// each one next to the last on the grid.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct DungeonFloor {
+    /// 1 for the first floor.
+    pub depth: usize,
+    pub rooms: Vec<Room>,
+}
+
+impl DungeonFloor {
+    /// lays out a floor as a random walk over the grid, starting from an empty room.
+    pub fn generate(depth: usize, rng: &mut impl Rng) -> Self {
+        let mut rooms = vec![Room {
+            cell: IVec2::ZERO,
+            enemies: 0,
+        }];
+
+        while rooms.len() < ROOMS_PER_FLOOR {
+            let last = rooms[rooms.len() - 1].cell;
+            let free: Vec<IVec2> = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+                .into_iter()
+                .map(|step| last + step)
+                .filter(|cell| rooms.iter().all(|room| room.cell != *cell))
+                .collect();
+
+            if free.is_empty() {
+                break;
+            }
+
+            rooms.push(Room {
+                cell: free[rng.random_range(0..free.len())],
+                enemies: rng.random_range(1..=MAX_ENEMIES_PER_ROOM),
+            });
+        }
+
+        Self { depth, rooms }
+    }
+}
+
+/// where the party is on the current floor.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct PartyProgress {
+    /// index into `DungeonFloor::rooms`.
+    pub room: usize,
+    /// automation steps spent clearing the current room.
+    pub steps: f64,
+}
+
+/// sent when the party walks into a room.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RoomEntered {
+    pub depth: usize,
+    pub room: usize,
+    /// how many enemies are waiting in the room.
+    pub enemies: usize,
+}
+
+/// sent when the party finishes clearing a room.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RoomCleared {
+    pub depth: usize,
+    pub room: usize,
+    /// how many enemies the party defeated in the room.
+    pub enemies: usize,
+}
+
+pub struct DungeonPlugin;
+
+impl Plugin for DungeonPlugin {
+    fn build(&self, app: &mut App) {
+        let mut rng = DungeonRng::default();
+
+        app.insert_resource(DungeonFloor::generate(1, &mut *rng));
+        app.insert_resource(rng);
+        app.init_resource::<PartyProgress>();
+        app.add_message::<RoomEntered>();
+        app.add_message::<RoomCleared>();
+        app.add_plugins(DungeonViewPlugin);
+        app.add_systems(Startup, enter_first_room);
+        app.add_systems(
+            Update,
+            advance_party
+                .run_if(in_state(AutomationStates::Automation))
+                .run_if(in_state(PauseStates::Running))
+                .run_if(should_automate),
+        );
+    }
+}
+
+/// the party starts out in the first room of the first floor, so it walks in there like any other.
+fn enter_first_room(
+    floor: Res<DungeonFloor>,
+    progress: Res<PartyProgress>,
+    mut entered: MessageWriter<RoomEntered>,
+) {
+    entered.write(RoomEntered {
+        depth: floor.depth,
+        room: progress.room,
+        enemies: floor.rooms[progress.room].enemies,
+    });
+}
+
+/// steps the party through the floor at the automation speed, moving down a floor after the last
+/// room is cleared.
+fn advance_party(
+    mut progress: ResMut<PartyProgress>,
+    mut floor: ResMut<DungeonFloor>,
+    mut rng: ResMut<DungeonRng>,
+    mut entered: MessageWriter<RoomEntered>,
+    mut cleared: MessageWriter<RoomCleared>,
+    automation_speed: Res<AutomationSpeed>,
+    time: Res<Time>,
+) {
+    progress.steps += **automation_speed * time.delta_secs_f64();
+
+    if progress.steps < ROOM_CLEAR_STEPS {
+        return;
+    }
+
+    progress.steps = 0.0;
+    cleared.write(RoomCleared {
+        depth: floor.depth,
+        room: progress.room,
+        enemies: floor.rooms[progress.room].enemies,
+    });
+    // the layout didn't change, so the floor isn't marked changed & redrawn. the view takes the
+    // room's enemies away when it sees the `RoomCleared`.
+    floor.bypass_change_detection().rooms[progress.room].enemies = 0;
+
+    if progress.room + 1 < floor.rooms.len() {
+        progress.room += 1;
+    } else {
+        *floor = DungeonFloor::generate(floor.depth + 1, &mut **rng);
+        progress.room = 0;
+        info!("the party reached floor {}", floor.depth);
+    }
+
+    entered.write(RoomEntered {
+        depth: floor.depth,
+        room: progress.room,
+        enemies: floor.rooms[progress.room].enemies,
+    });
+}
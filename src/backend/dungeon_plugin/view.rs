@@ -0,0 +1,255 @@
+use std::f32::consts::TAU;
+
+use bevy::{pbr::wireframe::WireframeColor, prelude::*};
+
+use crate::backend::{
+    base_plugin::UiCommand,
+    bevy_scene_plugin::OrbitCamera,
+    dungeon_plugin::{DungeonFloor, PartyProgress, RoomCleared},
+    sphere::{BoundingSphere, UndulateSphere},
+    theme::Theme,
+};
+
+/// distance between the centers of neighbouring rooms.
+pub const ROOM_SPACING: f32 = 3.0;
+pub const ROOM_SIZE: f32 = 2.0;
+const CORRIDOR_WIDTH: f32 = 0.4;
+const FLOOR_THICKNESS: f32 = 0.05;
+/// how quickly the party walks to the next room, higher is snappier.
+const PARTY_SMOOTHING: f32 = 3.0;
+
+/// whether the dungeon is drawn in place of the sphere.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DungeonView {
+    pub enabled: bool,
+}
+
+/// everything spawned to draw the dungeon, so it can be torn down when the floor changes.
+#[derive(Clone, Copy, Debug, Component)]
+pub struct DungeonMesh;
+
+#[derive(Clone, Copy, Debug, Component)]
+pub struct PartyMarker;
+
+#[derive(Clone, Copy, Debug, Component)]
+pub struct EnemyMarker {
+    /// index into `DungeonFloor::rooms` of the room the enemy waits in.
+    pub room: usize,
+}
+
+/// the meshes of the sphere, which the dungeon is drawn in place of.
+type SphereFilter = Or<(With<UndulateSphere>, With<BoundingSphere>)>;
+
+/// the world position of the center of a room on the floor's grid.
+pub fn room_position(cell: IVec2) -> Vec3 {
+    Vec3::new(cell.x as f32, 0.0, cell.y as f32) * ROOM_SPACING
+}
+
+pub struct DungeonViewPlugin;
+
+impl Plugin for DungeonViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DungeonView>();
+        app.add_systems(
+            Update,
+            (
+                toggle_dungeon_view.run_if(on_message::<UiCommand>),
+                (
+                    show_dungeon.run_if(resource_changed::<DungeonView>),
+                    despawn_dungeon.run_if(
+                        resource_changed::<DungeonView>.and(|view: Res<DungeonView>| !view.enabled),
+                    ),
+                    build_dungeon
+                        .run_if(
+                            resource_changed::<DungeonView>
+                                .or(resource_changed::<DungeonFloor>)
+                                .or(resource_changed::<Theme>),
+                        )
+                        .run_if(|view: Res<DungeonView>| view.enabled),
+                    despawn_cleared_enemies.run_if(on_message::<RoomCleared>),
+                ),
+                (move_party, follow_party)
+                    .chain()
+                    .run_if(|view: Res<DungeonView>| view.enabled),
+            )
+                .chain(),
+        );
+    }
+}
+
+fn toggle_dungeon_view(mut commands: MessageReader<UiCommand>, mut view: ResMut<DungeonView>) {
+    for command in commands.read() {
+        if let UiCommand::ToggleDungeonView = command {
+            view.enabled = !view.enabled;
+        }
+    }
+}
+
+/// swaps the sphere out for the dungeon, and points the camera back at the sphere when the
+/// dungeon is hidden.
+fn show_dungeon(
+    view: Res<DungeonView>,
+    mut spheres: Query<&mut Visibility, SphereFilter>,
+    mut cameras: Query<&mut OrbitCamera>,
+) {
+    let visibility = if view.enabled {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+
+    spheres
+        .iter_mut()
+        .for_each(|mut sphere| *sphere = visibility);
+
+    if !view.enabled {
+        cameras
+            .iter_mut()
+            .for_each(|mut camera| camera.focus = Vec3::ZERO);
+    }
+}
+
+fn despawn_dungeon(mut cmds: Commands, dungeon: Query<Entity, With<DungeonMesh>>) {
+    for entity in &dungeon {
+        cmds.entity(entity).despawn();
+    }
+}
+
+/// (re)spawns the meshes of the current floor.
+fn build_dungeon(
+    mut cmds: Commands,
+    floor: Res<DungeonFloor>,
+    progress: Res<PartyProgress>,
+    theme: Res<Theme>,
+    old_meshes: Query<(Entity, &Transform, Has<PartyMarker>), With<DungeonMesh>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mut party_position = None;
+
+    for (entity, transform, is_party) in &old_meshes {
+        if is_party {
+            party_position = Some(transform.translation);
+        }
+
+        cmds.entity(entity).despawn();
+    }
+
+    // the party keeps walking from where it was drawn, unless it's on a new floor.
+    let party_position = party_position.filter(|_| !floor.is_changed() || progress.room != 0);
+
+    let room_material = materials.add(StandardMaterial::from_color(theme.overlay));
+    let enemy_material = materials.add(StandardMaterial::from_color(theme.chart_line));
+    let party_material = materials.add(StandardMaterial::from_color(theme.highlight));
+    let wireframe = WireframeColor {
+        color: theme.mantle.into(),
+    };
+    let room_mesh = meshes.add(Cuboid::new(ROOM_SIZE, FLOOR_THICKNESS, ROOM_SIZE));
+    let enemy_mesh = meshes.add(Cone::new(0.15, 0.4));
+
+    for (index, room) in floor.rooms.iter().enumerate() {
+        let center = room_position(room.cell);
+
+        cmds.spawn((
+            Mesh3d(room_mesh.clone()),
+            MeshMaterial3d(room_material.clone()),
+            Transform::from_translation(center),
+            wireframe,
+            DungeonMesh,
+        ));
+
+        for i in 0..room.enemies {
+            let angle = i as f32 / room.enemies as f32 * TAU;
+
+            cmds.spawn((
+                Mesh3d(enemy_mesh.clone()),
+                MeshMaterial3d(enemy_material.clone()),
+                Transform::from_translation(
+                    center + Vec3::new(angle.cos() * 0.5, 0.2, angle.sin() * 0.5),
+                ),
+                EnemyMarker { room: index },
+                DungeonMesh,
+            ));
+        }
+    }
+
+    for rooms in floor.rooms.windows(2) {
+        let (from, to) = (room_position(rooms[0].cell), room_position(rooms[1].cell));
+        let length = ROOM_SPACING - ROOM_SIZE;
+        let size = if from.x != to.x {
+            Vec3::new(length, FLOOR_THICKNESS, CORRIDOR_WIDTH)
+        } else {
+            Vec3::new(CORRIDOR_WIDTH, FLOOR_THICKNESS, length)
+        };
+
+        cmds.spawn((
+            Mesh3d(meshes.add(Cuboid::from_size(size))),
+            MeshMaterial3d(room_material.clone()),
+            Transform::from_translation(from.midpoint(to)),
+            wireframe,
+            DungeonMesh,
+        ));
+    }
+
+    let party_room = floor
+        .rooms
+        .get(progress.room)
+        .map(|room| room_position(room.cell))
+        .unwrap_or_default();
+
+    cmds.spawn((
+        Mesh3d(meshes.add(Capsule3d::new(0.15, 0.3))),
+        MeshMaterial3d(party_material),
+        Transform::from_translation(party_position.unwrap_or(party_room + Vec3::Y * 0.3)),
+        PartyMarker,
+        DungeonMesh,
+    ));
+}
+
+/// takes the enemies out of the rooms the party cleared on this floor. clearing the last room
+/// moves the party down a floor, which rebuilds the whole dungeon instead.
+fn despawn_cleared_enemies(
+    mut cmds: Commands,
+    mut cleared: MessageReader<RoomCleared>,
+    floor: Res<DungeonFloor>,
+    enemies: Query<(Entity, &EnemyMarker)>,
+) {
+    for cleared in cleared
+        .read()
+        .filter(|cleared| cleared.depth == floor.depth)
+    {
+        for (entity, enemy) in &enemies {
+            if enemy.room == cleared.room {
+                cmds.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+/// walks the party towards the room it's clearing.
+fn move_party(
+    mut party: Query<&mut Transform, With<PartyMarker>>,
+    floor: Res<DungeonFloor>,
+    progress: Res<PartyProgress>,
+    time: Res<Time>,
+) {
+    let Some(room) = floor.rooms.get(progress.room) else {
+        return;
+    };
+    let target = room_position(room.cell) + Vec3::Y * 0.3;
+    let ease = 1.0 - (-PARTY_SMOOTHING * time.delta_secs()).exp();
+
+    for mut transform in &mut party {
+        transform.translation = transform.translation.lerp(target, ease);
+    }
+}
+
+fn follow_party(party: Query<&Transform, With<PartyMarker>>, mut cameras: Query<&mut OrbitCamera>) {
+    let Ok(party) = party.single() else {
+        return;
+    };
+
+    for mut camera in &mut cameras {
+        camera.focus = party.translation;
+    }
+}
@@ -0,0 +1,242 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    big_num::BigNum,
+    economy_plugin::{Currency, Generator, Upgrade, UpgradeEffect},
+    save_file::SaveFile,
+};
+
+/// why something couldn't be bought.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PurchaseError {
+    TooExpensive { currency: Currency, cost: BigNum },
+    AlreadyOwned(Upgrade),
+}
+
+impl Display for PurchaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooExpensive { currency, cost } => {
+                write!(f, "not enough {currency}, it costs {cost}")
+            }
+            Self::AlreadyOwned(upgrade) => write!(f, "{upgrade} has already been bought"),
+        }
+    }
+}
+
+impl std::error::Error for PurchaseError {}
+
+/// every currency the player has, & everything that produces them.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ledger {
+    pub balances: BTreeMap<Currency, BigNum>,
+    /// everything earned since the ledger was started, spending doesn't take away from it.
+    pub earned: BTreeMap<Currency, BigNum>,
+    /// how many of each generator are owned.
+    pub generators: BTreeMap<Generator, u64>,
+    pub upgrades: BTreeSet<Upgrade>,
+    /// when the ledger was last saved, in seconds since the unix epoch.
+    pub saved_at: Option<u64>,
+}
+
+impl Default for Ledger {
+    fn default() -> Self {
+        Self {
+            balances: Currency::ALL
+                .into_iter()
+                .map(|currency| (currency, currency.starting_balance()))
+                .collect(),
+            earned: BTreeMap::new(),
+            generators: BTreeMap::new(),
+            upgrades: BTreeSet::new(),
+            saved_at: None,
+        }
+    }
+}
+
+impl Ledger {
+    pub fn balance(&self, currency: Currency) -> BigNum {
+        self.balances.get(&currency).copied().unwrap_or_default()
+    }
+
+    pub fn owned(&self, generator: Generator) -> u64 {
+        self.generators.get(&generator).copied().unwrap_or_default()
+    }
+
+    /// what the next `generator` costs, the price grows with each one owned.
+    pub fn generator_cost(&self, generator: Generator) -> BigNum {
+        BigNum::from(generator.base_cost())
+            * BigNum::from(generator.cost_growth()).powf(self.owned(generator) as f64)
+    }
+
+    /// how much the bought upgrades scale `generator`'s output by.
+    pub fn multiplier(&self, generator: Generator) -> f64 {
+        self.upgrades
+            .iter()
+            .map(|upgrade| match upgrade.effect() {
+                UpgradeEffect::Generator(target, multiplier) if target == generator => multiplier,
+                UpgradeEffect::Currency(target, multiplier) if target == generator.currency() => {
+                    multiplier
+                }
+                _ => 1.0,
+            })
+            .product()
+    }
+
+    /// what all of the owned `generator`s make each second at `automation_speed`.
+    pub fn output(&self, generator: Generator, automation_speed: f64) -> BigNum {
+        BigNum::from(self.owned(generator))
+            * (generator.base_output() * self.multiplier(generator) * automation_speed)
+    }
+
+    /// each currency made per second at `automation_speed`, with everything scaled by
+    /// `multiplier`. nothing changes between purchases, so `income * secs` is exactly what that
+    /// many seconds of production earns.
+    pub fn income(&self, automation_speed: f64, multiplier: f64) -> BTreeMap<Currency, BigNum> {
+        let mut income: BTreeMap<Currency, BigNum> = Currency::ALL
+            .into_iter()
+            .map(|currency| (currency, BigNum::ZERO))
+            .collect();
+
+        for generator in Generator::ALL {
+            *income.entry(generator.currency()).or_default() +=
+                self.output(generator, automation_speed) * multiplier;
+        }
+
+        income
+    }
+
+    /// adds `secs` seconds worth of `income`.
+    pub fn earn(&mut self, income: &BTreeMap<Currency, BigNum>, secs: f64) {
+        for (currency, per_sec) in income {
+            let amount = *per_sec * secs;
+
+            *self.balances.entry(*currency).or_default() += amount;
+            *self.earned.entry(*currency).or_default() += amount;
+        }
+    }
+
+    fn pay(&mut self, currency: Currency, cost: BigNum) -> Result<(), PurchaseError> {
+        let balance = self.balances.entry(currency).or_default();
+
+        if *balance < cost {
+            return Err(PurchaseError::TooExpensive { currency, cost });
+        }
+
+        *balance -= cost;
+
+        Ok(())
+    }
+
+    pub fn buy_generator(&mut self, generator: Generator) -> Result<(), PurchaseError> {
+        self.pay(generator.cost_currency(), self.generator_cost(generator))?;
+        *self.generators.entry(generator).or_default() += 1;
+
+        Ok(())
+    }
+
+    pub fn buy_upgrade(&mut self, upgrade: Upgrade) -> Result<(), PurchaseError> {
+        if self.upgrades.contains(&upgrade) {
+            return Err(PurchaseError::AlreadyOwned(upgrade));
+        }
+
+        let (currency, cost) = upgrade.cost();
+        self.pay(currency, cost)?;
+        self.upgrades.insert(upgrade);
+
+        Ok(())
+    }
+}
+
+impl SaveFile for Ledger {
+    const NAME: &'static str = "the ledger";
+    const PATH: &'static str = "saves/ledger.json";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: BigNum, expected: f64) {
+        assert!(
+            (actual.to_f64() - expected).abs() < 1e-9 * expected.abs().max(1.0),
+            "{} != {expected}",
+            actual.to_f64()
+        );
+    }
+
+    #[test]
+    fn buying_a_generator_pays_and_raises_the_price() {
+        let mut ledger = Ledger::default();
+
+        assert_eq!(ledger.buy_generator(Generator::Prospector), Ok(()));
+        assert_close(ledger.balance(Currency::Gold), 0.0);
+        assert_eq!(ledger.owned(Generator::Prospector), 1);
+        assert_close(ledger.generator_cost(Generator::Prospector), 11.5);
+        assert_eq!(
+            ledger.buy_generator(Generator::Prospector),
+            Err(PurchaseError::TooExpensive {
+                currency: Currency::Gold,
+                cost: BigNum::from(11.5),
+            })
+        );
+        assert_eq!(ledger.owned(Generator::Prospector), 1);
+    }
+
+    #[test]
+    fn upgrades_can_only_be_bought_once() {
+        let mut ledger = Ledger::default();
+        ledger.balances.insert(Currency::Gold, BigNum::from(250.0));
+
+        assert_eq!(ledger.buy_upgrade(Upgrade::SharperPicks), Ok(()));
+        assert_close(ledger.balance(Currency::Gold), 150.0);
+        assert_eq!(
+            ledger.buy_upgrade(Upgrade::SharperPicks),
+            Err(PurchaseError::AlreadyOwned(Upgrade::SharperPicks))
+        );
+        assert_close(ledger.balance(Currency::Gold), 150.0);
+    }
+
+    #[test]
+    fn upgrades_stack_on_output() {
+        let mut ledger = Ledger::default();
+        ledger.generators.insert(Generator::Prospector, 4);
+        ledger.upgrades.insert(Upgrade::SharperPicks);
+        ledger.upgrades.insert(Upgrade::GildedVaults);
+
+        assert_eq!(ledger.multiplier(Generator::Prospector), 3.0);
+        assert_eq!(ledger.multiplier(Generator::Alchemist), 1.0);
+        // 4 prospectors making 0.5 each, x2 & x1.5, at 2 Hz.
+        assert_close(ledger.output(Generator::Prospector, 2.0), 12.0);
+    }
+
+    #[test]
+    fn income_covers_every_currency() {
+        let mut ledger = Ledger::default();
+        ledger.generators.insert(Generator::Merchant, 2);
+
+        let income = ledger.income(1.0, 0.5);
+
+        assert_close(income[&Currency::Gold], 4.0);
+        assert_close(income[&Currency::Essence], 0.0);
+    }
+
+    #[test]
+    fn earning_adds_to_the_balance_and_what_was_earned() {
+        let mut ledger = Ledger::default();
+        let income = BTreeMap::from([(Currency::Gold, BigNum::from(3.0))]);
+
+        ledger.earn(&income, 10.0);
+        ledger.buy_generator(Generator::Prospector).unwrap();
+
+        assert_close(ledger.balance(Currency::Gold), 30.0);
+        assert_close(ledger.earned[&Currency::Gold], 30.0);
+    }
+}
@@ -0,0 +1,342 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    AutomationSpeed,
+    base_plugin::{AutomationStates, PauseStates, UiCommand},
+    big_num::BigNum,
+    economy_plugin::ledger::Ledger,
+    idle_time_plugin::should_automate,
+    prestige_plugin::Prestiged,
+    save_file::{SaveFile, SavePath},
+};
+
+pub mod ledger;
+
+/// how often the ledger is saved, besides on exit & after prestiging. it changes every tick &
+/// offline progress counts from its last save, so it's saved more often than the other files.
+pub const LEDGER_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+/// the automation speed generators run at while the game is closed.
+pub const OFFLINE_AUTOMATION_SPEED: f64 = 1.0;
+/// the most time away that's paid out as offline progress.
+pub const MAX_OFFLINE_TIME: Duration = Duration::from_secs(12 * 60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Currency {
+    Gold,
+    Essence,
+}
+
+impl Currency {
+    pub const ALL: [Self; 2] = [Self::Gold, Self::Essence];
+
+    /// how much of the currency a new game starts with, enough for the first generator.
+    pub fn starting_balance(&self) -> BigNum {
+        match self {
+            Self::Gold => BigNum::from(10.0),
+            Self::Essence => BigNum::ZERO,
+        }
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Gold => "gold",
+            Self::Essence => "essence",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// something that makes a currency while the party is automating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Generator {
+    Prospector,
+    Merchant,
+    Mint,
+    Alchemist,
+    Shrine,
+}
+
+impl Generator {
+    pub const ALL: [Self; 5] = [
+        Self::Prospector,
+        Self::Merchant,
+        Self::Mint,
+        Self::Alchemist,
+        Self::Shrine,
+    ];
+
+    /// the currency the generator makes.
+    pub fn currency(&self) -> Currency {
+        match self {
+            Self::Prospector | Self::Merchant | Self::Mint => Currency::Gold,
+            Self::Alchemist | Self::Shrine => Currency::Essence,
+        }
+    }
+
+    /// what one generator makes per second at an automation speed of 1 Hz.
+    pub fn base_output(&self) -> f64 {
+        match self {
+            Self::Prospector => 0.5,
+            Self::Merchant => 4.0,
+            Self::Mint => 30.0,
+            Self::Alchemist => 0.2,
+            Self::Shrine => 2.0,
+        }
+    }
+
+    /// the currency the generator is bought with.
+    pub fn cost_currency(&self) -> Currency {
+        match self {
+            Self::Prospector | Self::Merchant | Self::Mint | Self::Alchemist => Currency::Gold,
+            Self::Shrine => Currency::Essence,
+        }
+    }
+
+    /// the price of the first one.
+    pub fn base_cost(&self) -> f64 {
+        match self {
+            Self::Prospector => 10.0,
+            Self::Merchant => 120.0,
+            Self::Mint => 1_500.0,
+            Self::Alchemist => 500.0,
+            Self::Shrine => 50.0,
+        }
+    }
+
+    /// how much the price is multiplied by for each one owned.
+    pub fn cost_growth(&self) -> f64 {
+        match self {
+            Self::Prospector | Self::Merchant => 1.15,
+            Self::Mint => 1.17,
+            Self::Alchemist | Self::Shrine => 1.2,
+        }
+    }
+}
+
+impl Display for Generator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Prospector => "prospector",
+            Self::Merchant => "merchant",
+            Self::Mint => "mint",
+            Self::Alchemist => "alchemist",
+            Self::Shrine => "shrine",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// what buying an upgrade does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UpgradeEffect {
+    /// multiplies one generator's output.
+    Generator(Generator, f64),
+    /// multiplies the output of every generator making the currency.
+    Currency(Currency, f64),
+}
+
+impl Display for UpgradeEffect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Generator(generator, multiplier) => write!(f, "x{multiplier} {generator} output"),
+            Self::Currency(currency, multiplier) => write!(f, "x{multiplier} {currency} income"),
+        }
+    }
+}
+
+/// a one off purchase that makes the generators better.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Upgrade {
+    SharperPicks,
+    TradeRoutes,
+    MintPresses,
+    GildedVaults,
+    Distillation,
+    LeyLines,
+}
+
+impl Upgrade {
+    pub const ALL: [Self; 6] = [
+        Self::SharperPicks,
+        Self::TradeRoutes,
+        Self::MintPresses,
+        Self::GildedVaults,
+        Self::Distillation,
+        Self::LeyLines,
+    ];
+
+    pub fn cost(&self) -> (Currency, BigNum) {
+        let (currency, cost) = match self {
+            Self::SharperPicks => (Currency::Gold, 100.0),
+            Self::TradeRoutes => (Currency::Gold, 1_000.0),
+            Self::MintPresses => (Currency::Gold, 15_000.0),
+            Self::GildedVaults => (Currency::Essence, 25.0),
+            Self::Distillation => (Currency::Gold, 5_000.0),
+            Self::LeyLines => (Currency::Essence, 250.0),
+        };
+
+        (currency, BigNum::from(cost))
+    }
+
+    pub fn effect(&self) -> UpgradeEffect {
+        match self {
+            Self::SharperPicks => UpgradeEffect::Generator(Generator::Prospector, 2.0),
+            Self::TradeRoutes => UpgradeEffect::Generator(Generator::Merchant, 2.0),
+            Self::MintPresses => UpgradeEffect::Generator(Generator::Mint, 2.0),
+            Self::GildedVaults => UpgradeEffect::Currency(Currency::Gold, 1.5),
+            Self::Distillation => UpgradeEffect::Generator(Generator::Alchemist, 2.0),
+            Self::LeyLines => UpgradeEffect::Currency(Currency::Essence, 1.5),
+        }
+    }
+}
+
+impl Display for Upgrade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::SharperPicks => "sharper picks",
+            Self::TradeRoutes => "trade routes",
+            Self::MintPresses => "mint presses",
+            Self::GildedVaults => "gilded vaults",
+            Self::Distillation => "distillation",
+            Self::LeyLines => "ley lines",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// each currency made per second at the current automation speed.
+#[derive(Resource, Debug, Clone, PartialEq, Default, Deref)]
+pub struct Income(pub BTreeMap<Currency, BigNum>);
+
+/// scales everything the generators make, on top of their upgrades.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Deref, DerefMut)]
+pub struct IncomeMultiplier(pub f64);
+
+impl Default for IncomeMultiplier {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// what the generators made while the game was closed.
+#[derive(Resource, Debug, Clone, PartialEq, Default)]
+pub struct OfflineProgress {
+    pub secs: f64,
+    pub earned: BTreeMap<Currency, BigNum>,
+}
+
+pub fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+pub struct EconomyPlugin;
+
+impl Plugin for EconomyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SavePath<Ledger>>();
+        app.init_resource::<Income>();
+        app.init_resource::<IncomeMultiplier>();
+        app.add_systems(PreStartup, load_ledger);
+        app.add_systems(PostStartup, pay_offline_progress);
+        app.add_systems(
+            Update,
+            (
+                buy.run_if(on_message::<UiCommand>),
+                update_income,
+                produce
+                    .run_if(in_state(AutomationStates::Automation))
+                    .run_if(in_state(PauseStates::Running))
+                    .run_if(should_automate),
+            )
+                .chain(),
+        );
+        app.add_systems(
+            Last,
+            save_ledger.run_if(
+                on_timer(LEDGER_AUTOSAVE_INTERVAL)
+                    .or(on_message::<AppExit>)
+                    .or(on_message::<Prestiged>),
+            ),
+        );
+    }
+}
+
+fn load_ledger(mut cmds: Commands, save_path: Res<SavePath<Ledger>>) {
+    let ledger = Ledger::load_or_default(&save_path.path);
+
+    cmds.insert_resource(ledger);
+}
+
+/// pays out whatever the generators made since the ledger was last saved, once every bonus to
+/// income has been applied.
+fn pay_offline_progress(
+    mut cmds: Commands,
+    mut ledger: ResMut<Ledger>,
+    multiplier: Res<IncomeMultiplier>,
+) {
+    let away = ledger
+        .saved_at
+        .map(|saved_at| unix_secs(SystemTime::now()).saturating_sub(saved_at))
+        .map(Duration::from_secs)
+        .unwrap_or_default()
+        .min(MAX_OFFLINE_TIME);
+    let income = ledger.income(OFFLINE_AUTOMATION_SPEED, **multiplier);
+    let secs = away.as_secs_f64();
+    let earned = income
+        .iter()
+        .map(|(currency, per_sec)| (*currency, *per_sec * secs))
+        .collect();
+
+    ledger.earn(&income, secs);
+    cmds.insert_resource(OfflineProgress { secs, earned });
+}
+
+fn buy(mut commands: MessageReader<UiCommand>, mut ledger: ResMut<Ledger>) {
+    for command in commands.read() {
+        let result = match *command {
+            UiCommand::BuyGenerator(generator) => ledger.buy_generator(generator),
+            UiCommand::BuyUpgrade(upgrade) => ledger.buy_upgrade(upgrade),
+            _ => continue,
+        };
+
+        if let Err(e) = result {
+            warn!("{e}");
+        }
+    }
+}
+
+fn update_income(
+    ledger: Res<Ledger>,
+    automation_speed: Res<AutomationSpeed>,
+    multiplier: Res<IncomeMultiplier>,
+    mut income: ResMut<Income>,
+) {
+    income.set_if_neq(Income(ledger.income(**automation_speed, **multiplier)));
+}
+
+fn produce(income: Res<Income>, mut ledger: ResMut<Ledger>, time: Res<Time>) {
+    ledger.earn(&income, time.delta_secs_f64());
+}
+
+fn save_ledger(mut ledger: ResMut<Ledger>, save_path: Res<SavePath<Ledger>>) {
+    ledger.saved_at = Some(unix_secs(SystemTime::now()));
+
+    ledger.save_or_log(&save_path.path);
+}
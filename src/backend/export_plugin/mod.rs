@@ -0,0 +1,511 @@
+use std::{
+    fmt::Display,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::Serialize;
+
+use crate::backend::{
+    CurrentIdleTimeSeconds, LongestIdleTimeSeconds,
+    base_plugin::{StartTime, UiCommand},
+    idle_time_plugin::{
+        AutomationSpeedHistory, IdleTimeHistory,
+        history::{HistoryRange, SampleHistory, TimeSample},
+    },
+};
+
+/// the directory exports are written to, relative to the working directory.
+pub const EXPORT_DIR: &str = "exports";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum ExportFormat {
+    Csv,
+    #[default]
+    Json,
+}
+
+impl Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Csv => write!(f, "csv"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Resource)]
+pub struct ExportSettings {
+    pub dir: PathBuf,
+    /// the format to export the session in when the app exits, `None` (the default) disables
+    /// exporting on exit.
+    pub on_exit: Option<ExportFormat>,
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from(EXPORT_DIR),
+            on_exit: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ExportedSample {
+    /// seconds since the start of the session.
+    pub seconds: f64,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportedHistory {
+    pub raw: Vec<ExportedSample>,
+    pub seconds: Vec<ExportedSample>,
+    pub minutes: Vec<ExportedSample>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SessionAggregates {
+    pub session_length_secs: f64,
+    pub current_idle_time_secs: f64,
+    pub longest_idle_time_secs: f64,
+    pub average_idle_time_secs: f64,
+    pub average_automation_speed: f64,
+    pub peak_automation_speed: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionExport {
+    pub session: SessionAggregates,
+    pub idle_time: ExportedHistory,
+    pub automation_speed: ExportedHistory,
+}
+
+impl ExportedHistory {
+    fn new<S>(history: &SampleHistory<S>, start: Instant) -> Self
+    where
+        S: TimeSample + Send + Sync + 'static,
+    {
+        let export = |samples: &mut dyn Iterator<Item = S>| {
+            samples
+                .map(|sample| ExportedSample {
+                    seconds: sample.when().saturating_duration_since(start).as_secs_f64(),
+                    value: sample.value(),
+                })
+                .collect()
+        };
+
+        Self {
+            raw: export(&mut history.raw()),
+            seconds: export(&mut history.seconds()),
+            minutes: export(&mut history.minutes()),
+        }
+    }
+
+    fn tiers(&self) -> [(&'static str, &[ExportedSample]); 3] {
+        [
+            ("raw", &self.raw),
+            ("seconds", &self.seconds),
+            ("minutes", &self.minutes),
+        ]
+    }
+}
+
+impl SessionExport {
+    pub fn new(
+        start_time: &StartTime,
+        idle_time: &CurrentIdleTimeSeconds,
+        longest_idle_time: &LongestIdleTimeSeconds,
+        idle_history: &IdleTimeHistory,
+        speed_history: &AutomationSpeedHistory,
+    ) -> Self {
+        let average = |samples: Vec<f64>| {
+            if samples.is_empty() {
+                0.0
+            } else {
+                samples.iter().sum::<f64>() / samples.len() as f64
+            }
+        };
+        let idle_values = idle_history
+            .samples(HistoryRange::Session)
+            .iter()
+            .map(TimeSample::value)
+            .collect();
+        let speed_values = speed_history
+            .samples(HistoryRange::Session)
+            .iter()
+            .map(TimeSample::value)
+            .collect();
+        let peak_automation_speed = speed_history
+            .raw()
+            .chain(speed_history.seconds())
+            .chain(speed_history.minutes())
+            .map(|sample| sample.value())
+            .fold(0.0, f64::max);
+
+        Self {
+            session: SessionAggregates {
+                session_length_secs: start_time.0.elapsed().as_secs_f64(),
+                current_idle_time_secs: **idle_time,
+                longest_idle_time_secs: **longest_idle_time,
+                average_idle_time_secs: average(idle_values),
+                average_automation_speed: average(speed_values),
+                peak_automation_speed,
+            },
+            idle_time: ExportedHistory::new(idle_history, start_time.0),
+            automation_speed: ExportedHistory::new(speed_history, start_time.0),
+        }
+    }
+
+    /// writes the export into `dir`, returning the paths of the files written. JSON exports are
+    /// a single file, CSV exports are split into a samples file & a session aggregates file.
+    pub fn write(&self, dir: &Path, format: ExportFormat) -> io::Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)?;
+
+        // millisecond timestamps, so exports made in the same second don't overwrite each other.
+        let stem = format!(
+            "session-{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|time| time.as_millis())
+                .unwrap_or_default()
+        );
+
+        match format {
+            ExportFormat::Json => {
+                let path = dir.join(format!("{stem}.json"));
+                let file = fs::File::create(&path)?;
+                serde_json::to_writer_pretty(io::BufWriter::new(file), self)?;
+
+                Ok(vec![path])
+            }
+            ExportFormat::Csv => {
+                let samples_path = dir.join(format!("{stem}-samples.csv"));
+                let mut samples = io::BufWriter::new(fs::File::create(&samples_path)?);
+                writeln!(samples, "series,tier,seconds,value")?;
+
+                for (series, history) in [
+                    ("idle_time", &self.idle_time),
+                    ("automation_speed", &self.automation_speed),
+                ] {
+                    for (tier, tier_samples) in history.tiers() {
+                        for sample in tier_samples {
+                            writeln!(
+                                samples,
+                                "{series},{tier},{},{}",
+                                sample.seconds, sample.value
+                            )?;
+                        }
+                    }
+                }
+
+                let session_path = dir.join(format!("{stem}-session.csv"));
+                let mut session = io::BufWriter::new(fs::File::create(&session_path)?);
+                let aggregates = &self.session;
+                writeln!(session, "metric,value")?;
+
+                for (metric, value) in [
+                    ("session_length_secs", aggregates.session_length_secs),
+                    ("current_idle_time_secs", aggregates.current_idle_time_secs),
+                    ("longest_idle_time_secs", aggregates.longest_idle_time_secs),
+                    ("average_idle_time_secs", aggregates.average_idle_time_secs),
+                    (
+                        "average_automation_speed",
+                        aggregates.average_automation_speed,
+                    ),
+                    ("peak_automation_speed", aggregates.peak_automation_speed),
+                ] {
+                    writeln!(session, "{metric},{value}")?;
+                }
+
+                samples.flush()?;
+                session.flush()?;
+
+                Ok(vec![samples_path, session_path])
+            }
+        }
+    }
+}
+
+pub struct ExportPlugin;
+
+impl Plugin for ExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ExportSettings>();
+        app.add_systems(
+            Update,
+            (export_on_request, set_export_on_exit).run_if(on_message::<UiCommand>),
+        );
+        app.add_systems(Last, export_on_exit.run_if(on_message::<AppExit>));
+    }
+}
+
+/// everything a `SessionExport` is built from.
+#[derive(SystemParam)]
+struct SessionData<'w> {
+    settings: Res<'w, ExportSettings>,
+    start_time: Res<'w, StartTime>,
+    idle_time: Res<'w, CurrentIdleTimeSeconds>,
+    longest_idle_time: Res<'w, LongestIdleTimeSeconds>,
+    idle_history: Res<'w, IdleTimeHistory>,
+    speed_history: Res<'w, AutomationSpeedHistory>,
+}
+
+impl SessionData<'_> {
+    fn export(&self, format: ExportFormat) {
+        let export = SessionExport::new(
+            &self.start_time,
+            &self.idle_time,
+            &self.longest_idle_time,
+            &self.idle_history,
+            &self.speed_history,
+        );
+
+        match export.write(&self.settings.dir, format) {
+            Ok(paths) => {
+                for path in paths {
+                    info!("exported session {format} to {}", path.display());
+                }
+            }
+            Err(e) => error!("failed to export session as {format}: {e}"),
+        }
+    }
+}
+
+fn export_on_request(mut commands: MessageReader<UiCommand>, session: SessionData) {
+    for command in commands.read() {
+        if let UiCommand::Export(format) = command {
+            session.export(*format);
+        }
+    }
+}
+
+fn set_export_on_exit(
+    mut commands: MessageReader<UiCommand>,
+    mut settings: ResMut<ExportSettings>,
+) {
+    for command in commands.read() {
+        if let UiCommand::SetExportOnExit(on_exit) = command {
+            settings.on_exit = *on_exit;
+        }
+    }
+}
+
+fn export_on_exit(session: SessionData) {
+    if let Some(format) = session.settings.on_exit {
+        session.export(format);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{
+        idle_time_plugin::{AutomationSpeedSample, IdleTimeSample},
+        temp_path,
+    };
+
+    fn session() -> (
+        StartTime,
+        CurrentIdleTimeSeconds,
+        LongestIdleTimeSeconds,
+        IdleTimeHistory,
+        AutomationSpeedHistory,
+    ) {
+        let mut idle_history = IdleTimeHistory::default();
+        let mut speed_history = AutomationSpeedHistory::default();
+
+        for value in [1.0, 2.0, 3.0] {
+            idle_history.push(IdleTimeSample::new(value));
+        }
+
+        for value in [0.5, 1.5] {
+            speed_history.push(AutomationSpeedSample::new(value));
+        }
+
+        (
+            StartTime(Instant::now()),
+            CurrentIdleTimeSeconds(3.0),
+            LongestIdleTimeSeconds(4.0),
+            idle_history,
+            speed_history,
+        )
+    }
+
+    fn export() -> SessionExport {
+        let (start_time, idle_time, longest_idle_time, idle_history, speed_history) = session();
+
+        SessionExport::new(
+            &start_time,
+            &idle_time,
+            &longest_idle_time,
+            &idle_history,
+            &speed_history,
+        )
+    }
+
+    /// the files in `dir` with their contents, empty if there's no `dir`.
+    fn written(dir: &Path) -> Vec<(String, String)> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut files: Vec<(String, String)> = entries
+            .map(|entry| entry.unwrap().path())
+            .map(|path| {
+                (
+                    path.file_name().unwrap().to_string_lossy().into_owned(),
+                    fs::read_to_string(&path).unwrap(),
+                )
+            })
+            .collect();
+
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn aggregates_summarise_the_session() {
+        let aggregates = export().session;
+
+        assert_eq!(aggregates.current_idle_time_secs, 3.0);
+        assert_eq!(aggregates.longest_idle_time_secs, 4.0);
+        assert_eq!(aggregates.average_idle_time_secs, 2.0);
+        assert_eq!(aggregates.average_automation_speed, 1.0);
+        assert_eq!(aggregates.peak_automation_speed, 1.5);
+    }
+
+    #[test]
+    fn json_exports_everything_in_one_file() {
+        let dir = temp_path("export/json");
+        let export = export();
+        let paths = export.write(&dir, ExportFormat::Json).unwrap();
+
+        assert_eq!(paths.len(), 1);
+        assert!(paths[0].to_string_lossy().ends_with(".json"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&paths[0]).unwrap()).unwrap();
+
+        assert_eq!(json["session"]["average_idle_time_secs"], 2.0);
+        assert_eq!(json["session"]["peak_automation_speed"], 1.5);
+
+        let idle_values: Vec<f64> = json["idle_time"]["raw"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|sample| sample["value"].as_f64().unwrap())
+            .collect();
+
+        assert_eq!(idle_values, vec![1.0, 2.0, 3.0]);
+        assert_eq!(json["automation_speed"]["raw"].as_array().unwrap().len(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn csv_exports_samples_and_aggregates_separately() {
+        let dir = temp_path("export/csv");
+        let export = export();
+        let paths = export.write(&dir, ExportFormat::Csv).unwrap();
+        let [samples, session] = &paths[..] else {
+            panic!("expected 2 files, got {paths:?}");
+        };
+
+        assert!(samples.to_string_lossy().ends_with("-samples.csv"));
+        assert!(session.to_string_lossy().ends_with("-session.csv"));
+
+        let samples = fs::read_to_string(samples).unwrap();
+        let rows: Vec<(&str, &str, f64)> = samples
+            .lines()
+            .skip(1)
+            .map(|line| {
+                let fields: Vec<&str> = line.split(',').collect();
+
+                (fields[0], fields[1], fields[3].parse().unwrap())
+            })
+            .collect();
+
+        assert_eq!(samples.lines().next(), Some("series,tier,seconds,value"));
+        assert_eq!(
+            rows,
+            vec![
+                ("idle_time", "raw", 1.0),
+                ("idle_time", "raw", 2.0),
+                ("idle_time", "raw", 3.0),
+                // the buckets that are still open are exported with their average so far.
+                ("idle_time", "seconds", 2.0),
+                ("idle_time", "minutes", 2.0),
+                ("automation_speed", "raw", 0.5),
+                ("automation_speed", "raw", 1.5),
+                ("automation_speed", "seconds", 1.0),
+                ("automation_speed", "minutes", 1.0),
+            ]
+        );
+
+        let session = fs::read_to_string(session).unwrap();
+
+        assert_eq!(session.lines().next(), Some("metric,value"));
+        assert_eq!(session.lines().count(), 7);
+        assert!(
+            session
+                .lines()
+                .any(|line| line == "longest_idle_time_secs,4")
+        );
+        assert!(
+            session
+                .lines()
+                .any(|line| line == "average_idle_time_secs,2")
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn exports_on_exit_once_enabled() {
+        let dir = temp_path("export/on_exit");
+        let (start_time, idle_time, longest_idle_time, idle_history, speed_history) = session();
+        let mut app = App::new();
+
+        app.add_message::<UiCommand>();
+        app.insert_resource(start_time);
+        app.insert_resource(idle_time);
+        app.insert_resource(longest_idle_time);
+        app.insert_resource(idle_history);
+        app.insert_resource(speed_history);
+        app.add_plugins(ExportPlugin);
+        app.insert_resource(ExportSettings {
+            dir: dir.clone(),
+            on_exit: None,
+        });
+
+        app.world_mut().write_message(AppExit::Success);
+        app.update();
+
+        assert!(written(&dir).is_empty());
+
+        app.world_mut()
+            .write_message(UiCommand::SetExportOnExit(Some(ExportFormat::Csv)));
+        app.update();
+
+        assert_eq!(
+            app.world().resource::<ExportSettings>().on_exit,
+            Some(ExportFormat::Csv)
+        );
+        assert!(written(&dir).is_empty());
+
+        app.world_mut().write_message(AppExit::Success);
+        app.update();
+
+        let files = written(&dir);
+
+        assert_eq!(files.len(), 2);
+        assert!(files[0].0.ends_with("-samples.csv"));
+        assert!(files[1].0.ends_with("-session.csv"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,350 @@
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    time::{Duration, Instant, SystemTime},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::IDLE_SAMPLE_WINDOW;
+use crate::backend::{economy_plugin::unix_secs, save_file::SaveFile};
+
+/// how long the raw samples are kept for.
+pub const RAW_RETENTION: Duration = IDLE_SAMPLE_WINDOW;
+/// how long the 1 second averages are kept for.
+pub const SECOND_RETENTION: Duration = Duration::from_hours(1);
+/// the bucket size of the first tier of averages.
+pub const SECOND_BUCKET: Duration = Duration::from_secs(1);
+/// the bucket size of the second tier of averages, which are kept for the whole save.
+pub const MINUTE_BUCKET: Duration = Duration::from_mins(1);
+
+/// a timestamped value that can be stored in a `SampleHistory`.
+pub trait TimeSample: Copy {
+    fn when(&self) -> Instant;
+    fn value(&self) -> f64;
+    fn at(when: Instant, value: f64) -> Self;
+}
+
+/// the span of history a graph displays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum HistoryRange {
+    #[default]
+    Minute,
+    TenMinutes,
+    Hour,
+    Session,
+    AllTime,
+}
+
+impl HistoryRange {
+    pub const ALL: [Self; 5] = [
+        Self::Minute,
+        Self::TenMinutes,
+        Self::Hour,
+        Self::Session,
+        Self::AllTime,
+    ];
+
+    /// the fixed length of the range, if it has one.
+    pub fn window(&self) -> Option<Duration> {
+        match self {
+            Self::Minute => Some(Duration::from_mins(1)),
+            Self::TenMinutes => Some(Duration::from_mins(10)),
+            Self::Hour => Some(Duration::from_hours(1)),
+            Self::Session | Self::AllTime => None,
+        }
+    }
+}
+
+impl Display for HistoryRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Self::Minute => "1m",
+            Self::TenMinutes => "10m",
+            Self::Hour => "1h",
+            Self::Session => "session",
+            Self::AllTime => "all-time",
+        };
+
+        write!(f, "{label}")
+    }
+}
+
+/// a 1 minute average kept between sessions. it's timestamped with the wall clock, since an
+/// `Instant` means nothing once the game is closed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SavedSample {
+    /// seconds since the unix epoch.
+    pub at: u64,
+    pub value: f64,
+}
+
+/// the 1 minute averages of every history, from every session.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedHistories {
+    pub idle_time: Vec<SavedSample>,
+    pub automation_speed: Vec<SavedSample>,
+}
+
+impl SaveFile for SavedHistories {
+    const NAME: &'static str = "the histories";
+    const PATH: &'static str = "saves/history.json";
+}
+
+/// running average of the samples that fall into one bucket.
+#[derive(Debug, Clone, Copy, Default)]
+struct Bucket {
+    start: Option<Instant>,
+    sum: f64,
+    count: usize,
+}
+
+impl Bucket {
+    /// adds a sample to the bucket, returning the average of the previous bucket if `sample` is
+    /// the first one past the end of it.
+    fn push<S: TimeSample>(&mut self, sample: &S, size: Duration) -> Option<S> {
+        let flushed = match self.start {
+            Some(start) if sample.when().duration_since(start) >= size => {
+                let average = S::at(start, self.sum / self.count as f64);
+                *self = Self::default();
+                Some(average)
+            }
+            _ => None,
+        };
+
+        self.start.get_or_insert(sample.when());
+        self.sum += sample.value();
+        self.count += 1;
+
+        flushed
+    }
+
+    /// the average of the samples so far, `None` if the bucket is empty.
+    fn average<S: TimeSample>(&self) -> Option<S> {
+        self.start
+            .map(|start| S::at(start, self.sum / self.count as f64))
+    }
+}
+
+/// history of a sampled value with tiered retention: raw samples for `RAW_RETENTION`, 1 second
+/// averages for `SECOND_RETENTION` and 1 minute averages for the whole save, see `SavedHistories`.
+#[derive(Debug, Clone, Resource)]
+pub struct SampleHistory<S: TimeSample + Send + Sync + 'static> {
+    start: Instant,
+    /// the 1 minute averages of earlier sessions.
+    earlier: Vec<SavedSample>,
+    raw: VecDeque<S>,
+    seconds: VecDeque<S>,
+    minutes: Vec<S>,
+    second_bucket: Bucket,
+    minute_bucket: Bucket,
+}
+
+impl<S: TimeSample + Send + Sync + 'static> Default for SampleHistory<S> {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            earlier: Vec::new(),
+            raw: VecDeque::new(),
+            seconds: VecDeque::new(),
+            minutes: Vec::new(),
+            second_bucket: Bucket::default(),
+            minute_bucket: Bucket::default(),
+        }
+    }
+}
+
+impl<S: TimeSample + Send + Sync + 'static> SampleHistory<S> {
+    /// a new history carrying on from the 1 minute averages of earlier sessions.
+    pub fn with_earlier(earlier: Vec<SavedSample>) -> Self {
+        Self {
+            earlier,
+            ..default()
+        }
+    }
+
+    pub fn push(&mut self, sample: S) {
+        if let Some(average) = self.second_bucket.push(&sample, SECOND_BUCKET) {
+            self.seconds.push_back(average);
+        }
+
+        if let Some(average) = self.minute_bucket.push(&sample, MINUTE_BUCKET) {
+            self.minutes.push(average);
+        }
+
+        self.raw.push_back(sample);
+
+        while self
+            .raw
+            .front()
+            .is_some_and(|sample| sample.when().elapsed() > RAW_RETENTION)
+        {
+            self.raw.pop_front();
+        }
+
+        while self
+            .seconds
+            .front()
+            .is_some_and(|sample| sample.when().elapsed() > SECOND_RETENTION)
+        {
+            self.seconds.pop_front();
+        }
+    }
+
+    /// how many samples the history is holding, across all of its tiers.
+    pub fn len(&self) -> usize {
+        self.earlier.len() + self.raw.len() + self.seconds.len() + self.minutes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// when this history started recording.
+    pub fn start(&self) -> Instant {
+        self.start
+    }
+
+    /// the raw samples from the last `RAW_RETENTION`.
+    pub fn raw(&self) -> impl Iterator<Item = S> + '_ {
+        self.raw.iter().copied()
+    }
+
+    /// the 1 second averages from the last `SECOND_RETENTION`, ending with the average of the
+    /// second so far.
+    pub fn seconds(&self) -> impl Iterator<Item = S> + '_ {
+        self.seconds
+            .iter()
+            .copied()
+            .chain(self.second_bucket.average())
+    }
+
+    /// the 1 minute averages of this session, ending with the average of the minute so far.
+    pub fn minutes(&self) -> impl Iterator<Item = S> + '_ {
+        self.minutes
+            .iter()
+            .copied()
+            .chain(self.minute_bucket.average())
+    }
+
+    /// the 1 minute averages of the whole save, earlier sessions included, for saving.
+    pub fn saved(&self) -> Vec<SavedSample> {
+        let now = SystemTime::now();
+        let this_session = self.minutes().map(|sample| SavedSample {
+            at: unix_secs(now - sample.when().elapsed()),
+            value: sample.value(),
+        });
+
+        self.earlier.iter().copied().chain(this_session).collect()
+    }
+
+    /// the samples covering `range`, taken from the finest tier that still holds all of it.
+    pub fn samples(&self, range: HistoryRange) -> Vec<S> {
+        if range == HistoryRange::AllTime {
+            return self.earlier().chain(self.minutes()).collect();
+        }
+
+        // one `now` for the window & the samples' ages, so the session window can't end up
+        // shorter than its newest sample.
+        let now = Instant::now();
+        let window = range.window().unwrap_or(now.duration_since(self.start));
+        let tier: Box<dyn Iterator<Item = S>> = if window <= RAW_RETENTION {
+            Box::new(self.raw())
+        } else if window <= SECOND_RETENTION {
+            Box::new(self.seconds())
+        } else {
+            Box::new(self.minutes())
+        };
+
+        tier.filter(|sample| now.duration_since(sample.when()) <= window)
+            .collect()
+    }
+
+    /// the averages of earlier sessions as samples. ones from before the clock `Instant` uses
+    /// started (usually the last boot) are placed at the start of this session.
+    fn earlier(&self) -> impl Iterator<Item = S> + '_ {
+        let (now, now_secs) = (Instant::now(), unix_secs(SystemTime::now()));
+
+        self.earlier.iter().map(move |sample| {
+            let age = Duration::from_secs(now_secs.saturating_sub(sample.at));
+
+            S::at(now.checked_sub(age).unwrap_or(self.start), sample.value)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::idle_time_plugin::IdleTimeSample;
+
+    fn history(start: Instant, values: &[f64], every: Duration) -> SampleHistory<IdleTimeSample> {
+        let mut history = SampleHistory::default();
+
+        for (i, value) in values.iter().enumerate() {
+            history.push(IdleTimeSample::at(start + every * i as u32, *value));
+        }
+
+        history
+    }
+
+    #[test]
+    fn open_buckets_are_read() {
+        let start = Instant::now() - Duration::from_secs(3);
+        let history = history(start, &[1.0, 3.0, 5.0, 7.0], Duration::from_millis(500));
+
+        let seconds: Vec<f64> = history.seconds().map(|sample| sample.value()).collect();
+        let minutes: Vec<f64> = history.minutes().map(|sample| sample.value()).collect();
+
+        // the second that's still open is averaged with the closed ones.
+        assert_eq!(seconds, vec![2.0, 6.0]);
+        assert_eq!(minutes, vec![4.0]);
+        assert_eq!(
+            history
+                .samples(HistoryRange::TenMinutes)
+                .last()
+                .map(|sample| sample.value()),
+            Some(6.0)
+        );
+    }
+
+    #[test]
+    fn earlier_sessions_are_kept() {
+        let now = unix_secs(SystemTime::now());
+        let earlier = vec![
+            SavedSample {
+                at: now - 3_600,
+                value: 10.0,
+            },
+            SavedSample {
+                at: now - 3_540,
+                value: 20.0,
+            },
+        ];
+        let mut history = SampleHistory::<IdleTimeSample>::with_earlier(earlier.clone());
+
+        history.push(IdleTimeSample::new(30.0));
+
+        let all_time: Vec<f64> = history
+            .samples(HistoryRange::AllTime)
+            .iter()
+            .map(|sample| sample.value())
+            .collect();
+        let session: Vec<f64> = history
+            .samples(HistoryRange::Session)
+            .iter()
+            .map(|sample| sample.value())
+            .collect();
+
+        assert_eq!(all_time, vec![10.0, 20.0, 30.0]);
+        assert_eq!(session, vec![30.0]);
+
+        let saved = history.saved();
+
+        assert_eq!(saved[..2], earlier[..]);
+        assert_eq!(saved[2].value, 30.0);
+        assert!(saved[2].at >= now);
+    }
+}
@@ -0,0 +1,171 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use bevy::prelude::*;
+
+/// the most inputs `InputHistory` holds, enough for a full `TIME_WINDOW` of inputs at well over
+/// any rate a person can manage.
+pub const INPUT_HISTORY_CAPACITY: usize = 256;
+
+/// when recent inputs happened, oldest first. a fixed-capacity ring buffer, once it's full each new
+/// input pushes out the oldest one.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct InputHistory {
+    times: VecDeque<Instant>,
+    capacity: usize,
+}
+
+impl Default for InputHistory {
+    fn default() -> Self {
+        Self::with_capacity(INPUT_HISTORY_CAPACITY)
+    }
+}
+
+impl InputHistory {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            times: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// records an input. inputs are expected in the order they happen, an input from before the
+    /// latest one is counted as happening alongside it.
+    pub fn push(&mut self, when: Instant) {
+        let when = self.times.back().map_or(when, |latest| when.max(*latest));
+
+        if self.times.len() == self.capacity {
+            self.times.pop_front();
+        }
+
+        self.times.push_back(when);
+    }
+
+    /// forgets the inputs older than `window` as of `now`.
+    pub fn expire(&mut self, now: Instant, window: Duration) {
+        while self
+            .times
+            .front()
+            .is_some_and(|when| now.duration_since(*when) >= window)
+        {
+            self.times.pop_front();
+        }
+    }
+
+    /// how many inputs happened within `window` of `now`.
+    pub fn count(&self, now: Instant, window: Duration) -> usize {
+        self.times.len() - self.first_within(now, window)
+    }
+
+    /// the time between the oldest & latest inputs within `window` of `now`, spread over one more
+    /// interval than there were inputs, `None` if there weren't any. that's not quite the average
+    /// interval, but it's the number idle time growth was tuned against with the `KeyPress`
+    /// entities this replaced, so it's kept as is.
+    pub fn average_interval(&self, now: Instant, window: Duration) -> Option<Duration> {
+        let count = self.count(now, window);
+
+        if count == 0 {
+            return None;
+        }
+
+        let oldest = self.times[self.times.len() - count];
+        let latest = self.times[self.times.len() - 1];
+
+        Some((latest - oldest) / (count + 1) as u32)
+    }
+
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// the index of the oldest input within `window` of `now`.
+    fn first_within(&self, now: Instant, window: Duration) -> usize {
+        self.times
+            .partition_point(|when| now.duration_since(*when) >= window)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// how the `KeyPress` entities worked out the interval: newest first, with `now` pushed on
+    /// the end, summing the gaps (the one to `now` saturates to zero) over every press.
+    fn legacy_average_interval(presses: &[Instant], now: Instant, window: Duration) -> f64 {
+        let mut presses: Vec<Instant> = presses
+            .iter()
+            .copied()
+            .filter(|press| now.duration_since(*press) < window)
+            .collect();
+        presses.sort_by(|a, b| b.cmp(a));
+        presses.push(now);
+
+        let total: Duration = presses.windows(2).map(|pair| pair[0] - pair[1]).sum();
+
+        total.as_secs_f64() / presses.len() as f64
+    }
+
+    #[test]
+    fn average_interval_matches_the_key_press_entities() {
+        let now = Instant::now();
+        let window = Duration::from_secs(1);
+
+        for count in [1, 2, 3, 10, 100] {
+            let presses: Vec<Instant> = (0..count)
+                .map(|i| now - Duration::from_millis(900) * i / count)
+                .rev()
+                .collect();
+            let mut history = InputHistory::default();
+            presses.iter().for_each(|press| history.push(*press));
+
+            let interval = history.average_interval(now, window).unwrap();
+            let legacy = legacy_average_interval(&presses, now, window);
+
+            assert!(
+                (interval.as_secs_f64() - legacy).abs() < 1e-6,
+                "{count} inputs: {interval:?} vs {legacy}"
+            );
+        }
+    }
+
+    #[test]
+    fn average_interval_ignores_expired_inputs() {
+        let now = Instant::now();
+        let mut history = InputHistory::default();
+        history.push(now - Duration::from_secs(5));
+        history.push(now - Duration::from_millis(600));
+        history.push(now - Duration::from_millis(300));
+
+        assert_eq!(
+            history.average_interval(now, Duration::from_secs(1)),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            history.average_interval(now, Duration::from_millis(100)),
+            None
+        );
+    }
+
+    #[test]
+    fn full_history_drops_the_oldest_input() {
+        let now = Instant::now();
+        let mut history = InputHistory::with_capacity(2);
+
+        for millis in [300, 200, 100] {
+            history.push(now - Duration::from_millis(millis));
+        }
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.count(now, Duration::from_millis(250)), 2);
+    }
+}
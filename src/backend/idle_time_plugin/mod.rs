@@ -1,32 +1,55 @@
-use std::time::{Duration, Instant};
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
 
 use bevy::{
-    input::{keyboard::KeyboardInput, mouse::MouseButtonInput},
     prelude::*,
     time::common_conditions::on_timer,
-    window::{WindowFocused, WindowResized},
+    window::{WindowClosed, WindowFocused, WindowResized},
 };
-use crossbeam::channel::Sender;
 
 use crate::backend::{
-    AutomationSpeed, CurrentIdleTimeSeconds, LongestIdleTimeSeconds, base_plugin::AutomationStates,
+    AutomationSpeed, CurrentIdleTimeSeconds, LongestIdleTimeSeconds,
+    action_plugin::{Action, Activity},
+    base_plugin::{AutomationStates, PauseStates, UiCommand},
+    idle_time_plugin::{
+        history::{SampleHistory, SavedHistories, TimeSample},
+        input_history::InputHistory,
+    },
+    modifier::{Rate, Rates},
+    save_file::{SaveFile, SavePath},
 };
 
+pub mod history;
+pub mod input_history;
+
 pub const TIME_WINDOW: f64 = 1.0;
+/// the idle time growth rate before any modifiers, see `Rates`.
 pub const IDLE_TIME_GROWTH_RATE: f64 = 1.25;
+/// the automation speed growth rate before any modifiers, see `Rates`.
 pub const AUTOMATION_SPEED_GROWTH_RATE: f64 = 1.25;
 pub const IDLE_SAMPLE_WINDOW: Duration = Duration::from_mins(1);
+/// how often the histories are saved, besides on exit. only their 1 minute averages are kept, so
+/// saving more often gains nothing.
+pub const HISTORY_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Resource)]
 pub struct KeyCount(pub usize);
 
-// NOTE: consider changing to be more generic and allow for weighting of the input. ie. value mouse
-// input or battle order change events more then key-presses.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Component)]
-pub struct KeyPress(pub Instant);
+/// the focus of the app as a whole, across all of its windows.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Resource)]
+pub struct AppFocus {
+    pub focused_windows: HashSet<Entity>,
+    /// when the last of the app's windows lost focus, `None` while one of them is focused.
+    pub lost_at: Option<Instant>,
+}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Component)]
-pub struct LostFocusTimestamp(pub Instant);
+impl AppFocus {
+    pub fn is_focused(&self) -> bool {
+        !self.focused_windows.is_empty()
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Component)]
 pub struct IdleTimeSample {
@@ -52,11 +75,25 @@ impl From<f64> for AutomationSpeedSample {
     }
 }
 
-#[derive(Resource, Debug, Clone, Deref, DerefMut)]
-pub struct IdleTimeTx(pub Sender<IdleTimeSample>);
+pub type IdleTimeHistory = SampleHistory<IdleTimeSample>;
+pub type AutomationSpeedHistory = SampleHistory<AutomationSpeedSample>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Resource)]
+pub struct AutomationSettings {
+    /// whether losing window focus starts automating.
+    pub on_focus_loss: bool,
+    /// how long the window has to be out of focus before automating starts.
+    pub focus_loss_delay: Duration,
+}
 
-#[derive(Resource, Debug, Clone, Deref, DerefMut)]
-pub struct AutomationSpeedTx(pub Sender<AutomationSpeedSample>);
+impl Default for AutomationSettings {
+    fn default() -> Self {
+        Self {
+            on_focus_loss: true,
+            focus_loss_delay: Duration::from_secs(2),
+        }
+    }
+}
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd, Resource)]
 pub struct WResolution {
@@ -82,134 +119,136 @@ impl AutomationSpeedSample {
     }
 }
 
+impl TimeSample for IdleTimeSample {
+    fn when(&self) -> Instant {
+        self.when
+    }
+
+    fn value(&self) -> f64 {
+        self.time
+    }
+
+    fn at(when: Instant, time: f64) -> Self {
+        Self { when, time }
+    }
+}
+
+impl TimeSample for AutomationSpeedSample {
+    fn when(&self) -> Instant {
+        self.when
+    }
+
+    fn value(&self) -> f64 {
+        self.time
+    }
+
+    fn at(when: Instant, time: f64) -> Self {
+        Self { when, time }
+    }
+}
+
 /// a function to asertain if teh game should step the loaded automation
 pub fn should_automate(idle_time: Res<CurrentIdleTimeSeconds>, key_count: Res<KeyCount>) -> bool {
     **idle_time > 0.0 && key_count.0 == 0
 }
 
-fn automation_timer_done(last_lost_focus: Single<Option<&LostFocusTimestamp>>) -> bool {
-    last_lost_focus
-        .is_some_and(|focus_timer| focus_timer.0.elapsed() > Duration::from_secs_f64(2.0))
+fn automation_timer_done(settings: Res<AutomationSettings>, focus: Res<AppFocus>) -> bool {
+    settings.on_focus_loss
+        && focus
+            .lost_at
+            .is_some_and(|lost_at| lost_at.elapsed() > settings.focus_loss_delay)
 }
 
-pub struct IdleTimePlugin {
-    pub idle_tx: Sender<IdleTimeSample>,
-    pub speed_tx: Sender<AutomationSpeedSample>,
-}
+pub struct IdleTimePlugin;
 
 impl Plugin for IdleTimePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<KeyCount>();
+        app.init_resource::<InputHistory>();
         app.init_resource::<WResolution>();
         app.init_resource::<AutomationSpeed>();
+        app.init_resource::<AutomationSettings>();
+        app.init_resource::<Rates>();
+        app.init_resource::<AppFocus>();
+        app.init_resource::<SavePath<SavedHistories>>();
         app.insert_resource(CurrentIdleTimeSeconds(0.0));
         app.insert_resource(LongestIdleTimeSeconds(0.0));
-        app.insert_resource(IdleTimeTx(self.idle_tx.clone()));
-        app.insert_resource(AutomationSpeedTx(self.speed_tx.clone()));
+        app.add_systems(PreStartup, load_histories);
+        app.add_systems(Startup, init_window_focus);
         app.add_systems(
             Update,
             (
                 (
-                    (
-                        gather_kbd_input.run_if(on_message::<KeyboardInput>),
-                        gather_mouse_input.run_if(on_message::<MouseButtonInput>),
-                    ),
-                    // gather_mouse_input.run_if(on_message::<MouseButtonInput>),
+                    gather_activity.run_if(on_message::<Activity>),
                     step_inputs,
-                    (
-                        step_idle_time,
-                        step_automation_speed,
-                        // send_idle_time.run_if(on_timer(Duration::from_secs_f64(TIME_WINDOW * 0.5))),
-                        (
-                            send_time_sample::<CurrentIdleTimeSeconds, IdleTimeTx, IdleTimeSample>,
-                            send_time_sample::<
-                                AutomationSpeed,
-                                AutomationSpeedTx,
-                                AutomationSpeedSample,
-                            >,
-                        )
-                            .run_if(on_timer(Duration::from_secs_f64(TIME_WINDOW * 0.25))),
-                    ),
+                    (step_idle_time, step_automation_speed).run_if(in_state(PauseStates::Running)),
                 )
                     .chain(),
-                step_automating_timer,
+                track_window_focus,
                 start_automating
                     .run_if(in_state(AutomationStates::Manual))
+                    .run_if(in_state(PauseStates::Running))
                     .run_if(automation_timer_done),
-                (collect_idle_time_data, clean_idle_time_data)
-                    .chain()
-                    .run_if(on_timer(Duration::from_secs_f64(0.75))),
+                control_automation,
+                (
+                    record_time_sample::<CurrentIdleTimeSeconds, IdleTimeSample>,
+                    record_time_sample::<AutomationSpeed, AutomationSpeedSample>,
+                )
+                    .run_if(on_timer(Duration::from_secs_f64(TIME_WINDOW * 0.25))),
                 update_window_size.run_if(on_message::<WindowResized>),
             ),
         );
+        app.add_systems(
+            Last,
+            save_histories.run_if(on_timer(HISTORY_AUTOSAVE_INTERVAL).or(on_message::<AppExit>)),
+        );
     }
 }
 
-fn gather_kbd_input(mut cmds: Commands, mut keyboard_inputs: MessageReader<KeyboardInput>) {
-    for _input in keyboard_inputs.read() {
-        cmds.spawn(KeyPress(Instant::now()));
-    }
-}
+fn load_histories(mut cmds: Commands, save_path: Res<SavePath<SavedHistories>>) {
+    let saved = SavedHistories::load_or_default(&save_path.path);
 
-fn gather_mouse_input(mut cmds: Commands, mut mouse_inputs: MessageReader<MouseButtonInput>) {
-    for _input in mouse_inputs.read() {
-        cmds.spawn(KeyPress(Instant::now()));
-    }
+    cmds.insert_resource(IdleTimeHistory::with_earlier(saved.idle_time));
+    cmds.insert_resource(AutomationSpeedHistory::with_earlier(saved.automation_speed));
 }
 
-fn step_inputs(
-    mut cmds: Commands,
-    presses: Query<(Entity, &KeyPress)>,
-    mut key_count: ResMut<KeyCount>,
-) {
-    let mut presses: Vec<(Entity, &KeyPress)> = presses.into_iter().collect();
-    presses.retain(|(entity, press)| {
-        let is_old = press.0.elapsed() >= Duration::from_secs_f64(TIME_WINDOW);
+// NOTE: consider changing to be more generic and allow for weighting of the input. ie. value mouse
+// input or battle order change events more then key-presses.
+fn gather_activity(mut history: ResMut<InputHistory>, mut activity: MessageReader<Activity>) {
+    let now = Instant::now();
 
-        if is_old {
-            cmds.entity(*entity).despawn();
-        }
+    for _input in activity.read() {
+        history.push(now);
+    }
+}
 
-        !is_old
-    });
+fn step_inputs(mut history: ResMut<InputHistory>, mut key_count: ResMut<KeyCount>) {
+    history.expire(Instant::now(), Duration::from_secs_f64(TIME_WINDOW));
 
-    key_count.0 = presses.len();
+    key_count.0 = history.len();
 }
 
-fn step_idle_time(
+pub fn step_idle_time(
     key_count: Res<KeyCount>,
     mut idle_time: ResMut<CurrentIdleTimeSeconds>,
     mut longest_idle_time: ResMut<LongestIdleTimeSeconds>,
-    presses: Query<&KeyPress>,
+    history: Res<InputHistory>,
+    rates: Res<Rates>,
     time: Res<Time>,
 ) {
     if key_count.0 > 0 {
         // let input_rate = key_count.0 as f64 * TIME_WINDOW;
         // let increment_amount = input_rate * time.delta_secs_f64();
-        let mut presses: Vec<&KeyPress> = presses
-            .iter()
-            .sort_by::<&KeyPress>(|val1, val2| {
-                val1.0
-                    .elapsed()
-                    .as_secs_f64()
-                    .total_cmp(&val2.0.elapsed().as_secs_f64())
-                    .then(std::cmp::Ordering::Less)
-            })
-            .collect();
-        let now = KeyPress(Instant::now());
-        presses.push(&now);
-        let total_time_delta: Duration = presses
-            .windows(2)
-            .map(|presses| presses[0].0 - presses[1].0)
-            .sum();
-        let total_time_delta = total_time_delta.as_secs_f64();
-        let avg_press_time_delta = total_time_delta / presses.len() as f64;
+        let avg_press_time_delta = history
+            .average_interval(Instant::now(), Duration::from_secs_f64(TIME_WINDOW))
+            .unwrap_or_default()
+            .as_secs_f64();
 
         // if avg_press_time_delta > 0.0 {
         let avg_press_time_delta = 1.0 - avg_press_time_delta;
         let input_rate = avg_press_time_delta;
         // let compensater = 10.0;
-        let increment_amount = input_rate * IDLE_TIME_GROWTH_RATE * time.delta_secs_f64();
+        let increment_amount = input_rate * rates.get(Rate::IdleTimeGrowth) * time.delta_secs_f64();
 
         **idle_time += increment_amount;
 
@@ -228,9 +267,13 @@ fn step_idle_time(
     }
 }
 
-fn step_automation_speed(key_count: Res<KeyCount>, mut automation_speed: ResMut<AutomationSpeed>) {
+fn step_automation_speed(
+    key_count: Res<KeyCount>,
+    rates: Res<Rates>,
+    mut automation_speed: ResMut<AutomationSpeed>,
+) {
     if key_count.0 > 0 {
-        let speed = key_count.0 as f64 / TIME_WINDOW * AUTOMATION_SPEED_GROWTH_RATE;
+        let speed = key_count.0 as f64 / TIME_WINDOW * rates.get(Rate::AutomationSpeedGrowth);
 
         automation_speed.step_by(speed);
 
@@ -238,67 +281,106 @@ fn step_automation_speed(key_count: Res<KeyCount>, mut automation_speed: ResMut<
     }
 }
 
-// fn send_idle_time(idle_time: Res<CurrentIdleTimeSeconds>, time_sender: Res<IdleTimeTx>) {
-//     if let Err(e) = time_sender.send(IdleTimeSample::new(idle_time.0)) {
-//         error!("{e}");
-//     } else {
-//         info!("sent time, {} time samples in queue.", time_sender.len());
-//     }
-// }
-
-fn send_time_sample<C, T, S>(curent_time: Res<C>, time_sender: Res<T>)
-where
-    C: Resource + std::ops::Deref<Target = f64>,
-    T: Resource + std::ops::Deref<Target = Sender<S>>,
-    S: From<f64>,
-{
-    if let Err(e) = time_sender.send(S::from(*curent_time.deref())) {
-        error!("{e}");
-    }
-    // else {
-    //     info!("sent time, {} time samples in queue.", time_sender.len());
-    // }
+/// starts from the windows' own focus, since there's no `WindowFocused` for a window that opens
+/// without focus.
+fn init_window_focus(windows: Query<(Entity, &Window)>, mut focus: ResMut<AppFocus>) {
+    focus.focused_windows = windows
+        .iter()
+        .filter(|(_, window)| window.focused)
+        .map(|(entity, _)| entity)
+        .collect();
+    focus.lost_at = (!focus.is_focused()).then(Instant::now);
 }
 
-fn step_automating_timer(
-    mut cmds: Commands,
-    mut events: MessageReader<WindowFocused>,
-    last_lost_focus: Single<Option<(Entity, &LostFocusTimestamp)>>,
+/// keeps track of which of the app's windows are focused. the app only counts as having lost
+/// focus once none of them are, so moving between our own windows doesn't start automating.
+fn track_window_focus(
+    mut focus_events: MessageReader<WindowFocused>,
+    mut closed_events: MessageReader<WindowClosed>,
+    mut focus: ResMut<AppFocus>,
 ) {
-    for event in events.read() {
-        if !event.focused && last_lost_focus.is_none() {
-            cmds.spawn(LostFocusTimestamp(Instant::now()));
-        } else if let Some((entity, _lost_focus)) = *last_lost_focus
-            && !event.focused
-        {
-            cmds.spawn(LostFocusTimestamp(Instant::now()));
-            cmds.entity(entity).despawn();
-        } else if let Some((entity, _lost_focus)) = *last_lost_focus
-            && event.focused
-        {
-            cmds.entity(entity).despawn();
+    let was_focused = focus.is_focused();
+
+    for event in focus_events.read() {
+        if event.focused {
+            focus.focused_windows.insert(event.window);
+        } else {
+            focus.focused_windows.remove(&event.window);
         }
     }
+
+    for event in closed_events.read() {
+        focus.focused_windows.remove(&event.window);
+    }
+
+    if focus.is_focused() {
+        focus.lost_at = None;
+    } else if was_focused {
+        focus.lost_at = Some(Instant::now());
+    }
 }
 
 fn start_automating(mut automation_state: ResMut<NextState<AutomationStates>>) {
     automation_state.set(AutomationStates::Automation);
 }
 
-fn collect_idle_time_data(mut cmds: Commands, idle_time: Res<CurrentIdleTimeSeconds>) {
-    cmds.spawn(IdleTimeSample::new(idle_time.0));
-    // debug!("spawned idle time sample: {}", idle_time.0);
-}
+/// the player's manual automation controls.
+fn control_automation(
+    mut commands: MessageReader<UiCommand>,
+    actions: Res<ButtonInput<Action>>,
+    automation_state: Res<State<AutomationStates>>,
+    mut next_automation_state: ResMut<NextState<AutomationStates>>,
+    mut settings: ResMut<AutomationSettings>,
+    mut focus: ResMut<AppFocus>,
+) {
+    let mut toggles = usize::from(actions.just_pressed(Action::ToggleAutomation));
+
+    for command in commands.read() {
+        match command {
+            UiCommand::ToggleAutomation => toggles += 1,
+            UiCommand::SetAutomateOnFocusLoss(on_focus_loss) => {
+                settings.on_focus_loss = *on_focus_loss
+            }
+            _ => {}
+        }
+    }
 
-fn clean_idle_time_data(mut cmds: Commands, data: Query<(Entity, &IdleTimeSample)>) {
-    for (entity, time) in data {
-        if time.when.elapsed() > IDLE_SAMPLE_WINDOW {
-            cmds.entity(entity).despawn();
-            // debug!("rmed idle time sample");
+    if toggles % 2 == 0 {
+        return;
+    }
+
+    match automation_state.get() {
+        AutomationStates::Manual => next_automation_state.set(AutomationStates::Automation),
+        AutomationStates::Automation => {
+            next_automation_state.set(AutomationStates::Manual);
+
+            // forget the focus loss, or automating would start straight back up.
+            focus.lost_at = None;
         }
     }
 }
 
+fn record_time_sample<C, S>(curent_time: Res<C>, mut history: ResMut<SampleHistory<S>>)
+where
+    C: Resource + std::ops::Deref<Target = f64>,
+    S: TimeSample + From<f64> + Send + Sync + 'static,
+{
+    history.push(S::from(*curent_time.deref()));
+}
+
+fn save_histories(
+    idle_history: Res<IdleTimeHistory>,
+    speed_history: Res<AutomationSpeedHistory>,
+    save_path: Res<SavePath<SavedHistories>>,
+) {
+    let saved = SavedHistories {
+        idle_time: idle_history.saved(),
+        automation_speed: speed_history.saved(),
+    };
+
+    saved.save_or_log(&save_path.path);
+}
+
 /// This system shows how to respond to a window being resized.
 fn update_window_size(
     mut resize_events: MessageReader<WindowResized>,
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+
+/// one entry of a loot table.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LootDrop {
+    /// the id of the item that drops.
+    pub item: String,
+    /// how likely the drop is relative to the rest of the table.
+    pub weight: u32,
+    /// how many of the item drop at once.
+    #[serde(default = "one")]
+    pub count: u32,
+}
+
+fn one() -> u32 {
+    1
+}
+
+/// the drops one roll can come up with, picked by weight.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Deref, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LootTable(pub Vec<LootDrop>);
+
+impl LootTable {
+    /// picks a drop, `None` if the table is empty or all of its weights are 0.
+    pub fn roll(&self, rng: &mut impl Rng) -> Option<&LootDrop> {
+        self.choose_weighted(rng, |drop| drop.weight).ok()
+    }
+}
+
+/// the rng loot is rolled with. seeded from the run's seed, see `RunRecord::seed`, but separately
+/// from the `DungeonRng` so a run's drops can be reproduced from its seed.
+#[derive(Resource, Debug, Clone, Deref, DerefMut)]
+pub struct LootRng {
+    pub seed: u64,
+    #[deref]
+    rng: StdRng,
+}
+
+impl LootRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Default for LootRng {
+    fn default() -> Self {
+        Self::from_seed(rand::rng().random())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> LootTable {
+        LootTable(vec![
+            LootDrop {
+                item: "bone".to_string(),
+                weight: 3,
+                count: 1,
+            },
+            LootDrop {
+                item: "skull".to_string(),
+                weight: 1,
+                count: 2,
+            },
+        ])
+    }
+
+    #[test]
+    fn the_same_seed_rolls_the_same_loot() {
+        let table = table();
+        let rolls = |seed| {
+            let mut rng = LootRng::from_seed(seed);
+
+            (0..20)
+                .map(|_| table.roll(&mut *rng).unwrap().item.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(rolls(7), rolls(7));
+    }
+
+    #[test]
+    fn nothing_to_roll() {
+        let mut rng = LootRng::from_seed(0);
+        let mut table = table();
+
+        table.0.iter_mut().for_each(|drop| drop.weight = 0);
+
+        assert_eq!(table.roll(&mut *rng), None);
+        assert_eq!(LootTable::default().roll(&mut *rng), None);
+    }
+}
@@ -0,0 +1,790 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+
+use bevy::{
+    asset::{AssetLoadFailedEvent, LoadedFolder},
+    prelude::*,
+};
+use bevy_common_assets::ron::RonAssetPlugin;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    base_plugin::UiCommand,
+    bestiary_plugin::{EnemyCatalog, EnemyKilled},
+    combat::{CombatStats, EffectiveStats, ModifierSource, StatModifier, StatModifiers},
+    inventory_plugin::loot::LootRng,
+    status_effect_plugin::{ApplyStatusEffect, EffectTarget, StatusEffectCatalog},
+    theme::ThemeColors,
+};
+
+pub mod loot;
+
+/// where the item definitions are loaded from, relative to the asset folder.
+pub const ITEMS_FOLDER: &str = "items";
+/// the extension of item list files, each one maps item ids to their definitions.
+pub const ITEM_EXTENSION: &str = "items.ron";
+/// how many stacks the inventory holds.
+pub const INVENTORY_SLOTS: usize = 30;
+/// how many of an item fit in one stack, unless its definition says otherwise.
+pub const DEFAULT_MAX_STACK: u32 = 99;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl Rarity {
+    /// the color item names of this rarity are drawn in, from the active theme.
+    pub fn color(&self, colors: &ThemeColors) -> Srgba {
+        colors.rarities[*self as usize]
+    }
+}
+
+impl Display for Rarity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Common => "common",
+            Self::Uncommon => "uncommon",
+            Self::Rare => "rare",
+            Self::Epic => "epic",
+            Self::Legendary => "legendary",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// where on a party member an item is worn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EquipSlot {
+    Weapon,
+    Armor,
+    Trinket,
+}
+
+impl EquipSlot {
+    pub const ALL: [Self; 3] = [Self::Weapon, Self::Armor, Self::Trinket];
+}
+
+impl Display for EquipSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Weapon => "weapon",
+            Self::Armor => "armor",
+            Self::Trinket => "trinket",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ItemDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub rarity: Rarity,
+    /// where the item is worn, `None` if it can't be equipped.
+    #[serde(default)]
+    pub slot: Option<EquipSlot>,
+    #[serde(default = "default_max_stack")]
+    pub max_stack: u32,
+    /// applied to whoever has the item equipped.
+    #[serde(default)]
+    pub modifiers: Vec<StatModifier>,
+    /// the id of the status effect the item applies to the party when it's used up, `None` if
+    /// it can't be used.
+    #[serde(default)]
+    pub applies: Option<String>,
+    #[serde(default)]
+    pub description: String,
+}
+
+fn default_max_stack() -> u32 {
+    DEFAULT_MAX_STACK
+}
+
+/// something wrong with an `ItemDefinition` that parsed fine.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemDefinitionError {
+    EmptyName,
+    MaxStack,
+    StackedEquipment(u32),
+    UnusedModifiers,
+    Modifier(StatModifier),
+    EmptyEffect,
+    UsableEquipment,
+}
+
+impl Display for ItemDefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyName => write!(f, "the name is empty"),
+            Self::MaxStack => write!(f, "max_stack must be at least 1"),
+            Self::StackedEquipment(max_stack) => write!(
+                f,
+                "equipment can't stack, but max_stack is {max_stack} rather than 1"
+            ),
+            Self::UnusedModifiers => {
+                write!(f, "it has modifiers but no slot, they'd never be applied")
+            }
+            Self::Modifier(modifier) => {
+                write!(f, "the {} modifier isn't a finite number", modifier.stat)
+            }
+            Self::EmptyEffect => write!(f, "the status effect it applies is empty"),
+            Self::UsableEquipment => write!(f, "equipment can't be used up"),
+        }
+    }
+}
+
+impl std::error::Error for ItemDefinitionError {}
+
+impl ItemDefinition {
+    /// checks the definition makes sense, returning everything wrong with it.
+    pub fn validate(&self) -> Result<(), Vec<ItemDefinitionError>> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            errors.push(ItemDefinitionError::EmptyName);
+        }
+
+        if self.max_stack == 0 {
+            errors.push(ItemDefinitionError::MaxStack);
+        } else if self.slot.is_some() && self.max_stack > 1 {
+            errors.push(ItemDefinitionError::StackedEquipment(self.max_stack));
+        }
+
+        if self.slot.is_none() && !self.modifiers.is_empty() {
+            errors.push(ItemDefinitionError::UnusedModifiers);
+        }
+
+        for modifier in &self.modifiers {
+            if !modifier.add.is_finite() || !modifier.multiply.is_finite() {
+                errors.push(ItemDefinitionError::Modifier(*modifier));
+            }
+        }
+
+        if let Some(effect) = &self.applies {
+            if effect.trim().is_empty() {
+                errors.push(ItemDefinitionError::EmptyEffect);
+            }
+
+            if self.slot.is_some() {
+                errors.push(ItemDefinitionError::UsableEquipment);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// a file of item definitions, by id.
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Deref, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ItemList(pub BTreeMap<String, ItemDefinition>);
+
+/// the valid item definitions, by id.
+#[derive(Resource, Debug, Clone, PartialEq, Default, Deref)]
+pub struct ItemCatalog(pub BTreeMap<String, ItemDefinition>);
+
+impl ItemCatalog {
+    /// how many of `item` fit in one stack.
+    pub fn max_stack(&self, item: &str) -> u32 {
+        self.get(item)
+            .map_or(DEFAULT_MAX_STACK, |definition| definition.max_stack)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ItemStack {
+    pub item: String,
+    pub count: u32,
+}
+
+/// the items the party is carrying.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Deref, Serialize, Deserialize)]
+pub struct Inventory {
+    #[deref]
+    stacks: Vec<ItemStack>,
+    capacity: usize,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::with_capacity(INVENTORY_SLOTS)
+    }
+}
+
+impl Inventory {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            stacks: Vec::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// how many of `item` are carried, across all of its stacks.
+    pub fn count(&self, item: &str) -> u32 {
+        self.stacks
+            .iter()
+            .filter(|stack| stack.item == item)
+            .map(|stack| stack.count)
+            .sum()
+    }
+
+    /// how many of `item` can be added before the inventory is full.
+    pub fn room_for(&self, item: &str, max_stack: u32) -> u32 {
+        let max_stack = max_stack.max(1);
+        let partial: u32 = self
+            .stacks
+            .iter()
+            .filter(|stack| stack.item == item)
+            .map(|stack| max_stack.saturating_sub(stack.count))
+            .sum();
+        let free_stacks = self.capacity.saturating_sub(self.stacks.len()) as u32;
+
+        partial.saturating_add(free_stacks.saturating_mul(max_stack))
+    }
+
+    /// adds `count` of `item`, topping up its existing stacks before starting new ones. returns
+    /// how many didn't fit.
+    pub fn add(&mut self, item: &str, mut count: u32, max_stack: u32) -> u32 {
+        let max_stack = max_stack.max(1);
+
+        for stack in self.stacks.iter_mut().filter(|stack| stack.item == item) {
+            let moved = count.min(max_stack.saturating_sub(stack.count));
+            stack.count += moved;
+            count -= moved;
+        }
+
+        while count > 0 && self.stacks.len() < self.capacity {
+            let moved = count.min(max_stack);
+            self.stacks.push(ItemStack {
+                item: item.to_string(),
+                count: moved,
+            });
+            count -= moved;
+        }
+
+        count
+    }
+
+    /// whether the stack at `index` holds `item`, for checking a stack picked out in the UI is
+    /// still where it was.
+    pub fn holds(&self, index: usize, item: &str) -> bool {
+        self.stacks
+            .get(index)
+            .is_some_and(|stack| stack.item == item)
+    }
+
+    /// takes up to `count` items out of the stack at `index`, removing the stack if it's emptied.
+    pub fn take(&mut self, index: usize, count: u32) -> Option<ItemStack> {
+        let stack = self.stacks.get_mut(index)?;
+        let taken = count.min(stack.count);
+        let item = stack.item.clone();
+        stack.count -= taken;
+
+        if stack.count == 0 {
+            self.stacks.remove(index);
+        }
+
+        Some(ItemStack { item, count: taken })
+    }
+}
+
+/// what a party member has equipped, item ids by slot.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Default, Deref, DerefMut)]
+pub struct Equipment(pub BTreeMap<EquipSlot, String>);
+
+/// a party member's equipment & stats, for the UI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Loadout {
+    pub entity: Entity,
+    pub name: String,
+    pub equipment: Equipment,
+    pub stats: CombatStats,
+}
+
+/// everyone who can equip items.
+#[derive(Resource, Debug, Clone, PartialEq, Default, Deref)]
+pub struct Loadouts(pub Vec<Loadout>);
+
+/// sent when loot goes into the inventory.
+#[derive(Message, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LootPickedUp {
+    pub item: String,
+    pub count: u32,
+}
+
+/// keeps the item definitions loaded.
+#[derive(Resource, Debug, Clone, Deref)]
+pub struct ItemFolder(pub Handle<LoadedFolder>);
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<ItemList>::new(&[ITEM_EXTENSION]));
+        app.init_resource::<ItemCatalog>();
+        app.init_resource::<Inventory>();
+        app.init_resource::<Loadouts>();
+        app.init_resource::<LootRng>();
+        app.add_message::<LootPickedUp>();
+        app.add_systems(Startup, load_items);
+        app.add_systems(
+            Update,
+            (
+                report_load_failures.run_if(on_message::<AssetLoadFailedEvent<ItemList>>),
+                update_catalog.run_if(on_message::<AssetEvent<ItemList>>),
+                roll_loot.run_if(on_message::<EnemyKilled>),
+                (equip_items, use_items).run_if(on_message::<UiCommand>),
+                apply_equipment,
+                update_loadouts,
+            )
+                .chain(),
+        );
+    }
+}
+
+fn load_items(mut cmds: Commands, asset_server: Res<AssetServer>) {
+    cmds.insert_resource(ItemFolder(asset_server.load_folder(ITEMS_FOLDER)));
+}
+
+fn report_load_failures(mut failures: MessageReader<AssetLoadFailedEvent<ItemList>>) {
+    for failure in failures.read() {
+        error!(
+            "failed to load the items {}: {}",
+            failure.path, failure.error
+        );
+    }
+}
+
+/// rebuilds the catalog whenever an item list is loaded, (hot) reloaded or removed, reporting
+/// what's wrong with the items in the lists that changed.
+fn update_catalog(
+    mut events: MessageReader<AssetEvent<ItemList>>,
+    lists: Res<Assets<ItemList>>,
+    asset_server: Res<AssetServer>,
+    mut catalog: ResMut<ItemCatalog>,
+) {
+    for event in events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = event else {
+            continue;
+        };
+        let (Some(list), Some(path)) = (lists.get(*id), asset_server.get_path(*id)) else {
+            continue;
+        };
+
+        for (item, definition) in list.iter() {
+            if let Err(errors) = definition.validate() {
+                for e in errors {
+                    error!("the item \"{item}\" in {path} is invalid, it will be left out: {e}");
+                }
+            }
+        }
+    }
+
+    let mut items = BTreeMap::new();
+
+    for (id, list) in lists.iter() {
+        for (item, definition) in list.iter() {
+            if definition.validate().is_err() {
+                continue;
+            }
+
+            if items.contains_key(item) {
+                let path = asset_server.get_path(id);
+                error!(
+                    "more than one item is defined with the id \"{item}\", skipping the one in {}",
+                    path.map(|path| path.to_string()).unwrap_or_default()
+                );
+                continue;
+            }
+
+            items.insert(item.clone(), definition.clone());
+        }
+    }
+
+    if catalog.0 != items {
+        info!("{} items in the catalog", items.len());
+        catalog.0 = items;
+    }
+}
+
+/// rolls the loot table of every enemy the party kills.
+fn roll_loot(
+    mut killed: MessageReader<EnemyKilled>,
+    enemies: Res<EnemyCatalog>,
+    items: Res<ItemCatalog>,
+    mut rng: ResMut<LootRng>,
+    mut inventory: ResMut<Inventory>,
+    mut picked_up: MessageWriter<LootPickedUp>,
+) {
+    for EnemyKilled(enemy) in killed.read() {
+        let Some(drop) = enemies
+            .get(enemy)
+            .and_then(|definition| definition.loot_table.roll(&mut **rng))
+        else {
+            continue;
+        };
+
+        if !items.contains_key(&drop.item) {
+            error!(
+                "the {enemy} dropped \"{}\", which isn't a known item",
+                drop.item
+            );
+            continue;
+        }
+
+        let left_behind = inventory.add(&drop.item, drop.count, items.max_stack(&drop.item));
+
+        if left_behind > 0 {
+            info!(
+                "the inventory is full, {left_behind} {} left behind",
+                drop.item
+            );
+        }
+
+        if left_behind < drop.count {
+            picked_up.write(LootPickedUp {
+                item: drop.item.clone(),
+                count: drop.count - left_behind,
+            });
+        }
+    }
+}
+
+/// moves items between the inventory & party members' equipment.
+fn equip_items(
+    mut commands: MessageReader<UiCommand>,
+    catalog: Res<ItemCatalog>,
+    mut inventory: ResMut<Inventory>,
+    mut members: Query<&mut Equipment>,
+) {
+    for command in commands.read() {
+        match command {
+            UiCommand::Equip {
+                stack,
+                item,
+                member,
+            } => {
+                let Ok(mut equipment) = members.get_mut(*member) else {
+                    continue;
+                };
+
+                if !inventory.holds(*stack, item) {
+                    warn!("the inventory changed before {item} could be equipped");
+                    continue;
+                }
+
+                let item = item.clone();
+                let Some(slot) = catalog.get(&item).and_then(|definition| definition.slot) else {
+                    warn!("{item} can't be equipped");
+                    continue;
+                };
+
+                inventory.take(*stack, 1);
+
+                if let Some(old) = equipment.insert(slot, item) {
+                    // the equipped item took up the room the old one needs, so it always fits.
+                    inventory.add(&old, 1, catalog.max_stack(&old));
+                }
+            }
+            UiCommand::Unequip { member, slot } => {
+                let Ok(mut equipment) = members.get_mut(*member) else {
+                    continue;
+                };
+                let Some(item) = equipment.get(slot).cloned() else {
+                    continue;
+                };
+
+                if inventory.room_for(&item, catalog.max_stack(&item)) == 0 {
+                    warn!("the inventory is full, {item} can't be unequipped");
+                    continue;
+                }
+
+                equipment.remove(slot);
+                inventory.add(&item, 1, catalog.max_stack(&item));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// uses up items that apply a status effect.
+fn use_items(
+    mut commands: MessageReader<UiCommand>,
+    catalog: Res<ItemCatalog>,
+    effects: Res<StatusEffectCatalog>,
+    mut inventory: ResMut<Inventory>,
+    mut apply: MessageWriter<ApplyStatusEffect>,
+) {
+    for command in commands.read() {
+        let UiCommand::UseItem { stack, item } = command else {
+            continue;
+        };
+
+        if !inventory.holds(*stack, item) {
+            warn!("the inventory changed before {item} could be used");
+            continue;
+        }
+
+        let Some(effect) = catalog
+            .get(item)
+            .and_then(|definition| definition.applies.clone())
+        else {
+            warn!("{item} can't be used");
+            continue;
+        };
+
+        if !effects.contains_key(&effect) {
+            warn!("{item} applies \"{effect}\", which isn't a status effect, so it's kept");
+            continue;
+        }
+
+        inventory.take(*stack, 1);
+        apply.write(ApplyStatusEffect {
+            target: EffectTarget::Party,
+            effect,
+        });
+    }
+}
+
+/// hands the stat modifiers of everyone's equipment to the modifier pipeline.
+fn apply_equipment(
+    catalog: Res<ItemCatalog>,
+    mut combatants: Query<(Ref<Equipment>, &mut StatModifiers)>,
+) {
+    for (equipment, mut modifiers) in &mut combatants {
+        if !catalog.is_changed() && !equipment.is_changed() {
+            continue;
+        }
+
+        let equipped: Vec<StatModifier> = equipment
+            .values()
+            .filter_map(|item| catalog.get(item))
+            .flat_map(|definition| definition.modifiers.iter().copied())
+            .collect();
+
+        if modifiers.get(&ModifierSource::Equipment) != Some(&equipped) {
+            modifiers.insert(ModifierSource::Equipment, equipped);
+        }
+    }
+}
+
+type LoadoutQuery<'a> = (
+    Entity,
+    Ref<'a, Name>,
+    Ref<'a, Equipment>,
+    Ref<'a, EffectiveStats>,
+);
+
+fn update_loadouts(
+    members: Query<LoadoutQuery>,
+    mut removed: RemovedComponents<Equipment>,
+    mut loadouts: ResMut<Loadouts>,
+) {
+    let removed = removed.read().count() > 0;
+    let changed = members.iter().any(|(_, name, equipment, stats)| {
+        name.is_changed() || equipment.is_changed() || stats.is_changed()
+    });
+
+    if !removed && !changed {
+        return;
+    }
+
+    loadouts.0 = members
+        .iter()
+        .map(|(entity, name, equipment, stats)| Loadout {
+            entity,
+            name: name.to_string(),
+            equipment: equipment.clone(),
+            stats: stats.0,
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::combat::Stat;
+    use crate::backend::shipped_assets;
+
+    fn potion() -> ItemDefinition {
+        ItemDefinition {
+            name: "potion".to_string(),
+            rarity: Rarity::Common,
+            slot: None,
+            max_stack: DEFAULT_MAX_STACK,
+            modifiers: Vec::new(),
+            applies: Some("regenerating".to_string()),
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn add_tops_up_stacks_before_starting_new_ones() {
+        let mut inventory = Inventory::with_capacity(3);
+
+        assert_eq!(inventory.add("bone", 7, 5), 0);
+        assert_eq!(inventory.add("bone", 2, 5), 0);
+        assert_eq!(
+            inventory
+                .iter()
+                .map(|stack| stack.count)
+                .collect::<Vec<_>>(),
+            [5, 4]
+        );
+        assert_eq!(inventory.count("bone"), 9);
+    }
+
+    #[test]
+    fn add_returns_what_doesnt_fit() {
+        let mut inventory = Inventory::with_capacity(2);
+        inventory.add("sword", 1, 1);
+
+        assert_eq!(inventory.room_for("bone", 5), 5);
+        assert_eq!(inventory.add("bone", 8, 5), 3);
+        assert_eq!(inventory.room_for("bone", 5), 0);
+        assert_eq!(inventory.len(), 2);
+    }
+
+    #[test]
+    fn room_for_counts_partial_stacks() {
+        let mut inventory = Inventory::with_capacity(3);
+        inventory.add("bone", 3, 5);
+        inventory.add("sword", 1, 1);
+
+        assert_eq!(inventory.room_for("bone", 5), 2 + 5);
+        assert_eq!(inventory.room_for("sword", 1), 1);
+    }
+
+    #[test]
+    fn take_removes_emptied_stacks() {
+        let mut inventory = Inventory::with_capacity(3);
+        inventory.add("bone", 3, 5);
+        inventory.add("sword", 1, 1);
+
+        assert_eq!(
+            inventory.take(0, 5),
+            Some(ItemStack {
+                item: "bone".to_string(),
+                count: 3
+            })
+        );
+        assert!(inventory.holds(0, "sword"));
+        assert_eq!(inventory.take(3, 1), None);
+    }
+
+    #[test]
+    fn holds_catches_moved_stacks() {
+        let mut inventory = Inventory::with_capacity(3);
+        inventory.add("bone", 1, 5);
+        inventory.add("sword", 1, 1);
+
+        assert!(inventory.holds(1, "sword"));
+
+        inventory.take(0, 1);
+
+        assert!(!inventory.holds(1, "sword"));
+        assert!(!inventory.holds(0, "bone"));
+    }
+
+    #[test]
+    fn valid_items_pass() {
+        let sword = ItemDefinition {
+            name: "sword".to_string(),
+            slot: Some(EquipSlot::Weapon),
+            max_stack: 1,
+            modifiers: vec![StatModifier {
+                stat: Stat::Attack,
+                add: 2.0,
+                multiply: 1.0,
+            }],
+            applies: None,
+            ..potion()
+        };
+
+        assert_eq!(potion().validate(), Ok(()));
+        assert_eq!(sword.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_reports_everything_wrong() {
+        let modifier = StatModifier {
+            stat: Stat::Hp,
+            add: f64::INFINITY,
+            multiply: 1.0,
+        };
+        let item = ItemDefinition {
+            name: String::new(),
+            slot: Some(EquipSlot::Trinket),
+            max_stack: 3,
+            modifiers: vec![modifier],
+            applies: Some(" ".to_string()),
+            ..potion()
+        };
+
+        assert_eq!(
+            item.validate(),
+            Err(vec![
+                ItemDefinitionError::EmptyName,
+                ItemDefinitionError::StackedEquipment(3),
+                ItemDefinitionError::Modifier(modifier),
+                ItemDefinitionError::EmptyEffect,
+                ItemDefinitionError::UsableEquipment,
+            ])
+        );
+    }
+
+    #[test]
+    fn modifiers_need_a_slot() {
+        let item = ItemDefinition {
+            max_stack: 0,
+            modifiers: vec![StatModifier {
+                stat: Stat::Speed,
+                add: 0.0,
+                multiply: 1.1,
+            }],
+            ..potion()
+        };
+
+        assert_eq!(
+            item.validate(),
+            Err(vec![
+                ItemDefinitionError::MaxStack,
+                ItemDefinitionError::UnusedModifiers,
+            ])
+        );
+    }
+
+    #[test]
+    fn shipped_definitions_are_valid() {
+        for (path, source) in shipped_assets(ITEMS_FOLDER, ITEM_EXTENSION) {
+            let list: ItemList = ron::from_str(&source).unwrap_or_else(|e| panic!("{path}: {e}"));
+
+            for (id, item) in list.iter() {
+                assert_eq!(item.validate(), Ok(()), "{path}: {id}");
+            }
+        }
+    }
+}
@@ -3,10 +3,27 @@ use std::fmt::Display;
 use bevy::ecs::prelude::*;
 use bevy::prelude::{Deref, DerefMut};
 
+pub mod achievement_plugin;
+pub mod action_plugin;
 pub mod base_plugin;
+pub mod bestiary_plugin;
 pub mod bevy_scene_plugin;
+pub mod big_num;
+pub mod combat;
+pub mod diagnostics_plugin;
+pub mod dungeon_plugin;
+pub mod economy_plugin;
+pub mod export_plugin;
 pub mod idle_time_plugin;
+pub mod inventory_plugin;
+pub mod modifier;
+pub mod party_plugin;
+pub mod prestige_plugin;
+pub mod save_file;
 pub mod sphere;
+pub mod stats_plugin;
+pub mod status_effect_plugin;
+pub mod theme;
 
 #[derive(Resource, Debug, Clone, PartialEq, Deref, DerefMut)]
 pub struct CurrentIdleTimeSeconds(pub f64);
@@ -57,3 +74,39 @@ impl AutomationSpeed {
         self.raw_speed = value;
     }
 }
+
+/// every shipped asset in `folder` with `extension` & its contents, for tests that check the
+/// data the game actually loads.
+#[cfg(test)]
+pub(crate) fn shipped_assets(folder: &str, extension: &str) -> Vec<(String, String)> {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("assets")
+        .join(folder);
+    let mut assets: Vec<(String, String)> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.to_string_lossy().ends_with(&format!(".{extension}")))
+        .map(|path| {
+            let source = std::fs::read_to_string(&path).unwrap();
+
+            (path.display().to_string(), source)
+        })
+        .collect();
+
+    assets.sort();
+    assert!(
+        !assets.is_empty(),
+        "no {extension} files in {}",
+        dir.display()
+    );
+
+    assets
+}
+
+/// a path under the temp dir for tests that read & write files, unique to this test run.
+#[cfg(test)]
+pub(crate) fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir()
+        .join(format!("idle-dungeoneer-{}", std::process::id()))
+        .join(name)
+}
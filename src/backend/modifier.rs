@@ -0,0 +1,170 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::idle_time_plugin::{AUTOMATION_SPEED_GROWTH_RATE, IDLE_TIME_GROWTH_RATE};
+
+/// a change to one value, by a flat amount & a factor.
+pub trait Modifier {
+    type Target: PartialEq;
+
+    fn target(&self) -> Self::Target;
+    fn add(&self) -> f64;
+    fn multiply(&self) -> f64;
+    /// the modifier applied `stacks` times over.
+    fn stacked(&self, stacks: u32) -> Self;
+}
+
+/// `base` with every one of `modifiers` that targets `target` applied. every flat bonus is added
+/// before anything is multiplied, so the order the modifiers come in doesn't matter.
+pub fn modify<'a, M: Modifier + 'a>(
+    base: f64,
+    target: M::Target,
+    modifiers: impl IntoIterator<Item = &'a M>,
+) -> f64 {
+    let (add, multiply) = modifiers
+        .into_iter()
+        .filter(|modifier| modifier.target() == target)
+        .fold((0.0, 1.0), |(add, multiply), modifier| {
+            (add + modifier.add(), multiply * modifier.multiply())
+        });
+
+    (base + add) * multiply
+}
+
+/// the serde default of a modifier's `multiply`.
+pub(crate) fn no_scaling() -> f64 {
+    1.0
+}
+
+/// writes `modifier` as its flat bonus & factor, leaving out the ones that don't change anything.
+pub(crate) fn fmt_modifier<M>(modifier: &M, f: &mut fmt::Formatter<'_>) -> fmt::Result
+where
+    M: Modifier,
+    M::Target: Display,
+{
+    let mut parts = Vec::new();
+
+    if modifier.add() != 0.0 {
+        parts.push(format!("{:+} {}", modifier.add(), modifier.target()));
+    }
+
+    if modifier.multiply() != 1.0 {
+        parts.push(format!("x{} {}", modifier.multiply(), modifier.target()));
+    }
+
+    write!(f, "{}", parts.join(", "))
+}
+
+/// a game wide rate that isn't tied to a combatant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rate {
+    IdleTimeGrowth,
+    AutomationSpeedGrowth,
+    Income,
+    Xp,
+}
+
+impl Rate {
+    pub const ALL: [Self; 4] = [
+        Self::IdleTimeGrowth,
+        Self::AutomationSpeedGrowth,
+        Self::Income,
+        Self::Xp,
+    ];
+
+    /// the rate before any modifiers.
+    pub fn base(&self) -> f64 {
+        match self {
+            Self::IdleTimeGrowth => IDLE_TIME_GROWTH_RATE,
+            Self::AutomationSpeedGrowth => AUTOMATION_SPEED_GROWTH_RATE,
+            Self::Income | Self::Xp => 1.0,
+        }
+    }
+}
+
+impl Display for Rate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::IdleTimeGrowth => "idle time growth",
+            Self::AutomationSpeedGrowth => "automation speed growth",
+            Self::Income => "income",
+            Self::Xp => "party xp",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// a change to one rate, from a status effect, etc.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateModifier {
+    pub rate: Rate,
+    /// a flat bonus, can be negative.
+    #[serde(default)]
+    pub add: f64,
+    /// scales the rate after every flat bonus is added.
+    #[serde(default = "no_scaling")]
+    pub multiply: f64,
+}
+
+impl Modifier for RateModifier {
+    type Target = Rate;
+
+    fn target(&self) -> Rate {
+        self.rate
+    }
+
+    fn add(&self) -> f64 {
+        self.add
+    }
+
+    fn multiply(&self) -> f64 {
+        self.multiply
+    }
+
+    fn stacked(&self, stacks: u32) -> Self {
+        Self {
+            rate: self.rate,
+            add: self.add * stacks as f64,
+            multiply: self.multiply.powi(stacks as i32),
+        }
+    }
+}
+
+impl Display for RateModifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fmt_modifier(self, f)
+    }
+}
+
+/// every rate with its modifiers applied. rates don't go below 0.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Rates(pub BTreeMap<Rate, f64>);
+
+impl Default for Rates {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+impl Rates {
+    pub fn new<'a>(modifiers: impl IntoIterator<Item = &'a RateModifier> + Clone) -> Self {
+        Self(
+            Rate::ALL
+                .into_iter()
+                .map(|rate| (rate, modify(rate.base(), rate, modifiers.clone()).max(0.0)))
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, rate: Rate) -> f64 {
+        self.0.get(&rate).copied().unwrap_or_else(|| rate.base())
+    }
+}
@@ -0,0 +1,399 @@
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    bestiary_plugin::DamageType,
+    combat::{CombatStats, Stat},
+    party_plugin::MAX_LEVEL,
+};
+
+/// who a skill's status effect is applied to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillTarget {
+    /// whoever used the skill.
+    #[default]
+    User,
+    Party,
+}
+
+/// a status effect a skill applies each time it's used.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SkillEffect {
+    /// the id of the status effect.
+    pub effect: String,
+    #[serde(default)]
+    pub target: SkillTarget,
+}
+
+/// a skill a class can learn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClassSkill {
+    pub name: String,
+    /// the level the skill is learned at.
+    #[serde(default = "first_level")]
+    pub unlock_level: u32,
+    /// how hard the skill hits, as a multiple of the user's attack.
+    pub power: f64,
+    #[serde(default = "physical")]
+    pub damage_type: DamageType,
+    #[serde(default)]
+    pub applies: Option<SkillEffect>,
+}
+
+fn first_level() -> u32 {
+    1
+}
+
+fn physical() -> DamageType {
+    DamageType::Physical
+}
+
+/// how much xp each level takes, `base * level ^ exponent`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct XpCurve {
+    pub base: f64,
+    pub exponent: f64,
+}
+
+impl XpCurve {
+    /// the xp it takes to get from `level` to the next one.
+    pub fn xp_to_next(&self, level: u32) -> u64 {
+        (self.base * (level as f64).powf(self.exponent))
+            .round()
+            .max(1.0) as u64
+    }
+}
+
+/// a party member's class, loaded from `CLASSES_FOLDER`.
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClassDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// the stats at level 1.
+    pub base_stats: CombatStats,
+    /// the stats gained every level after the first.
+    pub growth: CombatStats,
+    /// how many skills a member of the class can have in their battle order.
+    pub skill_slots: usize,
+    pub skills: Vec<ClassSkill>,
+    pub xp_curve: XpCurve,
+}
+
+/// something wrong with a `ClassDefinition` that parsed fine.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClassDefinitionError {
+    EmptyName,
+    Stat { stat: Stat, value: f64 },
+    Growth { stat: Stat, value: f64 },
+    SkillSlots,
+    NoStartingSkill,
+    EmptySkill,
+    DuplicateSkill(String),
+    UnlockLevel { skill: String, level: u32 },
+    SkillPower { skill: String, power: f64 },
+    EmptySkillEffect(String),
+    XpCurve(XpCurve),
+}
+
+impl Display for ClassDefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyName => write!(f, "the name is empty"),
+            Self::Stat { stat, value } => write!(f, "the base {stat} can't be {value}"),
+            Self::Growth { stat, value } => write!(f, "the {stat} growth can't be {value}"),
+            Self::SkillSlots => write!(f, "skill_slots must be at least 1"),
+            Self::NoStartingSkill => write!(f, "no skill is learned at level 1"),
+            Self::EmptySkill => write!(f, "a skill name is empty"),
+            Self::DuplicateSkill(skill) => write!(f, "the skill \"{skill}\" is listed twice"),
+            Self::UnlockLevel { skill, level } => write!(
+                f,
+                "\"{skill}\" unlocks at level {level}, it has to be between 1 & {MAX_LEVEL}"
+            ),
+            Self::SkillPower { skill, power } => {
+                write!(f, "the power of \"{skill}\" can't be {power}")
+            }
+            Self::EmptySkillEffect(skill) => {
+                write!(f, "the status effect \"{skill}\" applies is empty")
+            }
+            Self::XpCurve(curve) => write!(
+                f,
+                "the xp curve needs a positive base & exponent, not {} & {}",
+                curve.base, curve.exponent
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ClassDefinitionError {}
+
+impl ClassDefinition {
+    /// checks the definition makes sense, returning everything wrong with it.
+    pub fn validate(&self) -> Result<(), Vec<ClassDefinitionError>> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            errors.push(ClassDefinitionError::EmptyName);
+        }
+
+        for stat in Stat::ALL {
+            let value = self.base_stats.get(stat);
+            let can_be_zero = matches!(stat, Stat::Attack | Stat::Defense);
+
+            if !value.is_finite() || value < 0.0 || (value == 0.0 && !can_be_zero) {
+                errors.push(ClassDefinitionError::Stat { stat, value });
+            }
+
+            let value = self.growth.get(stat);
+
+            if !value.is_finite() || value < 0.0 {
+                errors.push(ClassDefinitionError::Growth { stat, value });
+            }
+        }
+
+        if self.skill_slots == 0 {
+            errors.push(ClassDefinitionError::SkillSlots);
+        }
+
+        if self.skills.iter().all(|skill| skill.unlock_level > 1) {
+            errors.push(ClassDefinitionError::NoStartingSkill);
+        }
+
+        let mut skills = HashSet::new();
+
+        for skill in &self.skills {
+            if skill.name.trim().is_empty() {
+                errors.push(ClassDefinitionError::EmptySkill);
+            } else if !skills.insert(&skill.name) {
+                errors.push(ClassDefinitionError::DuplicateSkill(skill.name.clone()));
+            }
+
+            if !(1..=MAX_LEVEL).contains(&skill.unlock_level) {
+                errors.push(ClassDefinitionError::UnlockLevel {
+                    skill: skill.name.clone(),
+                    level: skill.unlock_level,
+                });
+            }
+
+            if !skill.power.is_finite() || skill.power < 0.0 {
+                errors.push(ClassDefinitionError::SkillPower {
+                    skill: skill.name.clone(),
+                    power: skill.power,
+                });
+            }
+
+            if skill
+                .applies
+                .as_ref()
+                .is_some_and(|applies| applies.effect.trim().is_empty())
+            {
+                errors.push(ClassDefinitionError::EmptySkillEffect(skill.name.clone()));
+            }
+        }
+
+        let positive = |value: f64| value.is_finite() && value > 0.0;
+
+        if !positive(self.xp_curve.base) || !positive(self.xp_curve.exponent) {
+            errors.push(ClassDefinitionError::XpCurve(self.xp_curve));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// the stats of a member of the class at `level`.
+    pub fn stats_at(&self, level: u32) -> CombatStats {
+        let mut stats = self.base_stats;
+        let levels = level.saturating_sub(1) as f64;
+
+        for stat in Stat::ALL {
+            *stats.get_mut(stat) += self.growth.get(stat) * levels;
+        }
+
+        stats
+    }
+
+    pub fn skill(&self, name: &str) -> Option<&ClassSkill> {
+        self.skills.iter().find(|skill| skill.name == name)
+    }
+
+    /// the skills a member of the class knows at `level`.
+    pub fn known_skills(&self, level: u32) -> impl Iterator<Item = &ClassSkill> {
+        self.skills
+            .iter()
+            .filter(move |skill| skill.unlock_level <= level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{
+        party_plugin::{CLASS_EXTENSION, CLASSES_FOLDER},
+        shipped_assets,
+    };
+
+    fn skill(name: &str, unlock_level: u32) -> ClassSkill {
+        ClassSkill {
+            name: name.to_string(),
+            unlock_level,
+            power: 1.0,
+            damage_type: DamageType::Physical,
+            applies: None,
+        }
+    }
+
+    fn warrior() -> ClassDefinition {
+        ClassDefinition {
+            name: "warrior".to_string(),
+            description: String::new(),
+            base_stats: CombatStats {
+                hp: 30.0,
+                attack: 5.0,
+                defense: 3.0,
+                speed: 1.0,
+            },
+            growth: CombatStats {
+                hp: 5.0,
+                attack: 1.0,
+                defense: 0.5,
+                speed: 0.0,
+            },
+            skill_slots: 2,
+            skills: vec![skill("slash", 1), skill("bash", 3), skill("cleave", 5)],
+            xp_curve: XpCurve {
+                base: 100.0,
+                exponent: 1.5,
+            },
+        }
+    }
+
+    #[test]
+    fn xp_to_next_follows_the_curve() {
+        let curve = warrior().xp_curve;
+
+        assert_eq!(curve.xp_to_next(1), 100);
+        assert_eq!(curve.xp_to_next(4), 800);
+        assert_eq!(
+            XpCurve {
+                base: 0.1,
+                exponent: 1.0
+            }
+            .xp_to_next(1),
+            1
+        );
+    }
+
+    #[test]
+    fn stats_grow_every_level_after_the_first() {
+        let warrior = warrior();
+
+        assert_eq!(warrior.stats_at(1), warrior.base_stats);
+        assert_eq!(
+            warrior.stats_at(3),
+            CombatStats {
+                hp: 40.0,
+                attack: 7.0,
+                defense: 4.0,
+                speed: 1.0,
+            }
+        );
+    }
+
+    #[test]
+    fn known_skills_unlock_by_level() {
+        let warrior = warrior();
+        let known = |level| {
+            warrior
+                .known_skills(level)
+                .map(|skill| skill.name.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(known(1), ["slash"]);
+        assert_eq!(known(4), ["slash", "bash"]);
+    }
+
+    #[test]
+    fn valid_class_passes() {
+        assert_eq!(warrior().validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_reports_everything_wrong() {
+        let mut class = warrior();
+        class.name = String::new();
+        class.base_stats.hp = 0.0;
+        class.growth.speed = -1.0;
+        class.skill_slots = 0;
+        class.skills = vec![skill("slash", 2), skill("slash", 0), skill("", 2)];
+        class.skills[0].power = -1.0;
+        class.skills[2].applies = Some(SkillEffect {
+            effect: String::new(),
+            target: SkillTarget::User,
+        });
+        class.xp_curve.exponent = 0.0;
+
+        assert_eq!(
+            class.validate(),
+            Err(vec![
+                ClassDefinitionError::EmptyName,
+                ClassDefinitionError::Stat {
+                    stat: Stat::Hp,
+                    value: 0.0
+                },
+                ClassDefinitionError::Growth {
+                    stat: Stat::Speed,
+                    value: -1.0
+                },
+                ClassDefinitionError::SkillSlots,
+                ClassDefinitionError::SkillPower {
+                    skill: "slash".to_string(),
+                    power: -1.0
+                },
+                ClassDefinitionError::DuplicateSkill("slash".to_string()),
+                ClassDefinitionError::UnlockLevel {
+                    skill: "slash".to_string(),
+                    level: 0
+                },
+                ClassDefinitionError::EmptySkill,
+                ClassDefinitionError::EmptySkillEffect(String::new()),
+                ClassDefinitionError::XpCurve(class.xp_curve),
+            ])
+        );
+    }
+
+    #[test]
+    fn a_skill_has_to_be_known_from_the_start() {
+        let mut class = warrior();
+        class.skills[0].unlock_level = 2;
+
+        assert_eq!(
+            class.validate(),
+            Err(vec![ClassDefinitionError::NoStartingSkill])
+        );
+    }
+
+    #[test]
+    fn shipped_definitions_are_valid() {
+        for (path, source) in shipped_assets(CLASSES_FOLDER, CLASS_EXTENSION) {
+            let class: ClassDefinition =
+                ron::from_str(&source).unwrap_or_else(|e| panic!("{path}: {e}"));
+
+            assert_eq!(class.validate(), Ok(()), "{path}");
+        }
+    }
+}
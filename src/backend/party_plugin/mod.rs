@@ -0,0 +1,820 @@
+use std::{collections::BTreeMap, path::Path};
+
+use bevy::{
+    asset::{AssetLoadFailedEvent, LoadedFolder},
+    prelude::*,
+};
+use bevy_common_assets::ron::RonAssetPlugin;
+
+use crate::backend::{
+    AutomationSpeed,
+    action_plugin::Action,
+    base_plugin::{AutomationStates, PauseStates, UiCommand},
+    bestiary_plugin::DamageType,
+    combat::{BaseStats, CombatStats, EffectiveStats, Health, StatModifiers},
+    dungeon_plugin::{RoomCleared, RoomEntered},
+    idle_time_plugin::should_automate,
+    inventory_plugin::Equipment,
+    party_plugin::class::{ClassDefinition, XpCurve},
+    prestige_plugin::Prestiged,
+    status_effect_plugin::{
+        StatusEffectCatalog, Stunned,
+        effect::{EffectSummary, StatusEffects},
+    },
+};
+
+pub mod class;
+
+/// where the class definitions are loaded from, relative to the asset folder.
+pub const CLASSES_FOLDER: &str = "classes";
+/// the extension of class definition files, the rest of the file name is the class's id.
+pub const CLASS_EXTENSION: &str = "class.ron";
+pub const MAX_LEVEL: u32 = 50;
+/// xp each member earns per enemy defeated, times the depth of the floor.
+pub const XP_PER_ENEMY: u64 = 10;
+/// who the party starts with, as `(name, class id)`.
+pub const STARTING_PARTY: [(&str, &str); 3] =
+    [("Aldric", "warrior"), ("Mira", "mage"), ("Tess", "rogue")];
+
+/// scales the xp party members earn.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Deref, DerefMut)]
+pub struct XpMultiplier(pub f64);
+
+impl Default for XpMultiplier {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// the valid class definitions, by id.
+#[derive(Resource, Debug, Clone, PartialEq, Default, Deref)]
+pub struct ClassCatalog(pub BTreeMap<String, ClassDefinition>);
+
+#[derive(Component, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PartyMember {
+    /// the id of the member's class.
+    pub class: String,
+    /// where the member stands in the party, 0 at the front.
+    pub position: usize,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Experience {
+    pub level: u32,
+    /// xp earned towards the next level.
+    pub xp: u64,
+}
+
+impl Default for Experience {
+    fn default() -> Self {
+        Self { level: 1, xp: 0 }
+    }
+}
+
+impl Experience {
+    /// adds `xp`, leveling up as many times as it covers. returns how many levels were gained.
+    pub fn gain(&mut self, xp: u64, curve: &XpCurve) -> u32 {
+        let start = self.level;
+        self.xp = self.xp.saturating_add(xp);
+
+        while self.level < MAX_LEVEL && self.xp >= curve.xp_to_next(self.level) {
+            self.xp -= curve.xp_to_next(self.level);
+            self.level += 1;
+        }
+
+        if self.level == MAX_LEVEL {
+            self.xp = 0;
+        }
+
+        self.level - start
+    }
+}
+
+/// the skills a member uses in battle, in the order they use them.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct SkillOrder {
+    pub skills: Vec<String>,
+    /// index of the skill that's used next.
+    next: usize,
+}
+
+impl SkillOrder {
+    /// the first skills a member of `class` knows at `level`, as many as fit in its slots.
+    pub fn starting(class: &ClassDefinition, level: u32) -> Self {
+        Self {
+            skills: class
+                .known_skills(level)
+                .take(class.skill_slots)
+                .map(|skill| skill.name.clone())
+                .collect(),
+            next: 0,
+        }
+    }
+
+    pub fn next(&self) -> Option<&String> {
+        self.skills.get(self.next)
+    }
+
+    pub fn next_index(&self) -> usize {
+        self.next
+    }
+
+    fn advance(&mut self) {
+        self.next = (self.next + 1) % self.skills.len().max(1);
+    }
+
+    /// drops the skills `class` doesn't know at `level` & any that don't fit in its slots.
+    pub fn sync(&mut self, class: &ClassDefinition, level: u32) {
+        self.skills.retain(|name| {
+            class
+                .skill(name)
+                .is_some_and(|skill| skill.unlock_level <= level)
+        });
+        self.skills.truncate(class.skill_slots);
+
+        if self.next >= self.skills.len() {
+            self.next = 0;
+        }
+    }
+
+    /// puts `skill` at the end of the order, if there's a free slot and it isn't in it already.
+    pub fn slot(&mut self, skill: &str, slots: usize) -> bool {
+        if self.skills.len() >= slots || self.skills.iter().any(|name| name == skill) {
+            return false;
+        }
+
+        self.skills.push(skill.to_string());
+
+        true
+    }
+
+    pub fn unslot(&mut self, index: usize) {
+        if index >= self.skills.len() {
+            return;
+        }
+
+        self.skills.remove(index);
+
+        if self.next > index {
+            self.next -= 1;
+        }
+
+        if self.next >= self.skills.len() {
+            self.next = 0;
+        }
+    }
+
+    /// moves the skill at `from` to `to`, shifting the ones in between.
+    pub fn move_skill(&mut self, from: usize, to: usize) {
+        if from >= self.skills.len() || to >= self.skills.len() {
+            return;
+        }
+
+        let skill = self.skills.remove(from);
+        self.skills.insert(to, skill);
+        self.next = 0;
+    }
+}
+
+/// fills up as the member waits for their turn, they act each time it reaches 1.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Deref, DerefMut)]
+pub struct ActionCharge(pub f64);
+
+/// sent each time a party member uses a skill.
+#[derive(Message, Debug, Clone, PartialEq)]
+pub struct SkillUsed {
+    pub member: Entity,
+    pub skill: String,
+    pub damage_type: DamageType,
+    pub damage: f64,
+}
+
+/// sent when a party member reaches a new level.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LeveledUp {
+    pub member: Entity,
+    pub level: u32,
+}
+
+/// a party member, for the UI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartyCard {
+    pub entity: Entity,
+    pub name: String,
+    pub class: String,
+    pub level: u32,
+    pub xp: u64,
+    /// `None` at the max level.
+    pub xp_to_next: Option<u64>,
+    pub stats: CombatStats,
+    pub hp: f64,
+    pub effects: Vec<EffectSummary>,
+    pub skill_order: Vec<String>,
+    pub next_skill: usize,
+    pub skill_slots: usize,
+    /// every skill the member has learned, slotted or not.
+    pub known_skills: Vec<String>,
+}
+
+/// the party, front to back, for the UI.
+#[derive(Resource, Debug, Clone, PartialEq, Default, Deref)]
+pub struct Party(pub Vec<PartyCard>);
+
+/// keeps the class definitions loaded.
+#[derive(Resource, Debug, Clone, Deref)]
+pub struct ClassFolder(pub Handle<LoadedFolder>);
+
+/// the id of a class definition at `path`, its file name without the extension.
+fn class_id(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+
+    name.strip_suffix(CLASS_EXTENSION)?
+        .strip_suffix('.')
+        .map(str::to_string)
+}
+
+pub struct PartyPlugin;
+
+impl Plugin for PartyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<ClassDefinition>::new(&[CLASS_EXTENSION]));
+        app.init_resource::<ClassCatalog>();
+        app.init_resource::<Party>();
+        app.init_resource::<XpMultiplier>();
+        app.add_message::<SkillUsed>();
+        app.add_message::<LeveledUp>();
+        app.add_systems(Startup, load_classes);
+        app.add_systems(
+            Update,
+            (
+                report_load_failures.run_if(on_message::<AssetLoadFailedEvent<ClassDefinition>>),
+                update_catalog.run_if(on_message::<AssetEvent<ClassDefinition>>),
+                spawn_party.run_if(on_message::<AssetEvent<LoadedFolder>>),
+                reset_party.run_if(on_message::<Prestiged>),
+                rest_party.run_if(on_message::<RoomEntered>),
+                gain_experience.run_if(on_message::<RoomCleared>),
+                sync_classes,
+                arrange_skills.run_if(on_message::<UiCommand>),
+                swap_skills,
+                advance_turn.run_if(in_state(PauseStates::Running)),
+                use_skills
+                    .run_if(in_state(AutomationStates::Automation))
+                    .run_if(in_state(PauseStates::Running))
+                    .run_if(should_automate),
+                update_party,
+            )
+                .chain(),
+        );
+    }
+}
+
+fn load_classes(mut cmds: Commands, asset_server: Res<AssetServer>) {
+    cmds.insert_resource(ClassFolder(asset_server.load_folder(CLASSES_FOLDER)));
+}
+
+fn report_load_failures(mut failures: MessageReader<AssetLoadFailedEvent<ClassDefinition>>) {
+    for failure in failures.read() {
+        error!(
+            "failed to load the class {}: {}",
+            failure.path, failure.error
+        );
+    }
+}
+
+/// rebuilds the catalog whenever a class definition is loaded, (hot) reloaded or removed,
+/// reporting what's wrong with the definitions that changed.
+fn update_catalog(
+    mut events: MessageReader<AssetEvent<ClassDefinition>>,
+    definitions: Res<Assets<ClassDefinition>>,
+    asset_server: Res<AssetServer>,
+    mut catalog: ResMut<ClassCatalog>,
+) {
+    for event in events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = event else {
+            continue;
+        };
+        let (Some(class), Some(path)) = (definitions.get(*id), asset_server.get_path(*id)) else {
+            continue;
+        };
+
+        if let Err(errors) = class.validate() {
+            for e in errors {
+                error!("the class {path} is invalid, it will be left out: {e}");
+            }
+        }
+    }
+
+    let mut classes = BTreeMap::new();
+
+    for (id, class) in definitions.iter() {
+        let Some(path) = asset_server.get_path(id) else {
+            continue;
+        };
+        let Some(class_id) = class_id(path.path()) else {
+            warn!("{path} isn't named like a class definition, skipping it");
+            continue;
+        };
+
+        if class.validate().is_err() {
+            continue;
+        }
+
+        if classes.contains_key(&class_id) {
+            error!("more than one class is defined with the id \"{class_id}\", skipping {path}");
+            continue;
+        }
+
+        classes.insert(class_id, class.clone());
+    }
+
+    if catalog.0 != classes {
+        info!("{} classes in the catalog", classes.len());
+        catalog.0 = classes;
+    }
+}
+
+/// brings in the starting party once every class definition has loaded.
+fn spawn_party(
+    mut cmds: Commands,
+    mut events: MessageReader<AssetEvent<LoadedFolder>>,
+    folder: Res<ClassFolder>,
+    catalog: Res<ClassCatalog>,
+    members: Query<(), With<PartyMember>>,
+) {
+    let loaded = events
+        .read()
+        .any(|event| event.is_loaded_with_dependencies(folder.id()));
+
+    if !loaded || !members.is_empty() {
+        return;
+    }
+
+    spawn_starting_party(&mut cmds, &catalog);
+}
+
+/// a prestige starts the party over from level 1, without any equipment.
+fn reset_party(
+    mut cmds: Commands,
+    catalog: Res<ClassCatalog>,
+    members: Query<Entity, With<PartyMember>>,
+) {
+    for member in &members {
+        cmds.entity(member).despawn();
+    }
+
+    spawn_starting_party(&mut cmds, &catalog);
+}
+
+fn spawn_starting_party(cmds: &mut Commands, catalog: &ClassCatalog) {
+    for (position, (name, class_id)) in STARTING_PARTY.into_iter().enumerate() {
+        let Some(class) = catalog.get(class_id) else {
+            error!("there's no \"{class_id}\" class, {name} won't join the party");
+            continue;
+        };
+        let experience = Experience::default();
+        let stats = class.stats_at(experience.level);
+
+        cmds.spawn((
+            Name::new(name),
+            PartyMember {
+                class: class_id.to_string(),
+                position,
+            },
+            experience,
+            BaseStats(stats),
+            EffectiveStats(stats),
+            StatModifiers::default(),
+            Health(stats.hp),
+            StatusEffects::default(),
+            Equipment::default(),
+            SkillOrder::starting(class, experience.level),
+            ActionCharge::default(),
+        ));
+    }
+}
+
+/// gives every party member the xp for each cleared room.
+fn gain_experience(
+    mut cleared: MessageReader<RoomCleared>,
+    catalog: Res<ClassCatalog>,
+    mut members: Query<(
+        Entity,
+        &Name,
+        &PartyMember,
+        &mut Experience,
+        &mut SkillOrder,
+    )>,
+    multiplier: Res<XpMultiplier>,
+    mut leveled_up: MessageWriter<LeveledUp>,
+) {
+    for room in cleared.read() {
+        let xp = XP_PER_ENEMY * room.enemies as u64 * room.depth as u64;
+        let xp = (xp as f64 * **multiplier).round() as u64;
+
+        if xp == 0 {
+            continue;
+        }
+
+        for (entity, name, member, mut experience, mut order) in &mut members {
+            let Some(class) = catalog.get(&member.class) else {
+                continue;
+            };
+            let old_level = experience.level;
+
+            if experience.gain(xp, &class.xp_curve) == 0 {
+                continue;
+            }
+
+            info!("{name} reached level {}", experience.level);
+            leveled_up.write(LeveledUp {
+                member: entity,
+                level: experience.level,
+            });
+
+            // newly learned skills go straight into any free slots.
+            for skill in class.known_skills(experience.level) {
+                if skill.unlock_level > old_level {
+                    order.slot(&skill.name, class.skill_slots);
+                }
+            }
+        }
+    }
+}
+
+/// keeps everyone's stats & skills in line with their class & level.
+fn sync_classes(
+    catalog: Res<ClassCatalog>,
+    mut members: Query<(
+        &PartyMember,
+        Ref<Experience>,
+        &mut BaseStats,
+        &mut SkillOrder,
+    )>,
+) {
+    for (member, experience, mut base, mut order) in &mut members {
+        if !catalog.is_changed() && !experience.is_changed() {
+            continue;
+        }
+
+        let Some(class) = catalog.get(&member.class) else {
+            continue;
+        };
+
+        base.set_if_neq(BaseStats(class.stats_at(experience.level)));
+        order.sync(class, experience.level);
+    }
+}
+
+/// changes party members' battle orders.
+fn arrange_skills(
+    mut commands: MessageReader<UiCommand>,
+    catalog: Res<ClassCatalog>,
+    mut members: Query<(&PartyMember, &Experience, &mut SkillOrder)>,
+) {
+    for command in commands.read() {
+        match *command {
+            UiCommand::SlotSkill {
+                member,
+                skill,
+                ref name,
+            } => {
+                let Ok((member, experience, mut order)) = members.get_mut(member) else {
+                    continue;
+                };
+                let Some(class) = catalog.get(&member.class) else {
+                    continue;
+                };
+                let Some(skill) = class
+                    .known_skills(experience.level)
+                    .nth(skill)
+                    .filter(|skill| skill.name == *name)
+                else {
+                    warn!("the known skills changed before {name} could be slotted");
+                    continue;
+                };
+
+                order.slot(&skill.name, class.skill_slots);
+            }
+            UiCommand::UnslotSkill { member, slot } => {
+                if let Ok((_, _, mut order)) = members.get_mut(member) {
+                    order.unslot(slot);
+                }
+            }
+            UiCommand::MoveSkill { member, from, to } => {
+                if let Ok((_, _, mut order)) = members.get_mut(member) {
+                    order.move_skill(from, to);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// swaps the first two skills in everyone's battle order, the same as moving the second one up
+/// from the UI.
+fn swap_skills(actions: Res<ButtonInput<Action>>, mut orders: Query<&mut SkillOrder>) {
+    if !actions.just_pressed(Action::SwapSkills) {
+        return;
+    }
+
+    for mut order in &mut orders {
+        if order.skills.len() > 1 {
+            order.move_skill(1, 0);
+        }
+    }
+}
+
+/// the party patches itself up at the start of every floor.
+fn rest_party(
+    mut entered: MessageReader<RoomEntered>,
+    mut members: Query<(&EffectiveStats, &mut Health), With<PartyMember>>,
+) {
+    if !entered.read().any(|room| room.room == 0) {
+        return;
+    }
+
+    for (stats, mut health) in &mut members {
+        health.set_if_neq(Health(stats.hp));
+    }
+}
+
+type SkillUserQuery<'a> = (
+    Entity,
+    &'a PartyMember,
+    &'a EffectiveStats,
+    &'a Health,
+    &'a mut SkillOrder,
+    &'a mut ActionCharge,
+);
+
+/// charges everyone's turns at the automation speed, scaled by their speed, and uses the next
+/// skill in their order each time a turn comes up. members who are stunned or out of hp don't
+/// get turns.
+fn use_skills(
+    catalog: Res<ClassCatalog>,
+    automation_speed: Res<AutomationSpeed>,
+    time: Res<Time>,
+    mut members: Query<SkillUserQuery, Without<Stunned>>,
+    mut used: MessageWriter<SkillUsed>,
+) {
+    for (entity, member, stats, health, mut order, mut charge) in &mut members {
+        let Some(class) = catalog.get(&member.class) else {
+            continue;
+        };
+
+        if **health <= 0.0 {
+            continue;
+        }
+
+        **charge += **automation_speed * stats.speed * time.delta_secs_f64();
+
+        while **charge >= 1.0 {
+            if !take_turn(entity, class, stats, &mut order, &mut used) {
+                // nothing to use, so there's no point saving the turn up.
+                **charge = 0.0;
+                break;
+            }
+
+            **charge -= 1.0;
+        }
+    }
+}
+
+/// gives everyone who can act a turn straight away, for playing without automation.
+fn advance_turn(
+    actions: Res<ButtonInput<Action>>,
+    catalog: Res<ClassCatalog>,
+    mut members: Query<SkillUserQuery, Without<Stunned>>,
+    mut used: MessageWriter<SkillUsed>,
+) {
+    if !actions.just_pressed(Action::AdvanceTurn) {
+        return;
+    }
+
+    for (entity, member, stats, health, mut order, _) in &mut members {
+        let Some(class) = catalog.get(&member.class) else {
+            continue;
+        };
+
+        if **health > 0.0 {
+            take_turn(entity, class, stats, &mut order, &mut used);
+        }
+    }
+}
+
+/// uses the next skill in the member's order, false if they have nothing to use.
+fn take_turn(
+    entity: Entity,
+    class: &ClassDefinition,
+    stats: &EffectiveStats,
+    order: &mut SkillOrder,
+    used: &mut MessageWriter<SkillUsed>,
+) -> bool {
+    let Some(skill) = order.next().and_then(|skill| class.skill(skill)) else {
+        return false;
+    };
+
+    used.write(SkillUsed {
+        member: entity,
+        skill: skill.name.clone(),
+        damage_type: skill.damage_type,
+        damage: stats.attack * skill.power,
+    });
+    order.advance();
+
+    true
+}
+
+type PartyCardQuery<'a> = (
+    Entity,
+    &'a Name,
+    &'a PartyMember,
+    Ref<'a, Experience>,
+    Ref<'a, EffectiveStats>,
+    Ref<'a, Health>,
+    Ref<'a, StatusEffects>,
+    Ref<'a, SkillOrder>,
+);
+
+fn update_party(
+    catalog: Res<ClassCatalog>,
+    effects_catalog: Res<StatusEffectCatalog>,
+    members: Query<PartyCardQuery>,
+    mut removed: RemovedComponents<PartyMember>,
+    mut party: ResMut<Party>,
+) {
+    let removed = removed.read().count() > 0;
+    let changed = members
+        .iter()
+        .any(|(_, _, _, experience, stats, health, effects, order)| {
+            experience.is_changed()
+                || stats.is_changed()
+                || health.is_changed()
+                || effects.is_changed()
+                || order.is_changed()
+        });
+
+    if !removed && !changed && !catalog.is_changed() {
+        return;
+    }
+
+    let mut cards: Vec<(usize, PartyCard)> = members
+        .iter()
+        .filter_map(
+            |(entity, name, member, experience, stats, health, effects, order)| {
+                let class = catalog.get(&member.class)?;
+                let card = PartyCard {
+                    entity,
+                    name: name.to_string(),
+                    class: class.name.clone(),
+                    level: experience.level,
+                    xp: experience.xp,
+                    xp_to_next: (experience.level < MAX_LEVEL)
+                        .then(|| class.xp_curve.xp_to_next(experience.level)),
+                    stats: stats.0,
+                    hp: **health,
+                    effects: effects.summary(&effects_catalog),
+                    skill_order: order.skills.clone(),
+                    next_skill: order.next_index(),
+                    skill_slots: class.skill_slots,
+                    known_skills: class
+                        .known_skills(experience.level)
+                        .map(|skill| skill.name.clone())
+                        .collect(),
+                };
+
+                Some((member.position, card))
+            },
+        )
+        .collect();
+
+    cards.sort_by_key(|(position, _)| *position);
+    // effects change every frame while they run, so only tell the UI when a card really changes.
+    party.set_if_neq(Party(cards.into_iter().map(|(_, card)| card).collect()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::party_plugin::class::ClassSkill;
+
+    const CURVE: XpCurve = XpCurve {
+        base: 10.0,
+        exponent: 1.0,
+    };
+
+    fn order(skills: &[&str]) -> SkillOrder {
+        SkillOrder {
+            skills: skills.iter().map(|skill| skill.to_string()).collect(),
+            next: 0,
+        }
+    }
+
+    #[test]
+    fn gain_levels_up_as_many_times_as_it_covers() {
+        let mut experience = Experience::default();
+
+        assert_eq!(experience.gain(5, &CURVE), 0);
+        // 10 to reach level 2, 20 more to reach level 3.
+        assert_eq!(experience.gain(30, &CURVE), 2);
+        assert_eq!(experience, Experience { level: 3, xp: 5 });
+    }
+
+    #[test]
+    fn gain_stops_at_the_max_level() {
+        let mut experience = Experience {
+            level: MAX_LEVEL - 1,
+            xp: 0,
+        };
+
+        assert_eq!(experience.gain(u64::MAX, &CURVE), 1);
+        assert_eq!(
+            experience,
+            Experience {
+                level: MAX_LEVEL,
+                xp: 0
+            }
+        );
+        assert_eq!(experience.gain(100, &CURVE), 0);
+    }
+
+    #[test]
+    fn slot_needs_a_free_slot_and_a_new_skill() {
+        let mut order = order(&["slash"]);
+
+        assert!(!order.slot("slash", 3));
+        assert!(order.slot("bash", 2));
+        assert!(!order.slot("cleave", 2));
+        assert_eq!(order.skills, ["slash", "bash"]);
+    }
+
+    #[test]
+    fn unslot_keeps_the_next_skill() {
+        let mut order = order(&["slash", "bash", "cleave"]);
+        order.advance();
+        order.advance();
+
+        order.unslot(0);
+
+        assert_eq!(order.next().map(String::as_str), Some("cleave"));
+
+        order.unslot(5);
+
+        assert_eq!(order.skills, ["bash", "cleave"]);
+    }
+
+    #[test]
+    fn move_skill_shifts_the_ones_in_between() {
+        let mut order = order(&["slash", "bash", "cleave"]);
+        order.advance();
+
+        order.move_skill(2, 0);
+
+        assert_eq!(order.skills, ["cleave", "slash", "bash"]);
+        assert_eq!(order.next_index(), 0);
+
+        order.move_skill(0, 3);
+
+        assert_eq!(order.skills, ["cleave", "slash", "bash"]);
+    }
+
+    #[test]
+    fn advance_wraps_around() {
+        let mut order = order(&["slash", "bash"]);
+
+        order.advance();
+        order.advance();
+
+        assert_eq!(order.next_index(), 0);
+
+        let mut empty = SkillOrder::default();
+        empty.advance();
+
+        assert_eq!(empty.next(), None);
+    }
+
+    #[test]
+    fn sync_drops_what_the_class_doesnt_know() {
+        let class = ClassDefinition {
+            name: "rogue".to_string(),
+            description: String::new(),
+            base_stats: CombatStats::default(),
+            growth: CombatStats::default(),
+            skill_slots: 1,
+            skills: vec![ClassSkill {
+                name: "stab".to_string(),
+                unlock_level: 1,
+                power: 1.0,
+                damage_type: DamageType::Physical,
+                applies: None,
+            }],
+            xp_curve: CURVE,
+        };
+        let mut order = order(&["slash", "stab", "stab"]);
+
+        order.sync(&class, 1);
+
+        assert_eq!(order.skills, ["stab"]);
+        assert_eq!(SkillOrder::starting(&class, 1), order);
+    }
+}
@@ -0,0 +1,665 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::{self, Display},
+    time::Duration,
+};
+
+use bevy::{ecs::system::SystemParam, prelude::*, time::common_conditions::on_timer};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    LongestIdleTimeSeconds,
+    achievement_plugin::Achievements,
+    action_plugin::{Action, ActionMap},
+    base_plugin::UiCommand,
+    bestiary_plugin::{Bestiary, Encounter},
+    big_num::BigNum,
+    dungeon_plugin::{DungeonFloor, DungeonRng, PartyProgress, RoomEntered},
+    economy_plugin::{Currency, IncomeMultiplier, ledger::Ledger},
+    idle_time_plugin::AutomationSettings,
+    inventory_plugin::{Inventory, loot::LootRng},
+    modifier::{Rate, Rates},
+    party_plugin::{Experience, PartyMember, XpMultiplier},
+    save_file::{SaveFile, SavePath},
+    stats_plugin::LifetimeStats,
+    status_effect_plugin::{GlobalEffects, StatusEffectCatalog, effect::StatusEffects},
+    theme::Theme,
+};
+
+/// catches meta upgrades bought between prestiges, the prestige progress is saved after every
+/// prestige & on exit anyway.
+pub const PRESTIGE_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
+/// how often the confirmation screen's preview is recalculated while it's open.
+pub const PREVIEW_INTERVAL: Duration = Duration::from_millis(250);
+/// the least renown a prestige has to be worth.
+pub const MIN_PRESTIGE_RENOWN: f64 = 1.0;
+/// set to a number to seed the current run with it, to replay a run's floors & loot.
+pub const SEED_ENV: &str = "IDLE_DUNGEONEER_SEED";
+
+/// a permanent upgrade bought with renown, kept through every prestige.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetaUpgrade {
+    Prosperity,
+    Veterans,
+    HeadStart,
+}
+
+impl MetaUpgrade {
+    pub const ALL: [Self; 3] = [Self::Prosperity, Self::Veterans, Self::HeadStart];
+
+    pub fn max_level(&self) -> u32 {
+        match self {
+            Self::Prosperity => 20,
+            Self::Veterans => 10,
+            Self::HeadStart => 10,
+        }
+    }
+
+    /// the renown it takes to go from `level` to the next one.
+    pub fn cost(&self, level: u32) -> BigNum {
+        let (base, growth) = match self {
+            Self::Prosperity => (1.0, 2.0),
+            Self::Veterans => (2.0, 2.0),
+            Self::HeadStart => (3.0, 3.0),
+        };
+
+        BigNum::from(base) * BigNum::from(growth).powf(level as f64)
+    }
+
+    /// what the upgrade does at `level`.
+    pub fn effect(&self, level: u32) -> String {
+        match self {
+            Self::Prosperity => format!("+{}% income", level * 25),
+            Self::Veterans => format!("+{}% party xp", level * 20),
+            Self::HeadStart => format!("+{} starting gold", head_start_gold(level)),
+        }
+    }
+}
+
+impl Display for MetaUpgrade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Prosperity => "prosperity",
+            Self::Veterans => "veterans",
+            Self::HeadStart => "head start",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+fn head_start_gold(level: u32) -> BigNum {
+    BigNum::from(100.0 * level as f64)
+}
+
+/// why a prestige action couldn't go through.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrestigeError {
+    NotEnoughRenown(BigNum),
+    MaxLevel(MetaUpgrade),
+    TooEarly(BigNum),
+}
+
+impl Display for PrestigeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEnoughRenown(cost) => write!(f, "not enough renown, it costs {cost}"),
+            Self::MaxLevel(upgrade) => write!(f, "{upgrade} is already at its max level"),
+            Self::TooEarly(renown) => write!(
+                f,
+                "prestiging now would only earn {renown} renown, it has to be at least \
+                 {MIN_PRESTIGE_RENOWN}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PrestigeError {}
+
+/// the run-scoped progress renown is worked out from, that isn't kept anywhere else.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunRecord {
+    pub deepest_floor: usize,
+    pub longest_idle_secs: f64,
+    /// what the run's floors & loot are drawn from. picked at random when the run starts, unless
+    /// it's overridden with `SEED_ENV`.
+    pub seed: u64,
+}
+
+impl Default for RunRecord {
+    fn default() -> Self {
+        Self {
+            deepest_floor: 0,
+            longest_idle_secs: 0.0,
+            seed: rand::rng().random(),
+        }
+    }
+}
+
+/// how much renown each part of a run is worth.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RenownBreakdown {
+    pub depth: f64,
+    pub idle: f64,
+    pub currency: f64,
+}
+
+impl RenownBreakdown {
+    pub fn new(run: &RunRecord, earned: &BTreeMap<Currency, BigNum>) -> Self {
+        Self {
+            depth: run.deepest_floor.saturating_sub(1) as f64 * 0.5,
+            idle: (run.longest_idle_secs / 60.0).sqrt(),
+            // a point for every order of magnitude earned past a thousand.
+            currency: earned
+                .values()
+                .map(|earned| (earned.log10() - 3.0).max(0.0))
+                .sum(),
+        }
+    }
+
+    /// the renown a prestige pays out, only whole points count.
+    pub fn total(&self) -> BigNum {
+        BigNum::from((self.depth + self.idle + self.currency).floor())
+    }
+}
+
+/// everything that survives a prestige.
+#[derive(Resource, Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrestigeState {
+    /// renown that hasn't been spent.
+    pub renown: BigNum,
+    pub total_renown: BigNum,
+    pub prestiges: u64,
+    pub meta_upgrades: BTreeMap<MetaUpgrade, u32>,
+    pub run: RunRecord,
+}
+
+impl PrestigeState {
+    pub fn level(&self, upgrade: MetaUpgrade) -> u32 {
+        self.meta_upgrades
+            .get(&upgrade)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn income_multiplier(&self) -> f64 {
+        1.0 + 0.25 * self.level(MetaUpgrade::Prosperity) as f64
+    }
+
+    pub fn xp_multiplier(&self) -> f64 {
+        1.0 + 0.2 * self.level(MetaUpgrade::Veterans) as f64
+    }
+
+    /// the gold every run starts with on top of the usual amount.
+    pub fn starting_gold(&self) -> BigNum {
+        head_start_gold(self.level(MetaUpgrade::HeadStart))
+    }
+
+    pub fn buy(&mut self, upgrade: MetaUpgrade) -> Result<(), PrestigeError> {
+        let level = self.level(upgrade);
+
+        if level >= upgrade.max_level() {
+            return Err(PrestigeError::MaxLevel(upgrade));
+        }
+
+        let cost = upgrade.cost(level);
+
+        if self.renown < cost {
+            return Err(PrestigeError::NotEnoughRenown(cost));
+        }
+
+        self.renown -= cost;
+        self.meta_upgrades.insert(upgrade, level + 1);
+
+        Ok(())
+    }
+}
+
+impl SaveFile for PrestigeState {
+    const NAME: &'static str = "the prestige progress";
+    const PATH: &'static str = "saves/prestige.json";
+}
+
+/// whether the prestige confirmation screen is open.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PrestigeScreen {
+    pub open: bool,
+}
+
+/// what prestiging right now would earn, keep & lose. only kept up to date while the
+/// confirmation screen is open.
+#[derive(Resource, Debug, Clone, PartialEq, Default)]
+pub struct PrestigePreview {
+    pub breakdown: RenownBreakdown,
+    pub renown: BigNum,
+    pub kept: Vec<String>,
+    pub lost: Vec<String>,
+}
+
+/// sent after a prestige, once the run-scoped resources have been reset.
+#[derive(Message, Debug, Clone, Copy, PartialEq)]
+pub struct Prestiged {
+    pub renown: BigNum,
+}
+
+/// the rngs drawn from with a run's seed, and the floor they lay out.
+#[derive(SystemParam)]
+struct RunRngs<'w> {
+    floor: ResMut<'w, DungeonFloor>,
+    dungeon: ResMut<'w, DungeonRng>,
+    loot: ResMut<'w, LootRng>,
+}
+
+impl RunRngs<'_> {
+    /// reseeds the rngs & lays out the first floor with them.
+    fn seed(&mut self, seed: u64) {
+        *self.dungeon = DungeonRng::from_seed(seed);
+        *self.loot = LootRng::from_seed(seed);
+        *self.floor = DungeonFloor::generate(1, &mut **self.dungeon);
+        info!("run seed: {seed}");
+    }
+}
+
+/// the resources scoped to a run, which a prestige resets.
+#[derive(SystemParam)]
+struct Run<'w> {
+    ledger: ResMut<'w, Ledger>,
+    inventory: ResMut<'w, Inventory>,
+    rngs: RunRngs<'w>,
+    progress: ResMut<'w, PartyProgress>,
+    encounter: ResMut<'w, Encounter>,
+    longest_idle_time: ResMut<'w, LongestIdleTimeSeconds>,
+    entered: MessageWriter<'w, RoomEntered>,
+}
+
+impl Run<'_> {
+    /// starts a new run from `seed`, with `starting_gold` on top of the usual starting balance.
+    fn reset(&mut self, starting_gold: BigNum, seed: u64) {
+        *self.ledger = Ledger::default();
+        *self.ledger.balances.entry(Currency::Gold).or_default() += starting_gold;
+        *self.inventory = Inventory::default();
+        self.rngs.seed(seed);
+        *self.progress = PartyProgress::default();
+        self.encounter.clear();
+        **self.longest_idle_time = 0.0;
+        self.entered.write(RoomEntered {
+            depth: self.rngs.floor.depth,
+            room: self.progress.room,
+            enemies: self.rngs.floor.rooms[self.progress.room].enemies,
+        });
+    }
+}
+
+pub struct PrestigePlugin;
+
+impl Plugin for PrestigePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SavePath<PrestigeState>>();
+        app.init_resource::<PrestigeScreen>();
+        app.init_resource::<PrestigePreview>();
+        app.add_message::<Prestiged>();
+        app.add_systems(PreStartup, (load_prestige, seed_run).chain());
+        app.add_systems(Startup, apply_bonuses);
+        app.add_systems(
+            Update,
+            (
+                track_run,
+                (toggle_screen, buy_meta_upgrades, prestige).run_if(on_message::<UiCommand>),
+                apply_bonuses.run_if(
+                    resource_changed::<PrestigeState>
+                        .or(resource_changed::<Achievements>)
+                        .or(resource_changed::<Rates>),
+                ),
+                update_preview.run_if(
+                    resource_equals(PrestigeScreen { open: true })
+                        .and(on_timer(PREVIEW_INTERVAL).or(resource_changed::<PrestigeScreen>)),
+                ),
+            )
+                .chain(),
+        );
+        app.add_systems(
+            Last,
+            save_prestige.run_if(
+                on_timer(PRESTIGE_AUTOSAVE_INTERVAL)
+                    .or(on_message::<AppExit>)
+                    .or(on_message::<Prestiged>),
+            ),
+        );
+    }
+}
+
+fn load_prestige(mut cmds: Commands, save_path: Res<SavePath<PrestigeState>>) {
+    let state = PrestigeState::load_or_default(&save_path.path);
+
+    cmds.insert_resource(state);
+}
+
+/// seeds the run that was loaded, or the one `SEED_ENV` asks for.
+fn seed_run(mut state: ResMut<PrestigeState>, mut rngs: RunRngs) {
+    if let Ok(seed) = env::var(SEED_ENV) {
+        match seed.parse() {
+            Ok(seed) => state.run.seed = seed,
+            Err(e) => error!("{SEED_ENV} isn't a seed, keeping the run's own: {e}"),
+        }
+    }
+
+    rngs.seed(state.run.seed);
+}
+
+/// keeps the run record up to date with the deepest floor & longest idle time so far.
+fn track_run(
+    mut entered: MessageReader<RoomEntered>,
+    longest_idle_time: Res<LongestIdleTimeSeconds>,
+    mut state: ResMut<PrestigeState>,
+) {
+    let deepest = entered
+        .read()
+        .map(|room| room.depth)
+        .max()
+        .unwrap_or_default();
+
+    if deepest > state.run.deepest_floor {
+        state.run.deepest_floor = deepest;
+    }
+
+    if **longest_idle_time > state.run.longest_idle_secs {
+        state.run.longest_idle_secs = **longest_idle_time;
+    }
+}
+
+fn toggle_screen(mut commands: MessageReader<UiCommand>, mut screen: ResMut<PrestigeScreen>) {
+    for command in commands.read() {
+        if let UiCommand::TogglePrestigeScreen = command {
+            screen.open = !screen.open;
+        }
+    }
+}
+
+fn buy_meta_upgrades(mut commands: MessageReader<UiCommand>, mut state: ResMut<PrestigeState>) {
+    for command in commands.read() {
+        if let UiCommand::BuyMetaUpgrade(upgrade) = *command
+            && let Err(e) = state.buy(upgrade)
+        {
+            warn!("{e}");
+        }
+    }
+}
+
+/// trades the run for renown, resetting everything that's scoped to a run.
+fn prestige(
+    mut commands: MessageReader<UiCommand>,
+    mut state: ResMut<PrestigeState>,
+    mut screen: ResMut<PrestigeScreen>,
+    mut run: Run,
+    mut prestiged: MessageWriter<Prestiged>,
+) {
+    for command in commands.read() {
+        let UiCommand::Prestige = command else {
+            continue;
+        };
+        let renown = RenownBreakdown::new(&state.run, &run.ledger.earned).total();
+
+        if renown < BigNum::from(MIN_PRESTIGE_RENOWN) {
+            warn!("{}", PrestigeError::TooEarly(renown));
+            continue;
+        }
+
+        state.renown += renown;
+        state.total_renown += renown;
+        state.prestiges += 1;
+        state.run = RunRecord::default();
+        run.reset(state.starting_gold(), state.run.seed);
+        screen.open = false;
+
+        info!("prestiged for {renown} renown");
+        prestiged.write(Prestiged { renown });
+    }
+}
+
+/// scales income & xp by the meta upgrades, the bonuses from unlocked achievements & the rates
+/// status effects change.
+fn apply_bonuses(
+    state: Res<PrestigeState>,
+    achievements: Res<Achievements>,
+    rates: Res<Rates>,
+    mut income_multiplier: ResMut<IncomeMultiplier>,
+    mut xp_multiplier: ResMut<XpMultiplier>,
+) {
+    income_multiplier.set_if_neq(IncomeMultiplier(
+        state.income_multiplier() * achievements.income_multiplier() * rates.get(Rate::Income),
+    ));
+    xp_multiplier.set_if_neq(XpMultiplier(
+        state.xp_multiplier() * achievements.xp_multiplier() * rates.get(Rate::Xp),
+    ));
+}
+
+/// the resources a prestige keeps, besides the `PrestigeState`.
+#[derive(SystemParam)]
+struct Kept<'w> {
+    bestiary: Res<'w, Bestiary>,
+    achievements: Res<'w, Achievements>,
+    lifetime_stats: Res<'w, LifetimeStats>,
+    action_map: Res<'w, ActionMap>,
+    theme: Res<'w, Theme>,
+    automation_settings: Res<'w, AutomationSettings>,
+    global_effects: Res<'w, GlobalEffects>,
+}
+
+/// the parts of the run the preview lists, read-only so working it out can't touch the run.
+#[derive(SystemParam)]
+struct RunView<'w> {
+    ledger: Res<'w, Ledger>,
+    inventory: Res<'w, Inventory>,
+    floor: Res<'w, DungeonFloor>,
+    longest_idle_time: Res<'w, LongestIdleTimeSeconds>,
+}
+
+fn update_preview(
+    state: Res<PrestigeState>,
+    run: RunView,
+    kept_resources: Kept,
+    members: Query<(&Name, &Experience, &StatusEffects), With<PartyMember>>,
+    catalog: Res<StatusEffectCatalog>,
+    mut preview: ResMut<PrestigePreview>,
+) {
+    let RunView {
+        ledger,
+        inventory,
+        floor,
+        longest_idle_time,
+    } = run;
+    let Kept {
+        bestiary,
+        achievements,
+        lifetime_stats,
+        action_map,
+        theme,
+        automation_settings,
+        global_effects,
+    } = kept_resources;
+    let breakdown = RenownBreakdown::new(&state.run, &ledger.earned);
+    let renown = breakdown.total();
+    let mut kept = vec![format!("{} renown", state.renown + renown)];
+    let mut lost = Vec::new();
+
+    for upgrade in MetaUpgrade::ALL {
+        let level = state.level(upgrade);
+
+        if level > 0 {
+            kept.push(format!("{upgrade} lvl {level} ({})", upgrade.effect(level)));
+        }
+    }
+
+    kept.push(format!(
+        "{} enemies in the bestiary",
+        bestiary
+            .values()
+            .filter(|entry| entry.encountered > 0)
+            .count()
+    ));
+    kept.push(format!(
+        "{} achievements & their bonuses",
+        achievements.len()
+    ));
+    kept.push(format!(
+        "lifetime stats of {:.0} min played & {} inputs",
+        lifetime_stats.play_secs / 60.0,
+        lifetime_stats.inputs
+    ));
+
+    let rebound = Action::ALL
+        .into_iter()
+        .filter(|action| action_map.get(action) != Some(&action.default_binding()))
+        .count();
+
+    kept.push(if rebound > 0 {
+        format!("key bindings, {rebound} of them changed")
+    } else {
+        "the default key bindings".to_string()
+    });
+    kept.push(format!("the {} theme", theme.palette));
+    kept.push(if automation_settings.on_focus_loss {
+        format!(
+            "automating {:.0} sec after losing focus",
+            automation_settings.focus_loss_delay.as_secs_f64()
+        )
+    } else {
+        "not automating on focus loss".to_string()
+    });
+
+    for summary in global_effects.summary(&catalog) {
+        kept.push(format!("the {summary} {}", summary.kind));
+    }
+
+    for currency in Currency::ALL {
+        let balance = ledger.balance(currency);
+
+        if !balance.is_zero() {
+            lost.push(format!("{balance} {currency}"));
+        }
+    }
+
+    for (generator, owned) in &ledger.generators {
+        lost.push(format!("{owned} {generator}s"));
+    }
+
+    for upgrade in &ledger.upgrades {
+        lost.push(format!("the {upgrade} upgrade"));
+    }
+
+    for (name, experience, effects) in &members {
+        lost.push(format!("{name}'s level {} & equipment", experience.level));
+
+        for summary in effects.summary(&catalog) {
+            lost.push(format!("{name}'s {summary} {}", summary.kind));
+        }
+    }
+
+    if !inventory.is_empty() {
+        lost.push(format!("{} stacks of items", inventory.len()));
+    }
+
+    lost.push(format!(
+        "floor {}, the deepest this run was {}",
+        floor.depth, state.run.deepest_floor
+    ));
+    lost.push(format!(
+        "the longest idle time of {:.0} sec",
+        **longest_idle_time
+    ));
+
+    preview.set_if_neq(PrestigePreview {
+        breakdown,
+        renown,
+        kept,
+        lost,
+    });
+}
+
+fn save_prestige(state: Res<PrestigeState>, save_path: Res<SavePath<PrestigeState>>) {
+    state.save_or_log(&save_path.path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(deepest_floor: usize, longest_idle_secs: f64) -> RunRecord {
+        RunRecord {
+            deepest_floor,
+            longest_idle_secs,
+            seed: 0,
+        }
+    }
+
+    #[test]
+    fn breakdown_of_an_empty_run_is_nothing() {
+        let breakdown = RenownBreakdown::new(&run(0, 0.0), &BTreeMap::new());
+
+        assert_eq!(breakdown, RenownBreakdown::default());
+        assert!(breakdown.total().is_zero());
+    }
+
+    #[test]
+    fn breakdown_scores_each_part_of_the_run() {
+        let earned = BTreeMap::from([
+            (Currency::Gold, BigNum::from(1.0e6)),
+            (Currency::Essence, BigNum::from(50.0)),
+        ]);
+        let breakdown = RenownBreakdown::new(&run(9, 3600.0), &earned);
+
+        assert_eq!(breakdown.depth, 4.0);
+        assert!((breakdown.idle - 60.0_f64.sqrt()).abs() < 1e-9);
+        assert!((breakdown.currency - 3.0).abs() < 1e-9);
+        // 4 + 7.75 + 3, rounded down.
+        assert_eq!(breakdown.total(), BigNum::from(14.0));
+    }
+
+    #[test]
+    fn only_whole_points_of_renown_count() {
+        let breakdown = RenownBreakdown {
+            depth: 0.5,
+            idle: 0.4,
+            currency: 0.0,
+        };
+
+        assert!(breakdown.total() < BigNum::from(MIN_PRESTIGE_RENOWN));
+    }
+
+    #[test]
+    fn buying_meta_upgrades_spends_renown() {
+        let mut state = PrestigeState {
+            renown: BigNum::from(3.0),
+            ..default()
+        };
+
+        assert_eq!(state.buy(MetaUpgrade::Prosperity), Ok(()));
+        assert_eq!(state.buy(MetaUpgrade::Prosperity), Ok(()));
+        assert_eq!(
+            state.buy(MetaUpgrade::Prosperity),
+            Err(PrestigeError::NotEnoughRenown(BigNum::from(4.0)))
+        );
+        assert_eq!(state.level(MetaUpgrade::Prosperity), 2);
+        assert_eq!(state.income_multiplier(), 1.5);
+        assert!(state.renown.is_zero());
+    }
+
+    #[test]
+    fn meta_upgrades_stop_at_their_max_level() {
+        let mut state = PrestigeState {
+            renown: BigNum::from(1.0e9),
+            ..default()
+        };
+        state.meta_upgrades.insert(MetaUpgrade::Veterans, 10);
+
+        assert_eq!(
+            state.buy(MetaUpgrade::Veterans),
+            Err(PrestigeError::MaxLevel(MetaUpgrade::Veterans))
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    fs, io,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use serde::{Serialize, de::DeserializeOwned};
+
+/// state kept in a file between sessions, as pretty printed JSON unless `load` & `save` are
+/// overridden.
+pub trait SaveFile: Serialize + DeserializeOwned + Default {
+    /// what the file holds, for log messages.
+    const NAME: &'static str;
+    /// where the file is kept unless `SavePath` says otherwise, relative to the working directory.
+    const PATH: &'static str;
+
+    fn load(path: &Path) -> io::Result<Self> {
+        let file = fs::File::open(path)?;
+
+        Ok(serde_json::from_reader(io::BufReader::new(file))?)
+    }
+
+    fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let file = fs::File::create(path)?;
+        serde_json::to_writer_pretty(io::BufWriter::new(file), self)?;
+
+        Ok(())
+    }
+
+    /// loads the file, starting from scratch if there isn't one yet or it can't be read.
+    fn load_or_default(path: &Path) -> Self {
+        match Self::load(path) {
+            Ok(loaded) => loaded,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                error!(
+                    "failed to load {} from {}, starting from scratch: {e}",
+                    Self::NAME,
+                    path.display()
+                );
+                Self::default()
+            }
+        }
+    }
+
+    /// saves the file, logging why if it can't be.
+    fn save_or_log(&self, path: &Path) {
+        if let Err(e) = self.save(path) {
+            error!("failed to save {} to {}: {e}", Self::NAME, path.display());
+        }
+    }
+}
+
+/// where a `T` is saved, so tests & tools can point it somewhere other than `SaveFile::PATH`.
+#[derive(Resource)]
+pub struct SavePath<T> {
+    pub path: PathBuf,
+    file: PhantomData<fn() -> T>,
+}
+
+impl<T> SavePath<T> {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            file: PhantomData,
+        }
+    }
+}
+
+impl<T: SaveFile> Default for SavePath<T> {
+    fn default() -> Self {
+        Self::new(T::PATH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::backend::temp_path;
+
+    #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+    struct Counter {
+        count: u32,
+    }
+
+    impl SaveFile for Counter {
+        const NAME: &'static str = "the counter";
+        const PATH: &'static str = "saves/counter.json";
+    }
+
+    #[test]
+    fn round_trips() {
+        let path = temp_path("round_trip/counter.json");
+        let counter = Counter { count: 3 };
+
+        counter.save(&path).unwrap();
+
+        assert_eq!(Counter::load(&path).unwrap(), counter);
+        assert_eq!(Counter::load_or_default(&path), counter);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn missing_or_broken_files_start_from_scratch() {
+        let path = temp_path("broken/counter.json");
+
+        assert_eq!(Counter::load_or_default(&path), Counter::default());
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "{ not json").unwrap();
+
+        assert!(Counter::load(&path).is_err());
+        assert_eq!(Counter::load_or_default(&path), Counter::default());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn save_path_defaults_to_the_file_path() {
+        assert_eq!(
+            SavePath::<Counter>::default().path,
+            PathBuf::from(Counter::PATH)
+        );
+    }
+}
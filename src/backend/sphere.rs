@@ -1,4 +1,5 @@
 use bevy::{
+    mesh::VertexAttributeValues,
     pbr::wireframe::{NoWireframe, WireframeColor, WireframeConfig, WireframePlugin},
     prelude::*,
 };
@@ -6,14 +7,95 @@ use noise::{NoiseFn, Perlin};
 use rand::Rng;
 use std::f32::consts::{PI, TAU};
 
+use crate::backend::{
+    AutomationSpeed, CurrentIdleTimeSeconds, LongestIdleTimeSeconds, base_plugin::AutomationStates,
+    bevy_scene_plugin::OrbitCamera, theme::Theme,
+};
+
+/// the automation speed, in Hz, at which the sphere is at its most turbulent.
+pub const HOT_AUTOMATION_SPEED: f64 = 20.0;
+/// how quickly the sphere eases towards its target mood, higher is snappier.
+const MOOD_SMOOTHING: f32 = 2.0;
+
 #[derive(Clone, Copy, Debug, Resource)]
-pub struct PerlinWrapper(Perlin);
+pub struct PerlinWrapper(pub Perlin);
 
 #[derive(Clone, Copy, Debug, Component)]
 pub struct UndulateSphere;
 
-#[derive(Clone, Copy, Debug, Component)]
-pub struct BaseSphere;
+/// the undeformed shape of the undulating sphere, cached so it isn't re-read from a second mesh
+/// every frame. the mesh's buffers are deformed in place rather than rebuilt.
+#[derive(Clone, Debug, Component)]
+pub struct SphereDeformation {
+    /// the vertices of the undeformed sphere, which are also the directions they get pushed in.
+    base_positions: Vec<Vec3>,
+    triangles: Vec<[usize; 3]>,
+    /// where the vertex normals are accumulated, kept around to avoid allocating every frame.
+    normals: Vec<Vec3>,
+}
+
+impl SphereDeformation {
+    pub fn new(mesh: &Mesh) -> Option<Self> {
+        let base_positions: Vec<Vec3> = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)?
+            .as_float3()?
+            .iter()
+            .copied()
+            .map(Vec3::from)
+            .collect();
+        let indices: Vec<usize> = mesh.indices()?.iter().collect();
+        let triangles = indices
+            .chunks_exact(3)
+            .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+            .collect();
+
+        Some(Self {
+            normals: vec![Vec3::ZERO; base_positions.len()],
+            base_positions,
+            triangles,
+        })
+    }
+
+    /// pushes every vertex of `mesh` out from the base sphere by `displacement(base_position)`,
+    /// as a fraction of its radius, then recomputes the smooth normals.
+    pub fn deform(&mut self, mesh: &mut Mesh, displacement: impl Fn(Vec3) -> f32) {
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+        else {
+            return;
+        };
+
+        for (position, base) in positions.iter_mut().zip(&self.base_positions) {
+            *position = (base * (1.0 + displacement(*base))).to_array();
+        }
+
+        // area weighted face normals, summed per vertex.
+        self.normals.fill(Vec3::ZERO);
+
+        for &[a, b, c] in &self.triangles {
+            let (pa, pb, pc) = (
+                Vec3::from(positions[a]),
+                Vec3::from(positions[b]),
+                Vec3::from(positions[c]),
+            );
+            let normal = (pb - pa).cross(pc - pa);
+
+            self.normals[a] += normal;
+            self.normals[b] += normal;
+            self.normals[c] += normal;
+        }
+
+        let Some(VertexAttributeValues::Float32x3(normals)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            return;
+        };
+
+        for (normal, sum) in normals.iter_mut().zip(&self.normals) {
+            *normal = sum.normalize_or_zero().to_array();
+        }
+    }
+}
 
 #[derive(Clone, Copy, Debug, Component)]
 pub struct BoundingSphere;
@@ -24,6 +106,34 @@ pub struct UndulateTimer(Timer);
 #[derive(Resource, Default, Deref, DerefMut)]
 pub struct Zoom(f32);
 
+/// how agitated the sphere looks, driven by the idle time budget & the automation speed. the
+/// sphere is calm when there is little idle time banked & turbulent when automation is running
+/// hot.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq)]
+pub struct SphereMood {
+    /// 0.0 is completely calm, 1.0 is as turbulent as the sphere gets.
+    pub turbulence: f32,
+    /// 0.0 when not automating, 1.0 when automating at `HOT_AUTOMATION_SPEED` or faster.
+    pub heat: f32,
+}
+
+impl SphereMood {
+    /// how far the surface is pushed in & out by the noise.
+    pub fn amplitude(&self) -> f32 {
+        0.05 + 0.3 * self.turbulence
+    }
+
+    /// how fast the noise pattern moves, in radians per second.
+    pub fn undulation_speed(&self) -> f32 {
+        (0.1 + 0.9 * self.turbulence) * TAU
+    }
+
+    /// rotations per second.
+    pub fn rotation_speed(&self) -> f32 {
+        0.015625 + 0.109375 * self.turbulence
+    }
+}
+
 // Define a component to designate a rotation speed to an entity.
 #[derive(Component, Clone, Copy, Debug)]
 struct Rotatable {
@@ -42,13 +152,7 @@ impl Plugin for SpherePlugin {
                 global: true,
                 // Controls the default color of all wireframes. Used as the default color for global wireframes.
                 // Can be changed per mesh using the `WireframeColor` component.
-                default_color: Srgba {
-                    red: (166. / 255.),
-                    green: (227. / 255.),
-                    blue: (161. / 255.),
-                    alpha: 1.0,
-                }
-                .into(),
+                default_color: Theme::default().accent.into(),
             })
             // .insert_resource(ClearColor(
             //     Srgba {
@@ -60,7 +164,16 @@ impl Plugin for SpherePlugin {
             //     .into(),
             // ))
             .add_systems(Startup, (gen_perlin, add_sphere, camera_setup, timer_setup))
-            .add_systems(Update, (undulate_sphere, rotate_sphere));
+            .init_resource::<SphereMood>()
+            .add_systems(
+                Update,
+                (update_sphere_mood, (rotate_sphere, color_sphere)).chain(),
+            )
+            .add_systems(Update, apply_theme.run_if(resource_changed::<Theme>));
+
+        // deforming the mesh is wasted work when nothing is rendered.
+        #[cfg(not(feature = "headless_ci"))]
+        app.add_systems(Update, undulate_sphere.after(update_sphere_mood));
     }
 }
 
@@ -73,9 +186,12 @@ fn timer_setup(mut commands: Commands) {
 }
 
 fn camera_setup(mut commands: Commands) {
+    let orbit = OrbitCamera::default();
+
     commands.spawn((
         Camera3d::default(),
-        Transform::from_xyz(0.0, 0.0, 4.0).looking_at(Vec3::new(0.0, 0.0, 0.0), Vec3::Y),
+        orbit.transform(),
+        orbit,
         Camera::default(),
         Projection::Perspective(PerspectiveProjection {
             // far: 1_000.0,
@@ -111,35 +227,26 @@ fn add_sphere(
     mut meshes: ResMut<Assets<Mesh>>,
     // debug_material: Single<&DebugTexture>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    theme: Res<Theme>,
 ) {
     // let mut sphere = |mul| meshes.add(Sphere::default());
-    let mut sphere = |mul| {
-        meshes.add({
-            let mut sphere = Sphere::default();
-            sphere.radius *= mul;
-            sphere
-        })
+    let sphere = |mul| {
+        let mut sphere = Sphere::default();
+        sphere.radius *= mul;
+        Mesh::from(sphere)
+    };
+    let undulating_sphere = sphere(1.0);
+    let Some(deformation) = SphereDeformation::new(&undulating_sphere) else {
+        error!("the sphere mesh is missing positions or indices, it won't undulate.");
+        return;
     };
 
     cmds.insert_resource(Zoom::default());
 
     cmds.spawn((
-        Mesh3d(sphere(1.0)),
-        Transform::from_xyz(0.0, 0.0, 0.0),
-        NoWireframe,
-        BaseSphere,
-    ));
-    cmds.spawn((
-        Mesh3d(sphere(1.25)),
+        Mesh3d(meshes.add(sphere(1.25))),
         Transform::from_xyz(0.0, 0.0, 0.0),
-        MeshMaterial3d(
-            materials.add(StandardMaterial::from_color(Color::Srgba(Srgba::rgba_u8(
-                // 32, 96, 127, 16,
-                // 166, 227, 161, 8,
-                // 243, 139, 168, 8,
-                147, 153, 178, 8,
-            )))),
-        ),
+        MeshMaterial3d(materials.add(StandardMaterial::from_color(theme.overlay))),
         // NoWireframe,
         WireframeColor {
             // color: Color::Srgba(Srgba::rgba_u8(32, 96, 127, 32)),
@@ -148,113 +255,121 @@ fn add_sphere(
             // color: Color::Srgba(Srgba::rgba_u8(17, 17, 27, 32)),
             // color: Color::Srgba(Srgba::rgba_u8(116, 199, 236, 32)),
             // color: Color::Srgba(Srgba::rgba_u8(203, 166, 247, 32)),
-            color: Color::Srgba(Srgba::rgba_u8(24, 24, 37, 32)),
+            color: theme.mantle.into(),
         },
         Rotatable { speed: 0.03125 },
         BoundingSphere,
     ));
     cmds.spawn((
-        Mesh3d(sphere(1.0)),
+        Mesh3d(meshes.add(undulating_sphere)),
         Transform::from_xyz(0.0, 0.0, 0.0),
         // MeshMaterial3d(debug_material.0.clone()),
-        MeshMaterial3d(
-            materials.add(StandardMaterial::from_color(Color::Srgba(Srgba::rgba_u8(
-                // 116, 199, 236, 64,
-                // 166, 227, 161, 64,
-                // 203, 166, 247, 64,
-                // 166, 227, 161, 64,
-                // 243, 139, 168, 127,
-                250, 179, 135, 255,
-            )))),
-        ),
+        MeshMaterial3d(materials.add(StandardMaterial::from_color(theme.highlight))),
         NoWireframe,
         Rotatable { speed: 0.03125 },
         UndulateSphere,
+        deformation,
     ));
 }
 
-fn undulate_sphere(
-    sphere: Single<&Mesh3d, With<UndulateSphere>>,
-    base_sphere: Single<&Mesh3d, With<BaseSphere>>,
+pub fn undulate_sphere(
+    sphere: Single<(&Mesh3d, &mut SphereDeformation), With<UndulateSphere>>,
     mut meshes: ResMut<Assets<Mesh>>,
     noise: Res<PerlinWrapper>,
     time: Res<Time>,
     mut zoom: ResMut<Zoom>,
-    // mut timer: Single<&mut UndulateTimer>,
+    mood: Res<SphereMood>,
 ) {
-    // if timer.tick(time.delta()).just_finished() {
-    // for ref mut point in sphere.0.
-    let Some(base_positions) = meshes.get(base_sphere.0.id()).map(|mesh| {
-        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
-            .unwrap()
-            .as_float3()
-            .unwrap()
-            .to_vec()
-    }) else {
-        return;
-    };
+    let (mesh, mut deformation) = sphere.into_inner();
 
-    let Some(mesh) = meshes.get_mut(sphere.0.id()) else {
+    let Some(mesh) = meshes.get_mut(mesh.id()) else {
         return;
     };
 
-    // Get the current mesh data.
-    let positions: Vec<[f32; 3]> = mesh
-            .attribute_mut(Mesh::ATTRIBUTE_POSITION)
-            .unwrap()
-            .as_float3()
-            .unwrap()
-            .to_vec()
-            // .as_typed()
-            // .into()
-        ;
-    // let base_positions: Vec<[f32; 3]> = base_mesh
-    //     .attribute_mut(Mesh::ATTRIBUTE_POSITION)
-    //     .unwrap()
-    //     .as_float3()
-    //     .unwrap()
-    //     .to_vec()
-    // .as_typed()
-    // .into()
-
-    // Modify the positions. For example, scale the mesh:
-    // let scale_factor = 2.0;
-    let mut new_positions: Vec<[f32; 3]> = Vec::with_capacity(positions.len());
-    let td = time.delta().as_secs_f32();
-    zoom.0 += 0.5 * TAU * td;
-
-    for pos in base_positions.iter() {
-        let scale_factor = noise.0.get([
-            (pos[0] * zoom.0) as f64,
-            (pos[1] * zoom.0) as f64,
-            (pos[2] * zoom.0) as f64,
+    zoom.0 += mood.undulation_speed() * time.delta_secs();
+    let amplitude = mood.amplitude();
+
+    deformation.deform(mesh, |pos| {
+        noise.0.get([
+            (pos.x * zoom.0) as f64,
+            (pos.y * zoom.0) as f64,
+            (pos.z * zoom.0) as f64,
         ]) as f32
-            * 0.25;
+            * amplitude
+    });
+}
 
-        new_positions.push([
-            pos[0] + pos[0] * scale_factor,
-            pos[1] + pos[1] * scale_factor,
-            pos[2] + pos[2] * scale_factor,
-        ]);
-    }
+/// eases the sphere's mood towards the current game state.
+fn update_sphere_mood(
+    idle_time: Res<CurrentIdleTimeSeconds>,
+    longest_idle_time: Res<LongestIdleTimeSeconds>,
+    automation_speed: Res<AutomationSpeed>,
+    automation_state: Res<State<AutomationStates>>,
+    mut mood: ResMut<SphereMood>,
+    time: Res<Time>,
+) {
+    let idle_budget = if **longest_idle_time > 0.0 {
+        (**idle_time / **longest_idle_time).clamp(0.0, 1.0) as f32
+    } else {
+        0.0
+    };
+    let heat = match automation_state.get() {
+        AutomationStates::Automation => {
+            (**automation_speed / HOT_AUTOMATION_SPEED).clamp(0.0, 1.0) as f32
+        }
+        AutomationStates::Manual => 0.0,
+    };
+    let turbulence = (0.5 * idle_budget + heat).clamp(0.0, 1.0);
+    let ease = 1.0 - (-MOOD_SMOOTHING * time.delta_secs()).exp();
 
-    // Update the mesh with the modified positions.
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, new_positions);
+    mood.turbulence += (turbulence - mood.turbulence) * ease;
+    mood.heat += (heat - mood.heat) * ease;
+}
 
-    // info!("undulated");
+/// recolors the spheres & wireframes whenever the theme changes.
+fn apply_theme(
+    theme: Res<Theme>,
+    mut wireframe_config: ResMut<WireframeConfig>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut bounding_sphere: Single<
+        (&MeshMaterial3d<StandardMaterial>, &mut WireframeColor),
+        With<BoundingSphere>,
+    >,
+) {
+    wireframe_config.default_color = theme.accent.into();
+    bounding_sphere.1.color = theme.mantle.into();
+
+    if let Some(material) = materials.get_mut(&bounding_sphere.0.0) {
+        material.base_color = theme.overlay.into();
+    }
+}
 
-    // Optionally, re-compute normals after modifying positions.
-    // mesh.compute_flat_normals(); // Or mesh.compute_smooth_normals();
-    mesh.compute_smooth_normals();
-    // mesh.verte
+/// shifts the undulating sphere from the theme's highlight color towards its chart line color as
+/// automation heats up.
+fn color_sphere(
+    theme: Res<Theme>,
+    mood: Res<SphereMood>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    sphere: Single<&MeshMaterial3d<StandardMaterial>, With<UndulateSphere>>,
+) {
+    if !mood.is_changed() && !theme.is_changed() {
+        return;
+    }
 
-    // You can also add or remove attributes, change indices, etc. as needed.
-    // }
+    if let Some(material) = materials.get_mut(&sphere.0) {
+        material.base_color = theme.highlight.mix(&theme.chart_line, mood.heat).into();
+    }
 }
 
 // This system will rotate any entity in the scene with a Rotatable component around its y-axis.
-fn rotate_sphere(mut spheres: Query<(&mut Transform, &Rotatable)>, timer: Res<Time>) {
-    for (mut transform, sphere) in &mut spheres {
+fn rotate_sphere(
+    mut spheres: Query<(&mut Transform, &mut Rotatable)>,
+    mood: Res<SphereMood>,
+    timer: Res<Time>,
+) {
+    for (mut transform, mut sphere) in &mut spheres {
+        sphere.speed = mood.rotation_speed();
+
         // The speed is first multiplied by TAU which is a full rotation (360deg) in radians,
         // and then multiplied by delta_secs which is the time that passed last frame.
         // In other words. Speed is equal to the amount of rotations per second.
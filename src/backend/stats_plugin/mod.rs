@@ -0,0 +1,247 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    AutomationSpeed,
+    action_plugin::Activity,
+    base_plugin::GameStatus,
+    idle_time_plugin::KeyCount,
+    save_file::{SaveFile, SavePath},
+};
+
+/// how often the lifetime stats are saved while playing, so a crash loses at most this much play
+/// time. they're saved on exit too.
+pub const STATS_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub play_secs: f64,
+    /// time spent with recent input, see `KeyCount`.
+    pub active_secs: f64,
+    /// time spent unpaused without any recent input.
+    pub idle_secs: f64,
+    pub paused_secs: f64,
+    pub manual_secs: f64,
+    pub automation_secs: f64,
+    /// inputs that counted as activity.
+    pub inputs: u64,
+    pub peak_automation_speed: f64,
+}
+
+impl Stats {
+    pub fn inputs_per_minute(&self) -> f64 {
+        if self.play_secs > 0.0 {
+            self.inputs as f64 / (self.play_secs / 60.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+impl SaveFile for Stats {
+    const NAME: &'static str = "the lifetime stats";
+    const PATH: &'static str = "saves/lifetime_stats.json";
+}
+
+/// stats of the current session.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default, Deref, DerefMut)]
+pub struct SessionStats(pub Stats);
+
+/// stats of every session, including the current one.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default, Deref, DerefMut)]
+pub struct LifetimeStats(pub Stats);
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SavePath<Stats>>();
+        app.init_resource::<SessionStats>();
+        app.add_systems(PreStartup, load_lifetime_stats);
+        app.add_systems(Update, record_stats);
+        app.add_systems(
+            Last,
+            save_lifetime_stats.run_if(on_timer(STATS_AUTOSAVE_INTERVAL).or(on_message::<AppExit>)),
+        );
+    }
+}
+
+fn load_lifetime_stats(mut cmds: Commands, save_path: Res<SavePath<Stats>>) {
+    let stats = Stats::load_or_default(&save_path.path);
+
+    cmds.insert_resource(LifetimeStats(stats));
+}
+
+fn record_stats(
+    mut activity: MessageReader<Activity>,
+    key_count: Res<KeyCount>,
+    automation_speed: Res<AutomationSpeed>,
+    status: Res<GameStatus>,
+    mut session: ResMut<SessionStats>,
+    mut lifetime: ResMut<LifetimeStats>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs_f64();
+    let inputs = activity.read().count() as u64;
+
+    for stats in [&mut **session, &mut **lifetime] {
+        stats.play_secs += delta;
+        stats.inputs += inputs;
+        stats.peak_automation_speed = stats.peak_automation_speed.max(**automation_speed);
+
+        if status.paused {
+            stats.paused_secs += delta;
+        } else if key_count.0 > 0 {
+            stats.active_secs += delta;
+        } else {
+            stats.idle_secs += delta;
+        }
+
+        if status.automating {
+            stats.automation_secs += delta;
+        } else {
+            stats.manual_secs += delta;
+        }
+    }
+}
+
+fn save_lifetime_stats(lifetime: Res<LifetimeStats>, save_path: Res<SavePath<Stats>>) {
+    lifetime.save_or_log(&save_path.path);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::backend::{action_plugin::Input, temp_path};
+
+    fn stats_app(lifetime: Stats) -> App {
+        let mut app = App::new();
+
+        app.add_message::<Activity>();
+        app.init_resource::<KeyCount>();
+        app.init_resource::<AutomationSpeed>();
+        app.init_resource::<GameStatus>();
+        app.init_resource::<SessionStats>();
+        app.insert_resource(LifetimeStats(lifetime));
+        app.init_resource::<Time>();
+        app.add_systems(Update, record_stats);
+
+        app
+    }
+
+    /// runs one frame `secs` long.
+    fn step(app: &mut App, secs: u64) {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs(secs));
+        app.update();
+    }
+
+    #[test]
+    fn time_is_split_by_what_the_game_was_doing() {
+        let mut app = stats_app(Stats::default());
+
+        // idle & manual.
+        step(&mut app, 2);
+
+        // active & automating.
+        app.world_mut().resource_mut::<KeyCount>().0 = 3;
+        *app.world_mut().resource_mut::<GameStatus>() = GameStatus {
+            paused: false,
+            automating: true,
+        };
+        step(&mut app, 3);
+
+        // paused takes priority over activity.
+        app.world_mut().resource_mut::<GameStatus>().paused = true;
+        step(&mut app, 5);
+
+        let session = **app.world().resource::<SessionStats>();
+
+        assert_eq!(session.play_secs, 10.0);
+        assert_eq!(session.idle_secs, 2.0);
+        assert_eq!(session.active_secs, 3.0);
+        assert_eq!(session.paused_secs, 5.0);
+        assert_eq!(session.manual_secs, 2.0);
+        assert_eq!(session.automation_secs, 8.0);
+    }
+
+    #[test]
+    fn lifetime_stats_add_to_earlier_sessions() {
+        let earlier = Stats {
+            play_secs: 60.0,
+            inputs: 10,
+            peak_automation_speed: 4.0,
+            ..default()
+        };
+        let mut app = stats_app(earlier);
+
+        app.world_mut()
+            .resource_mut::<AutomationSpeed>()
+            .step_by(2.0);
+        app.world_mut().write_message_batch(
+            [KeyCode::KeyA, KeyCode::KeyB].map(|key| Activity(Input::Key(key))),
+        );
+        step(&mut app, 60);
+
+        let session = **app.world().resource::<SessionStats>();
+        let lifetime = **app.world().resource::<LifetimeStats>();
+
+        assert_eq!(session.inputs, 2);
+        assert_eq!(session.peak_automation_speed, 2.0);
+        assert_eq!(lifetime.play_secs, 120.0);
+        assert_eq!(lifetime.inputs, 12);
+        assert_eq!(lifetime.peak_automation_speed, 4.0);
+        assert_eq!(lifetime.inputs_per_minute(), 6.0);
+    }
+
+    #[test]
+    fn inputs_per_minute_is_zero_before_playing() {
+        assert_eq!(Stats::default().inputs_per_minute(), 0.0);
+    }
+
+    #[test]
+    fn round_trips_through_a_save() {
+        let path = temp_path("stats/lifetime_stats.json");
+        let stats = Stats {
+            play_secs: 90.5,
+            active_secs: 30.0,
+            idle_secs: 50.5,
+            paused_secs: 10.0,
+            manual_secs: 40.0,
+            automation_secs: 50.5,
+            inputs: 42,
+            peak_automation_speed: 3.25,
+        };
+
+        stats.save(&path).unwrap();
+
+        assert_eq!(Stats::load(&path).unwrap(), stats);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn missing_fields_load_as_zero() {
+        let path = temp_path("stats/old_lifetime_stats.json");
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, r#"{ "play_secs": 12.0, "inputs": 3 }"#).unwrap();
+
+        assert_eq!(
+            Stats::load(&path).unwrap(),
+            Stats {
+                play_secs: 12.0,
+                inputs: 3,
+                ..default()
+            }
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+}
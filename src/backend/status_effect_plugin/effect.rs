@@ -0,0 +1,574 @@
+use std::fmt::{self, Display};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    combat::StatModifier,
+    modifier::{Modifier, RateModifier},
+    status_effect_plugin::StatusEffectCatalog,
+    theme::ThemeColors,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EffectKind {
+    Buff,
+    Debuff,
+}
+
+impl EffectKind {
+    /// the color the effect is shown in, from the active theme.
+    pub fn color(&self, colors: &ThemeColors) -> Srgba {
+        match self {
+            Self::Buff => colors.buff,
+            Self::Debuff => colors.debuff,
+        }
+    }
+}
+
+impl Display for EffectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Buff => "buff",
+            Self::Debuff => "debuff",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// what happens when an effect is applied to something that already has it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stacking {
+    /// the timer starts over.
+    #[default]
+    Refresh,
+    /// the duration is added to the time left.
+    Extend,
+    /// another stack is added, up to the max, & the timer starts over. ticks & modifiers are
+    /// applied once per stack.
+    Intensify(u32),
+    /// nothing, until the effect wears off.
+    Ignore,
+}
+
+/// something an effect does every `interval` seconds while it lasts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tick {
+    pub interval: f64,
+    /// the hp gained each tick, per stack. negative to hurt.
+    pub hp: f64,
+}
+
+/// a timed buff or debuff, loaded from `STATUS_EFFECTS_FOLDER`. an effect with rate modifiers
+/// applies to the whole game, the rest apply to a single party member.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatusEffectDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub kind: EffectKind,
+    /// how long the effect lasts, in seconds.
+    pub duration: f64,
+    #[serde(default)]
+    pub stacking: Stacking,
+    #[serde(default)]
+    pub tick: Option<Tick>,
+    #[serde(default)]
+    pub modifiers: Vec<StatModifier>,
+    #[serde(default)]
+    pub rates: Vec<RateModifier>,
+    /// whether whoever has the effect loses their turns while it lasts.
+    #[serde(default)]
+    pub stun: bool,
+}
+
+/// something wrong with a `StatusEffectDefinition` that parsed fine.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatusEffectDefinitionError {
+    EmptyName,
+    Duration(f64),
+    MaxStacks,
+    TickInterval(f64),
+    TickHp(f64),
+    Modifier(StatModifier),
+    Rate(RateModifier),
+    /// rate modifiers apply to the game, so they can't be mixed with anything that applies to a
+    /// party member.
+    MixedTargets,
+    NoEffect,
+}
+
+impl Display for StatusEffectDefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyName => write!(f, "the name is empty"),
+            Self::Duration(duration) => write!(f, "the duration can't be {duration}"),
+            Self::MaxStacks => write!(f, "an effect that intensifies needs at least 1 stack"),
+            Self::TickInterval(interval) => write!(f, "the tick interval can't be {interval}"),
+            Self::TickHp(hp) => write!(f, "the tick hp can't be {hp}"),
+            Self::Modifier(modifier) => {
+                write!(f, "the {} modifier isn't a finite number", modifier.stat)
+            }
+            Self::Rate(modifier) => {
+                write!(f, "the {} modifier isn't a finite number", modifier.rate)
+            }
+            Self::MixedTargets => write!(
+                f,
+                "rate modifiers apply to the whole game, they can't be mixed with ticks, stat \
+                 modifiers or stuns"
+            ),
+            Self::NoEffect => write!(f, "it doesn't do anything"),
+        }
+    }
+}
+
+impl std::error::Error for StatusEffectDefinitionError {}
+
+impl StatusEffectDefinition {
+    /// checks the definition makes sense, returning everything wrong with it.
+    pub fn validate(&self) -> Result<(), Vec<StatusEffectDefinitionError>> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            errors.push(StatusEffectDefinitionError::EmptyName);
+        }
+
+        if !self.duration.is_finite() || self.duration <= 0.0 {
+            errors.push(StatusEffectDefinitionError::Duration(self.duration));
+        }
+
+        if self.stacking == Stacking::Intensify(0) {
+            errors.push(StatusEffectDefinitionError::MaxStacks);
+        }
+
+        if let Some(tick) = self.tick {
+            if !tick.interval.is_finite() || tick.interval <= 0.0 {
+                errors.push(StatusEffectDefinitionError::TickInterval(tick.interval));
+            }
+
+            if !tick.hp.is_finite() {
+                errors.push(StatusEffectDefinitionError::TickHp(tick.hp));
+            }
+        }
+
+        for modifier in &self.modifiers {
+            if !modifier.add.is_finite() || !modifier.multiply.is_finite() {
+                errors.push(StatusEffectDefinitionError::Modifier(*modifier));
+            }
+        }
+
+        for modifier in &self.rates {
+            if !modifier.add.is_finite() || !modifier.multiply.is_finite() {
+                errors.push(StatusEffectDefinitionError::Rate(*modifier));
+            }
+        }
+
+        let on_members = self.tick.is_some() || !self.modifiers.is_empty() || self.stun;
+
+        if on_members && self.is_global() {
+            errors.push(StatusEffectDefinitionError::MixedTargets);
+        } else if !on_members && !self.is_global() {
+            errors.push(StatusEffectDefinitionError::NoEffect);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// whether the effect applies to the whole game rather than a party member.
+    pub fn is_global(&self) -> bool {
+        !self.rates.is_empty()
+    }
+
+    pub fn max_stacks(&self) -> u32 {
+        match self.stacking {
+            Stacking::Intensify(max_stacks) => max_stacks,
+            _ => 1,
+        }
+    }
+}
+
+/// an effect that's currently running.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveEffect {
+    /// the effect's id in the catalog.
+    pub effect: String,
+    pub stacks: u32,
+    /// seconds until it wears off.
+    pub remaining: f64,
+    /// seconds until it next ticks.
+    pub until_tick: f64,
+}
+
+/// what happened while the effects ran for a while.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Advanced {
+    /// the hp gained from ticks, negative if more was lost.
+    pub hp: f64,
+    /// the ids of the effects that wore off.
+    pub expired: Vec<String>,
+}
+
+/// an effect, for the UI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EffectSummary {
+    pub name: String,
+    pub kind: EffectKind,
+    pub stacks: u32,
+    /// whole seconds left, rounded up so the UI only changes once a second.
+    pub secs_left: u64,
+}
+
+/// the effects running on a party member.
+#[derive(Component, Debug, Clone, PartialEq, Default, Deref)]
+pub struct StatusEffects(pub Vec<ActiveEffect>);
+
+impl StatusEffects {
+    /// applies `definition`'s effect by its stacking rules, returning whether anything changed.
+    pub fn apply(&mut self, effect: &str, definition: &StatusEffectDefinition) -> bool {
+        let until_tick = definition.tick.map_or(f64::INFINITY, |tick| tick.interval);
+        let Some(active) = self.0.iter_mut().find(|active| active.effect == effect) else {
+            self.0.push(ActiveEffect {
+                effect: effect.to_string(),
+                stacks: 1,
+                remaining: definition.duration,
+                until_tick,
+            });
+
+            return true;
+        };
+
+        match definition.stacking {
+            Stacking::Refresh => active.remaining = definition.duration,
+            Stacking::Extend => active.remaining += definition.duration,
+            Stacking::Intensify(max_stacks) => {
+                active.stacks = (active.stacks + 1).min(max_stacks);
+                active.remaining = definition.duration;
+            }
+            Stacking::Ignore => return false,
+        }
+
+        true
+    }
+
+    /// runs every effect for `secs` seconds, ticking them & taking away the ones that wear off.
+    pub fn advance(&mut self, secs: f64, catalog: &StatusEffectCatalog) -> Advanced {
+        let mut advanced = Advanced::default();
+
+        for active in &mut self.0 {
+            // ticks stop once the effect wears off, even if it happens partway through `secs`.
+            let elapsed = secs.min(active.remaining);
+
+            active.remaining -= secs;
+
+            let Some(tick) = catalog
+                .get(&active.effect)
+                .and_then(|definition| definition.tick)
+            else {
+                continue;
+            };
+
+            active.until_tick -= elapsed;
+
+            while active.until_tick <= 0.0 {
+                advanced.hp += tick.hp * active.stacks as f64;
+                active.until_tick += tick.interval;
+            }
+        }
+
+        self.0.retain(|active| {
+            let expired = active.remaining <= 0.0;
+
+            if expired {
+                advanced.expired.push(active.effect.clone());
+            }
+
+            !expired
+        });
+
+        advanced
+    }
+
+    fn definitions<'a>(
+        &'a self,
+        catalog: &'a StatusEffectCatalog,
+    ) -> impl Iterator<Item = (&'a ActiveEffect, &'a StatusEffectDefinition)> {
+        self.iter()
+            .filter_map(|active| Some((active, catalog.get(&active.effect)?)))
+    }
+
+    /// the stat modifiers of every effect, once per stack.
+    pub fn stat_modifiers(&self, catalog: &StatusEffectCatalog) -> Vec<StatModifier> {
+        self.definitions(catalog)
+            .flat_map(|(active, definition)| {
+                definition
+                    .modifiers
+                    .iter()
+                    .map(|modifier| modifier.stacked(active.stacks))
+            })
+            .collect()
+    }
+
+    /// the rate modifiers of every effect, once per stack.
+    pub fn rate_modifiers(&self, catalog: &StatusEffectCatalog) -> Vec<RateModifier> {
+        self.definitions(catalog)
+            .flat_map(|(active, definition)| {
+                definition
+                    .rates
+                    .iter()
+                    .map(|modifier| modifier.stacked(active.stacks))
+            })
+            .collect()
+    }
+
+    pub fn stunned(&self, catalog: &StatusEffectCatalog) -> bool {
+        self.definitions(catalog)
+            .any(|(_, definition)| definition.stun)
+    }
+
+    pub fn summary(&self, catalog: &StatusEffectCatalog) -> Vec<EffectSummary> {
+        self.definitions(catalog)
+            .map(|(active, definition)| EffectSummary {
+                name: definition.name.clone(),
+                kind: definition.kind,
+                stacks: active.stacks,
+                secs_left: active.remaining.max(0.0).ceil() as u64,
+            })
+            .collect()
+    }
+}
+
+impl Display for EffectSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+
+        if self.stacks > 1 {
+            write!(f, " x{}", self.stacks)?;
+        }
+
+        write!(f, " ({}s)", self.secs_left)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::backend::{
+        combat::Stat,
+        modifier::Rate,
+        shipped_assets,
+        status_effect_plugin::{STATUS_EFFECT_EXTENSION, STATUS_EFFECTS_FOLDER, StatusEffectList},
+    };
+
+    fn definition(stacking: Stacking, tick: Option<Tick>) -> StatusEffectDefinition {
+        StatusEffectDefinition {
+            name: "poison".to_string(),
+            description: String::new(),
+            kind: EffectKind::Debuff,
+            duration: 10.0,
+            stacking,
+            tick,
+            modifiers: Vec::new(),
+            rates: Vec::new(),
+            stun: false,
+        }
+    }
+
+    fn catalog(definition: StatusEffectDefinition) -> StatusEffectCatalog {
+        StatusEffectCatalog(BTreeMap::from([("poison".to_string(), definition)]))
+    }
+
+    const TICK: Tick = Tick {
+        interval: 2.0,
+        hp: -3.0,
+    };
+
+    #[test]
+    fn valid_definitions_pass() {
+        assert_eq!(definition(Stacking::Refresh, Some(TICK)).validate(), Ok(()));
+
+        let global = StatusEffectDefinition {
+            rates: vec![RateModifier {
+                rate: Rate::Income,
+                add: 0.0,
+                multiply: 2.0,
+            }],
+            ..definition(Stacking::Refresh, None)
+        };
+
+        assert!(global.is_global());
+        assert_eq!(global.validate(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_definitions_list_every_error() {
+        let broken = StatusEffectDefinition {
+            name: " ".to_string(),
+            duration: 0.0,
+            ..definition(
+                Stacking::Intensify(0),
+                Some(Tick {
+                    interval: -1.0,
+                    hp: f64::NAN,
+                }),
+            )
+        };
+        let errors = broken.validate().unwrap_err();
+
+        assert_eq!(errors.len(), 5);
+        assert_eq!(errors[0], StatusEffectDefinitionError::EmptyName);
+        assert_eq!(errors[1], StatusEffectDefinitionError::Duration(0.0));
+        assert_eq!(errors[2], StatusEffectDefinitionError::MaxStacks);
+        assert_eq!(errors[3], StatusEffectDefinitionError::TickInterval(-1.0));
+        assert!(matches!(errors[4], StatusEffectDefinitionError::TickHp(hp) if hp.is_nan()));
+    }
+
+    #[test]
+    fn definitions_need_one_kind_of_target() {
+        assert_eq!(
+            definition(Stacking::Refresh, None).validate(),
+            Err(vec![StatusEffectDefinitionError::NoEffect])
+        );
+
+        let mixed = StatusEffectDefinition {
+            modifiers: vec![StatModifier {
+                stat: Stat::Attack,
+                add: 1.0,
+                multiply: 1.0,
+            }],
+            rates: vec![RateModifier {
+                rate: Rate::Xp,
+                add: 1.0,
+                multiply: 1.0,
+            }],
+            ..definition(Stacking::Refresh, None)
+        };
+
+        assert_eq!(
+            mixed.validate(),
+            Err(vec![StatusEffectDefinitionError::MixedTargets])
+        );
+    }
+
+    fn applied_twice(stacking: Stacking) -> (bool, ActiveEffect) {
+        let definition = definition(stacking, Some(TICK));
+        let mut effects = StatusEffects::default();
+
+        assert!(effects.apply("poison", &definition));
+        effects.0[0].remaining = 4.0;
+
+        let changed = effects.apply("poison", &definition);
+
+        assert_eq!(effects.len(), 1);
+
+        (changed, effects.0[0].clone())
+    }
+
+    #[test]
+    fn apply_follows_the_stacking_rules() {
+        let (changed, active) = applied_twice(Stacking::Refresh);
+
+        assert!(changed);
+        assert_eq!((active.stacks, active.remaining), (1, 10.0));
+
+        let (changed, active) = applied_twice(Stacking::Extend);
+
+        assert!(changed);
+        assert_eq!((active.stacks, active.remaining), (1, 14.0));
+
+        let (changed, active) = applied_twice(Stacking::Intensify(3));
+
+        assert!(changed);
+        assert_eq!((active.stacks, active.remaining), (2, 10.0));
+
+        let (changed, active) = applied_twice(Stacking::Ignore);
+
+        assert!(!changed);
+        assert_eq!((active.stacks, active.remaining), (1, 4.0));
+    }
+
+    #[test]
+    fn intensify_stops_at_the_max_stacks() {
+        let definition = definition(Stacking::Intensify(2), None);
+        let mut effects = StatusEffects::default();
+
+        for _ in 0..5 {
+            effects.apply("poison", &definition);
+        }
+
+        assert_eq!(definition.max_stacks(), 2);
+        assert_eq!(effects[0].stacks, 2);
+    }
+
+    #[test]
+    fn advance_ticks_once_per_stack() {
+        let definition = definition(Stacking::Intensify(3), Some(TICK));
+        let catalog = catalog(definition.clone());
+        let mut effects = StatusEffects::default();
+
+        effects.apply("poison", &definition);
+        effects.apply("poison", &definition);
+
+        let advanced = effects.advance(5.0, &catalog);
+
+        // ticks at 2 & 4 sec, for 2 stacks.
+        assert_eq!(advanced.hp, -12.0);
+        assert!(advanced.expired.is_empty());
+        assert_eq!(effects[0].remaining, 5.0);
+        assert_eq!(effects[0].until_tick, 1.0);
+    }
+
+    #[test]
+    fn advance_stops_ticking_when_an_effect_wears_off() {
+        let definition = definition(Stacking::Refresh, Some(TICK));
+        let catalog = catalog(definition.clone());
+        let mut effects = StatusEffects::default();
+
+        effects.apply("poison", &definition);
+
+        let advanced = effects.advance(100.0, &catalog);
+
+        // ticks at 2, 4, 6, 8 & 10 sec, then it's gone.
+        assert_eq!(advanced.hp, -15.0);
+        assert_eq!(advanced.expired, vec!["poison".to_string()]);
+        assert!(effects.is_empty());
+    }
+
+    #[test]
+    fn summary_rounds_the_time_left_up() {
+        let definition = definition(Stacking::Intensify(3), None);
+        let catalog = catalog(definition.clone());
+        let mut effects = StatusEffects::default();
+
+        effects.apply("poison", &definition);
+        effects.apply("poison", &definition);
+        effects.advance(0.5, &catalog);
+
+        let summary = effects.summary(&catalog);
+
+        assert_eq!(summary[0].secs_left, 10);
+        assert_eq!(summary[0].to_string(), "poison x2 (10s)");
+    }
+
+    #[test]
+    fn shipped_definitions_are_valid() {
+        for (path, source) in shipped_assets(STATUS_EFFECTS_FOLDER, STATUS_EFFECT_EXTENSION) {
+            let list: StatusEffectList =
+                ron::from_str(&source).unwrap_or_else(|e| panic!("{path}: {e}"));
+
+            for (id, effect) in list.iter() {
+                assert_eq!(effect.validate(), Ok(()), "{path}: {id}");
+            }
+        }
+    }
+}
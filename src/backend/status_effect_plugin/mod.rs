@@ -0,0 +1,426 @@
+use std::collections::BTreeMap;
+
+use bevy::{
+    asset::{AssetLoadFailedEvent, LoadedFolder},
+    ecs::{schedule::ScheduleConfigs, system::ScheduleSystem},
+    prelude::*,
+};
+use bevy_common_assets::ron::RonAssetPlugin;
+use rand::{Rng, seq::IndexedRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{
+    base_plugin::PauseStates,
+    bestiary_plugin::{Encounter, EnemyCatalog},
+    combat::{EffectiveStats, Health, ModifierSource, StatModifiers},
+    dungeon_plugin::DungeonRng,
+    modifier::Rates,
+    party_plugin::{ClassCatalog, PartyMember, SkillUsed, class::SkillTarget},
+    status_effect_plugin::effect::{EffectSummary, StatusEffectDefinition, StatusEffects},
+};
+
+pub mod effect;
+
+/// where the status effect definitions are loaded from, relative to the asset folder.
+pub const STATUS_EFFECTS_FOLDER: &str = "status_effects";
+/// the extension of status effect list files, each one maps effect ids to their definitions.
+pub const STATUS_EFFECT_EXTENSION: &str = "effects.ron";
+
+/// a file of status effect definitions, by id.
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Deref, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct StatusEffectList(pub BTreeMap<String, StatusEffectDefinition>);
+
+/// the valid status effect definitions, by id.
+#[derive(Resource, Debug, Clone, PartialEq, Default, Deref)]
+pub struct StatusEffectCatalog(pub BTreeMap<String, StatusEffectDefinition>);
+
+/// keeps the status effect definitions loaded.
+#[derive(Resource, Debug, Clone, Deref)]
+pub struct StatusEffectFolder(pub Handle<LoadedFolder>);
+
+/// the effects running on the whole game, the ones with rate modifiers.
+#[derive(Resource, Debug, Clone, PartialEq, Default, Deref, DerefMut)]
+pub struct GlobalEffects(pub StatusEffects);
+
+/// the global effects, for the UI.
+#[derive(Resource, Debug, Clone, PartialEq, Default, Deref)]
+pub struct GlobalEffectSummary(pub Vec<EffectSummary>);
+
+/// a party member who's lost their turns to a stun.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Stunned;
+
+/// who a status effect is applied to. effects that apply to the whole game ignore it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EffectTarget {
+    Member(Entity),
+    Party,
+}
+
+/// applies the status effect with the id to `target`, following its stacking rules.
+#[derive(Message, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ApplyStatusEffect {
+    pub target: EffectTarget,
+    pub effect: String,
+}
+
+pub struct StatusEffectPlugin;
+
+impl Plugin for StatusEffectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<StatusEffectList>::new(&[
+            STATUS_EFFECT_EXTENSION,
+        ]));
+        app.init_resource::<StatusEffectCatalog>();
+        app.init_resource::<GlobalEffects>();
+        app.init_resource::<GlobalEffectSummary>();
+        app.init_resource::<Rates>();
+        app.add_message::<ApplyStatusEffect>();
+        app.add_systems(Startup, load_definitions);
+        app.add_systems(
+            Update,
+            (
+                report_load_failures.run_if(on_message::<AssetLoadFailedEvent<StatusEffectList>>),
+                update_catalog.run_if(on_message::<AssetEvent<StatusEffectList>>),
+                inflict_effects.run_if(resource_changed::<Encounter>),
+                skill_effects.run_if(on_message::<SkillUsed>),
+                apply_effects.run_if(on_message::<ApplyStatusEffect>),
+                tick_effects(),
+                update_modifiers,
+                update_stuns,
+                update_rates.run_if(
+                    resource_changed::<GlobalEffects>.or(resource_changed::<StatusEffectCatalog>),
+                ),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// runs every effect on real time while the game isn't paused, manual play or not, so stuns &
+/// buffs wear off whoever's playing.
+fn tick_effects() -> ScheduleConfigs<ScheduleSystem> {
+    (tick_member_effects, tick_global_effects)
+        .run_if(in_state(PauseStates::Running))
+        .into_configs()
+}
+
+fn load_definitions(mut cmds: Commands, asset_server: Res<AssetServer>) {
+    cmds.insert_resource(StatusEffectFolder(
+        asset_server.load_folder(STATUS_EFFECTS_FOLDER),
+    ));
+}
+
+fn report_load_failures(mut failures: MessageReader<AssetLoadFailedEvent<StatusEffectList>>) {
+    for failure in failures.read() {
+        error!(
+            "failed to load the status effects {}: {}",
+            failure.path, failure.error
+        );
+    }
+}
+
+/// rebuilds the catalog whenever a status effect list is loaded, (hot) reloaded or removed,
+/// reporting what's wrong with the effects in the lists that changed.
+fn update_catalog(
+    mut events: MessageReader<AssetEvent<StatusEffectList>>,
+    lists: Res<Assets<StatusEffectList>>,
+    asset_server: Res<AssetServer>,
+    mut catalog: ResMut<StatusEffectCatalog>,
+) {
+    for event in events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = event else {
+            continue;
+        };
+        let (Some(list), Some(path)) = (lists.get(*id), asset_server.get_path(*id)) else {
+            continue;
+        };
+
+        for (effect, definition) in list.iter() {
+            if let Err(errors) = definition.validate() {
+                for e in errors {
+                    error!(
+                        "the status effect \"{effect}\" in {path} is invalid, it will be left \
+                         out: {e}"
+                    );
+                }
+            }
+        }
+    }
+
+    let mut effects = BTreeMap::new();
+
+    for (id, list) in lists.iter() {
+        for (effect, definition) in list.iter() {
+            if definition.validate().is_err() {
+                continue;
+            }
+
+            if effects.contains_key(effect) {
+                let path = asset_server.get_path(id);
+                error!(
+                    "more than one status effect is defined with the id \"{effect}\", skipping \
+                     the one in {}",
+                    path.map(|path| path.to_string()).unwrap_or_default()
+                );
+                continue;
+            }
+
+            effects.insert(effect.clone(), definition.clone());
+        }
+    }
+
+    if catalog.0 != effects {
+        info!("{} status effects in the catalog", effects.len());
+        catalog.0 = effects;
+    }
+}
+
+/// gives every enemy in a new encounter a chance to inflict its effects on a random party member.
+fn inflict_effects(
+    encounter: Res<Encounter>,
+    enemies: Res<EnemyCatalog>,
+    members: Query<Entity, With<PartyMember>>,
+    mut rng: ResMut<DungeonRng>,
+    mut apply: MessageWriter<ApplyStatusEffect>,
+) {
+    let members: Vec<Entity> = members.iter().collect();
+
+    for enemy in encounter.iter().filter_map(|enemy| enemies.get(enemy)) {
+        for inflict in &enemy.inflicts {
+            if !rng.random_bool(inflict.chance) {
+                continue;
+            }
+
+            let Some(member) = members.choose(&mut **rng) else {
+                return;
+            };
+
+            apply.write(ApplyStatusEffect {
+                target: EffectTarget::Member(*member),
+                effect: inflict.effect.clone(),
+            });
+        }
+    }
+}
+
+/// applies the effects of the skills the party uses.
+fn skill_effects(
+    mut used: MessageReader<SkillUsed>,
+    classes: Res<ClassCatalog>,
+    members: Query<&PartyMember>,
+    mut apply: MessageWriter<ApplyStatusEffect>,
+) {
+    for skill_used in used.read() {
+        let Some(applies) = members
+            .get(skill_used.member)
+            .ok()
+            .and_then(|member| classes.get(&member.class))
+            .and_then(|class| class.skill(&skill_used.skill))
+            .and_then(|skill| skill.applies.as_ref())
+        else {
+            continue;
+        };
+        let target = match applies.target {
+            SkillTarget::User => EffectTarget::Member(skill_used.member),
+            SkillTarget::Party => EffectTarget::Party,
+        };
+
+        apply.write(ApplyStatusEffect {
+            target,
+            effect: applies.effect.clone(),
+        });
+    }
+}
+
+fn apply_effects(
+    mut applied: MessageReader<ApplyStatusEffect>,
+    catalog: Res<StatusEffectCatalog>,
+    mut global: ResMut<GlobalEffects>,
+    mut members: Query<(Entity, &mut StatusEffects), With<PartyMember>>,
+) {
+    for ApplyStatusEffect { target, effect } in applied.read() {
+        let Some(definition) = catalog.get(effect) else {
+            warn!("there's no \"{effect}\" status effect");
+            continue;
+        };
+
+        if definition.is_global() {
+            global.apply(effect, definition);
+            continue;
+        }
+
+        for (entity, mut effects) in &mut members {
+            if let EffectTarget::Member(member) = *target
+                && member != entity
+            {
+                continue;
+            }
+
+            effects.apply(effect, definition);
+        }
+    }
+}
+
+fn tick_member_effects(
+    catalog: Res<StatusEffectCatalog>,
+    time: Res<Time>,
+    mut members: Query<(&Name, &EffectiveStats, &mut StatusEffects, &mut Health)>,
+) {
+    let secs = time.delta_secs_f64();
+
+    for (name, stats, mut effects, mut health) in &mut members {
+        if effects.is_empty() {
+            continue;
+        }
+
+        let advanced = effects.advance(secs, &catalog);
+
+        if advanced.hp != 0.0 {
+            health.set_if_neq(Health((**health + advanced.hp).clamp(0.0, stats.hp)));
+        }
+
+        for effect in advanced.expired {
+            debug!("{name}'s {effect} wore off");
+        }
+    }
+}
+
+fn tick_global_effects(
+    catalog: Res<StatusEffectCatalog>,
+    time: Res<Time>,
+    mut global: ResMut<GlobalEffects>,
+) {
+    if global.is_empty() {
+        return;
+    }
+
+    for effect in global.advance(time.delta_secs_f64(), &catalog).expired {
+        info!("{effect} wore off");
+    }
+}
+
+/// hands the stat modifiers of everyone's effects to the modifier pipeline.
+fn update_modifiers(
+    catalog: Res<StatusEffectCatalog>,
+    mut combatants: Query<(Ref<StatusEffects>, &mut StatModifiers)>,
+) {
+    for (effects, mut modifiers) in &mut combatants {
+        if !catalog.is_changed() && !effects.is_changed() {
+            continue;
+        }
+
+        let from_effects = effects.stat_modifiers(&catalog);
+
+        if modifiers.get(&ModifierSource::StatusEffects) != Some(&from_effects) {
+            modifiers.insert(ModifierSource::StatusEffects, from_effects);
+        }
+    }
+}
+
+fn update_stuns(
+    mut cmds: Commands,
+    catalog: Res<StatusEffectCatalog>,
+    combatants: Query<(Entity, Ref<StatusEffects>, Has<Stunned>)>,
+) {
+    for (entity, effects, was_stunned) in &combatants {
+        if !catalog.is_changed() && !effects.is_changed() {
+            continue;
+        }
+
+        match (effects.stunned(&catalog), was_stunned) {
+            (true, false) => {
+                cmds.entity(entity).insert(Stunned);
+            }
+            (false, true) => {
+                cmds.entity(entity).remove::<Stunned>();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// works out the game wide rates from the global effects.
+fn update_rates(
+    catalog: Res<StatusEffectCatalog>,
+    global: Res<GlobalEffects>,
+    mut rates: ResMut<Rates>,
+    mut summary: ResMut<GlobalEffectSummary>,
+) {
+    rates.set_if_neq(Rates::new(&global.rate_modifiers(&catalog)));
+    summary.set_if_neq(GlobalEffectSummary(global.summary(&catalog)));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+    use crate::backend::{
+        base_plugin::AutomationStates,
+        status_effect_plugin::effect::{EffectKind, Stacking},
+    };
+
+    fn stun() -> StatusEffectDefinition {
+        StatusEffectDefinition {
+            name: "stun".to_string(),
+            description: String::new(),
+            kind: EffectKind::Debuff,
+            duration: 2.0,
+            stacking: Stacking::Refresh,
+            tick: None,
+            modifiers: Vec::new(),
+            rates: Vec::new(),
+            stun: true,
+        }
+    }
+
+    #[test]
+    fn stuns_wear_off_in_manual_play() {
+        let mut app = App::new();
+
+        app.add_plugins(StatesPlugin);
+        app.init_state::<AutomationStates>();
+        app.init_state::<PauseStates>();
+        app.init_resource::<Time>();
+        app.insert_resource(StatusEffectCatalog(BTreeMap::from([(
+            "stun".to_string(),
+            stun(),
+        )])));
+        app.init_resource::<GlobalEffects>();
+        app.add_systems(Update, (tick_effects(), update_stuns).chain());
+
+        let mut effects = StatusEffects::default();
+
+        effects.apply("stun", &stun());
+
+        let member = app
+            .world_mut()
+            .spawn((
+                Name::new("rogue"),
+                EffectiveStats::default(),
+                effects,
+                Health(10.0),
+            ))
+            .id();
+
+        app.update();
+
+        assert_eq!(
+            *app.world().resource::<State<AutomationStates>>().get(),
+            AutomationStates::Manual
+        );
+        assert!(app.world().entity(member).contains::<Stunned>());
+
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs(3));
+        app.update();
+
+        assert!(app.world().get::<StatusEffects>(member).unwrap().is_empty());
+        assert!(!app.world().entity(member).contains::<Stunned>());
+    }
+}
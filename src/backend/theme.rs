@@ -0,0 +1,188 @@
+use std::fmt::Display;
+
+use bevy::prelude::*;
+
+use crate::backend::base_plugin::UiCommand;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Palette {
+    /// Catppuccin Mocha.
+    #[default]
+    Mocha,
+    /// Catppuccin Latte.
+    Latte,
+    HighContrast,
+    /// based on the Okabe-Ito palette, which stays distinguishable with the common forms of color
+    /// blindness.
+    ColorblindSafe,
+}
+
+impl Palette {
+    pub const ALL: [Self; 4] = [
+        Self::Mocha,
+        Self::Latte,
+        Self::HighContrast,
+        Self::ColorblindSafe,
+    ];
+
+    pub fn colors(&self) -> ThemeColors {
+        match self {
+            Self::Mocha => ThemeColors {
+                accent: Srgba::rgb_u8(166, 227, 161),
+                surface: Srgba::rgb_u8(88, 91, 112),
+                on_surface: Srgba::rgb_u8(17, 17, 27),
+                overlay: Srgba::rgba_u8(147, 153, 178, 8),
+                mantle: Srgba::rgba_u8(24, 24, 37, 32),
+                highlight: Srgba::rgb_u8(250, 179, 135),
+                chart_line: Srgba::rgb_u8(243, 139, 168),
+                chart_grid: Srgba::rgba_u8(20, 20, 20, 204),
+                buff: Srgba::rgb_u8(166, 227, 161),
+                debuff: Srgba::rgb_u8(243, 139, 168),
+                rarities: [
+                    Srgba::rgb_u8(186, 194, 222),
+                    Srgba::rgb_u8(166, 227, 161),
+                    Srgba::rgb_u8(137, 180, 250),
+                    Srgba::rgb_u8(203, 166, 247),
+                    Srgba::rgb_u8(250, 179, 135),
+                ],
+            },
+            Self::Latte => ThemeColors {
+                accent: Srgba::rgb_u8(64, 160, 43),
+                surface: Srgba::rgb_u8(172, 176, 190),
+                on_surface: Srgba::rgb_u8(76, 79, 105),
+                overlay: Srgba::rgba_u8(124, 127, 147, 8),
+                mantle: Srgba::rgba_u8(230, 233, 239, 32),
+                highlight: Srgba::rgb_u8(254, 100, 11),
+                chart_line: Srgba::rgb_u8(210, 15, 57),
+                chart_grid: Srgba::rgba_u8(156, 160, 176, 204),
+                buff: Srgba::rgb_u8(64, 160, 43),
+                debuff: Srgba::rgb_u8(210, 15, 57),
+                rarities: [
+                    Srgba::rgb_u8(108, 111, 133),
+                    Srgba::rgb_u8(64, 160, 43),
+                    Srgba::rgb_u8(30, 102, 245),
+                    Srgba::rgb_u8(136, 57, 239),
+                    Srgba::rgb_u8(254, 100, 11),
+                ],
+            },
+            Self::HighContrast => ThemeColors {
+                accent: Srgba::rgb_u8(255, 215, 0),
+                surface: Srgba::rgb_u8(128, 128, 128),
+                on_surface: Srgba::rgb_u8(0, 0, 0),
+                overlay: Srgba::rgba_u8(255, 255, 255, 8),
+                mantle: Srgba::rgba_u8(255, 255, 255, 64),
+                highlight: Srgba::rgb_u8(0, 255, 255),
+                chart_line: Srgba::rgb_u8(255, 255, 0),
+                chart_grid: Srgba::rgba_u8(255, 255, 255, 204),
+                buff: Srgba::rgb_u8(0, 255, 0),
+                debuff: Srgba::rgb_u8(255, 0, 0),
+                rarities: [
+                    Srgba::rgb_u8(255, 255, 255),
+                    Srgba::rgb_u8(0, 255, 0),
+                    Srgba::rgb_u8(0, 191, 255),
+                    Srgba::rgb_u8(255, 0, 255),
+                    Srgba::rgb_u8(255, 165, 0),
+                ],
+            },
+            Self::ColorblindSafe => ThemeColors {
+                accent: Srgba::rgb_u8(86, 180, 233),
+                surface: Srgba::rgb_u8(102, 102, 102),
+                on_surface: Srgba::rgb_u8(0, 0, 0),
+                overlay: Srgba::rgba_u8(153, 153, 153, 8),
+                mantle: Srgba::rgba_u8(0, 0, 0, 32),
+                highlight: Srgba::rgb_u8(230, 159, 0),
+                chart_line: Srgba::rgb_u8(240, 228, 66),
+                chart_grid: Srgba::rgba_u8(20, 20, 20, 204),
+                buff: Srgba::rgb_u8(0, 158, 115),
+                debuff: Srgba::rgb_u8(213, 94, 0),
+                rarities: [
+                    Srgba::rgb_u8(153, 153, 153),
+                    Srgba::rgb_u8(0, 158, 115),
+                    Srgba::rgb_u8(0, 114, 178),
+                    Srgba::rgb_u8(204, 121, 167),
+                    Srgba::rgb_u8(230, 159, 0),
+                ],
+            },
+        }
+    }
+}
+
+impl Display for Palette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Mocha => "mocha",
+            Self::Latte => "latte",
+            Self::HighContrast => "high contrast",
+            Self::ColorblindSafe => "colorblind safe",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// the colors of a palette, named by what they are used for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThemeColors {
+    /// progress bar fill, selected buttons & the default wireframe color.
+    pub accent: Srgba,
+    /// progress bar track & unselected buttons.
+    pub surface: Srgba,
+    /// text drawn on top of `accent` & `surface`.
+    pub on_surface: Srgba,
+    /// the translucent bounding sphere.
+    pub overlay: Srgba,
+    /// the wireframe of the bounding sphere.
+    pub mantle: Srgba,
+    /// the undulating sphere.
+    pub highlight: Srgba,
+    pub chart_line: Srgba,
+    pub chart_grid: Srgba,
+    /// status effects that help.
+    pub buff: Srgba,
+    /// status effects that hurt.
+    pub debuff: Srgba,
+    /// item names, by rarity from common to legendary.
+    pub rarities: [Srgba; 5],
+}
+
+/// the active color theme, shared by the 3D scene & the UI.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Deref)]
+pub struct Theme {
+    pub palette: Palette,
+    #[deref]
+    pub colors: ThemeColors,
+}
+
+impl From<Palette> for Theme {
+    fn from(palette: Palette) -> Self {
+        Self {
+            palette,
+            colors: palette.colors(),
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Palette::default().into()
+    }
+}
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Theme>();
+        app.add_systems(Update, set_palette.run_if(on_message::<UiCommand>));
+    }
+}
+
+fn set_palette(mut commands: MessageReader<UiCommand>, mut theme: ResMut<Theme>) {
+    for command in commands.read() {
+        if let UiCommand::SetPalette(palette) = command
+            && theme.palette != *palette
+        {
+            *theme = (*palette).into();
+        }
+    }
+}
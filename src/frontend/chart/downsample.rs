@@ -0,0 +1,95 @@
+/// Downsamples a line series with the Largest-Triangle-Three-Buckets algorithm, keeping at most
+/// `threshold` points. Peaks and valleys survive because every bucket keeps the point forming the
+/// largest triangle with its neighbours. The first and last points are always kept.
+///
+/// Returns the kept points as `(index, value)` pairs, where `index` is the point's index in
+/// `data`, so callers can still place them on the original x axis.
+pub(crate) fn lttb(data: &[f32], threshold: usize) -> Vec<(usize, f32)> {
+    if threshold < 3 || data.len() <= threshold {
+        return data.iter().copied().enumerate().collect();
+    }
+
+    let mut sampled = Vec::<(usize, f32)>::with_capacity(threshold);
+    // the first and last points are kept, so only the points between them are bucketed.
+    let bucket_size = (data.len() - 2) as f32 / (threshold - 2) as f32;
+    let mut a = 0;
+
+    sampled.push((0, data[0]));
+
+    for bucket in 0..threshold - 2 {
+        let start = (bucket as f32 * bucket_size) as usize + 1;
+        let end = ((bucket + 1) as f32 * bucket_size) as usize + 1;
+
+        // average of the next bucket, used as the third point of the triangle.
+        let next_start = end;
+        let next_end = (((bucket + 2) as f32 * bucket_size) as usize + 1).min(data.len());
+        let next_len = (next_end - next_start).max(1) as f32;
+        let avg_x = (next_start..next_end).sum::<usize>() as f32 / next_len;
+        let avg_y = data[next_start..next_end].iter().sum::<f32>() / next_len;
+
+        let (ax, ay) = (a as f32, data[a]);
+        let mut max_area = -1.0;
+        let mut picked = start;
+
+        for (i, y) in data.iter().enumerate().take(end).skip(start) {
+            let area = ((ax - avg_x) * (y - ay) - (ax - i as f32) * (avg_y - ay)).abs();
+
+            if area > max_area {
+                max_area = area;
+                picked = i;
+            }
+        }
+
+        sampled.push((picked, data[picked]));
+        a = picked;
+    }
+
+    sampled.push((data.len() - 1, data[data.len() - 1]));
+
+    sampled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_series_are_kept_whole() {
+        let data = [1.0, 3.0, 2.0, 5.0];
+
+        assert_eq!(
+            lttb(&data, 10),
+            vec![(0, 1.0), (1, 3.0), (2, 2.0), (3, 5.0)]
+        );
+        assert_eq!(lttb(&data, 2).len(), data.len());
+        assert!(lttb(&[], 10).is_empty());
+    }
+
+    #[test]
+    fn keeps_threshold_points_in_order() {
+        let data: Vec<f32> = (0..1_000).map(|i| (i as f32 / 25.0).sin()).collect();
+
+        for threshold in [3, 10, 100, 999] {
+            let sampled = lttb(&data, threshold);
+
+            assert_eq!(sampled.len(), threshold);
+            assert_eq!(sampled.first(), Some(&(0, data[0])));
+            assert_eq!(sampled.last(), Some(&(999, data[999])));
+            assert!(sampled.windows(2).all(|pair| pair[0].0 < pair[1].0));
+            assert!(sampled.iter().all(|(i, y)| data[*i] == *y));
+        }
+    }
+
+    #[test]
+    fn keeps_spikes() {
+        let mut data = vec![0.0; 500];
+
+        data[123] = 10.0;
+        data[321] = -10.0;
+
+        let sampled = lttb(&data, 20);
+
+        assert!(sampled.contains(&(123, 10.0)));
+        assert!(sampled.contains(&(321, -10.0)));
+    }
+}
@@ -13,7 +13,7 @@ pub enum Direction {
 }
 
 #[derive(Copy, Clone)]
-pub(crate) struct Axis {
+pub struct Axis {
     view: Rect,
     step_len: f32,
     steps: i32,
@@ -206,7 +206,7 @@ impl Axis {
     }
 }
 
-pub(crate) struct AxisBuilder<'a> {
+pub struct AxisBuilder<'a> {
     view: Rect,
     lowest: Option<f32>,
     highest: Option<f32>,
@@ -1,83 +1,95 @@
 use dioxus::prelude::*;
 
+use super::downsample::lttb;
 use super::grid::{Axis, Grid};
 use super::types::*;
 
 /// The `LineChart` properties struct for the configuration of the line chart.
-#[allow(clippy::struct_excessive_bools)]
+// `label_interpolation` is compared by address, so an equal function can still make the chart
+// re-render. that's harmless, it's only there to skip re-renders.
+#[allow(
+    clippy::struct_excessive_bools,
+    unpredictable_function_pointer_comparisons
+)]
 #[derive(Clone, PartialEq, Props)]
 pub struct LineChartProps {
-    series: Series,
+    pub series: Series,
     #[props(optional)]
-    labels: Option<Labels>,
+    pub labels: Option<Labels>,
     #[props(optional)]
-    series_labels: Option<Labels>,
+    pub series_labels: Option<Labels>,
 
     #[props(default = "100%".to_string(), into)]
-    width: String,
+    pub width: String,
     #[props(default = "100%".to_string(), into)]
-    height: String,
+    pub height: String,
     #[props(default = 600)]
-    viewbox_width: i32,
+    pub viewbox_width: i32,
     #[props(default = 400)]
-    viewbox_height: i32,
+    pub viewbox_height: i32,
 
     #[props(default)]
-    padding_top: i32,
+    pub padding_top: i32,
     #[props(default)]
-    padding_bottom: i32,
+    pub padding_bottom: i32,
     #[props(default)]
-    padding_left: i32,
+    pub padding_left: i32,
     #[props(default)]
-    padding_right: i32,
+    pub padding_right: i32,
 
     #[props(default = true)]
-    show_grid: bool,
+    pub show_grid: bool,
     #[props(default = true)]
-    show_dotted_grid: bool,
+    pub show_dotted_grid: bool,
     #[props(default = false)]
-    show_grid_ticks: bool,
+    pub show_grid_ticks: bool,
     #[props(default = true)]
-    show_labels: bool,
+    pub show_labels: bool,
     #[props(default = true)]
-    show_dots: bool,
+    pub show_dots: bool,
     #[props(default = true)]
-    show_lines: bool,
+    pub show_lines: bool,
     #[props(default = true)]
-    show_line_labels: bool,
+    pub show_line_labels: bool,
 
+    #[props(optional)]
+    pub line_colors: Option<Vec<String>>,
+    #[props(default = "rgba(20, 20, 20, 0.8)".to_string(), into)]
+    pub grid_color: String,
     #[props(default = "1%".to_string(), into)]
-    line_width: String,
+    pub line_width: String,
     #[props(default = "3%".to_string(), into)]
-    dot_size: String,
+    pub dot_size: String,
     #[props(optional)]
-    label_interpolation: Option<fn(f32) -> String>,
+    pub label_interpolation: Option<fn(f32) -> String>,
 
     #[props(optional)]
-    lowest: Option<f32>,
+    pub lowest: Option<f32>,
     #[props(optional)]
-    highest: Option<f32>,
+    pub highest: Option<f32>,
     #[props(default = 8)]
-    max_ticks: i32,
+    pub max_ticks: i32,
+    #[props(optional)]
+    pub max_points: Option<usize>,
 
     #[props(default = "dx-chart-line".to_string(), into)]
-    class_chart_line: String,
+    pub class_chart_line: String,
     #[props(default = "dx-line".to_string(), into)]
-    class_line: String,
+    pub class_line: String,
     #[props(default = "dx-line-path".to_string(), into)]
-    class_line_path: String,
+    pub class_line_path: String,
     #[props(default = "dx-line-dot".to_string(), into)]
-    class_line_dot: String,
+    pub class_line_dot: String,
     #[props(default = "dx-line-label".to_string(), into)]
-    class_line_label: String,
+    pub class_line_label: String,
     #[props(default = "dx-grid".to_string(), into)]
-    class_grid: String,
+    pub class_grid: String,
     #[props(default = "dx-grid-line".to_string(), into)]
-    class_grid_line: String,
+    pub class_grid_line: String,
     #[props(default = "dx-grid-label".to_string(), into)]
-    class_grid_label: String,
+    pub class_grid_label: String,
     #[props(default = "dx-grid-labels".to_string(), into)]
-    class_grid_labels: String,
+    pub class_grid_labels: String,
 }
 
 /// This is the `LineChart` function used to render the line chart `Element`.
@@ -118,10 +130,10 @@ pub struct LineChartProps {
 /// - `series_labels`: [Vec]<[String]> (optional): Optional labels to show for each generated line.
 /// ---
 /// - `width`: &[str] (default: `"100%"`): The SVG element width attribute. It also accepts any
-/// other CSS style, i.e., "200px"
+///   other CSS style, i.e., "200px"
 /// - `height`: &[str] (default: `"100%"`): The SVG height counter-part of the `width` prop above.
 /// - `viewbox_width`: [i32] (default: `600`): The SVG viewbox width. Together with
-/// `viewbox_height` it is useful for adjusting the aspect ratio for longer charts.
+///   `viewbox_height` it is useful for adjusting the aspect ratio for longer charts.
 /// - `viewbox_height`: [i32] (default: `400`): The SVG viewbox height.
 /// ---
 /// - `padding_top`: [i32] (default: `0`): Padding for the top side of the view box.
@@ -132,176 +144,86 @@ pub struct LineChartProps {
 /// - `lowest`: [f32] (optional): The lowest number on the chart for the value axis.
 /// - `highest`: [f32] (optional): The highest number on the chart for the value axis.
 /// - `max_ticks`: [i32] (default: `8`): The maximum number of ticks on the generated value axis.
+/// - `max_points`: [usize] (optional): The maximum number of points drawn per series. Longer
+///   series are downsampled (keeping peaks) to this many points. Defaults to the width of the view
+///   box minus padding, i.e. roughly one point per horizontal unit.
 /// ---
 /// - `show_grid`: [bool] (default: `true`): Show/hide the chart grid.
 /// - `show_dotted_grid`: [bool] (default: `true`): Show the chart grid with dotted style or not.
 /// - `show_grid_ticks`: [bool] (default: `false`): Show the chart grid ticks instead of drawing the
-/// whole grid lines for a cleaner look.
+///   whole grid lines for a cleaner look.
 /// - `show_labels`: [bool] (default: `true`): Show/hide the labels.
 /// - `show_dots`: [bool] (default: `true`): Show/hide the line dots.
 /// - `show_lines`: [bool] (default: `true`): Show/hide the series lines.
 /// - `show_line_labels`: [bool] (default: `true`): Show/hide the labels for the lines.
 /// ---
+/// - `line_colors`: [Vec]<[String]> (optional): CSS colors for the series lines, repeated if there
+///   are more series than colors. Defaults to a generated range of reds.
+/// - `grid_color`: &[str] (default: `"rgba(20, 20, 20, 0.8)"`): CSS color of the grid lines.
 /// - `line_width`: &[str] (default: `"1%"`): The width of the series lines.
 /// - `dot_size`: &[str] (default: `"3%"`): The size of the line dots.
 /// - `label_interpolation`: fn([f32]) -> [String] (optional): Function for formatting the
-/// generated labels.
+///   generated labels.
 /// ---
 /// - `class_chart_line`: &[str] (default: `"dx-chart-line"`): The HTML element `class` of the
-/// chart.
+///   chart.
 /// - `class_line`: &[str] (default: `"dx-line"`): The HTML element `class` of the whole line.
 /// - `class_line_path`: &[str] (default: `"dx-line"`): The HTML element `class` of the line path.
 /// - `class_line_dot`: &[str] (default: `"dx-line-dot"`): The HTML element `class` of the line dot.
 /// - `class_line_label`: &[str] (default: `"dx-line-label"`): The HTML element `class` of the line
-/// labels.
+///   labels.
 /// - `class_grid`: &[str] (default: `"dx-grid"`): The HTML element `class` of the grid.
 /// - `class_grid_line`: &[str] (default: `"dx-grid-line"`): The HTML element `class` of every grid
-/// line.
+///   line.
 /// - `class_grid_label`: &[str] (default: `"dx-grid-label"`): The HTML element `class` of the grid
-/// labels.
+///   labels.
 /// - `class_grid_labels`: &[str] (default: `"dx-grid-labels"`): The HTML element `class` of the
-/// group of grid labels.
+///   group of grid labels.
 #[allow(non_snake_case)]
 pub fn LineChart(props: LineChartProps) -> Element {
-    for series in props.series.iter() {
-        if series.is_empty() {
-            return rsx!("Line chart error: empty series");
-        }
-    }
-
-    let view = Rect::new(
-        props.padding_left as f32,
-        props.padding_top as f32,
-        (props.viewbox_width - props.padding_right) as f32,
-        (props.viewbox_height - props.padding_bottom) as f32,
-    );
-
-    let max_ticks = props.max_ticks.max(3);
-
-    let axis_x = Axis::builder()
-        .with_view(view)
-        .with_grid_ticks(props.show_grid_ticks)
-        .with_labels(props.labels.as_ref());
-
-    let axis_y = Axis::builder()
-        .with_view(view)
-        .with_max_ticks(max_ticks)
-        .with_grid_ticks(props.show_grid_ticks)
-        .with_series(&props.series)
-        .with_label_interpolation(props.label_interpolation)
-        .with_highest(props.highest)
-        .with_lowest(props.lowest);
-
-    let grid = Grid::new(axis_x, axis_y);
-    let lines = grid.lines();
-    let generated_labels = grid.y.generated_labels();
-
-    let grid_labels = if props.show_labels {
-        if let Some(labels) = props.labels.as_ref() {
-            Some(
-                grid.text_data(Some(labels.len()), Some(generated_labels.len()))
-                    .into_iter()
-                    .zip(labels.iter().chain(generated_labels.iter()))
-                    .collect::<Vec<(TextData, &String)>>(),
-            )
-        } else {
-            Some(
-                grid.y
-                    .text_data(generated_labels.len())
-                    .into_iter()
-                    .zip(generated_labels.iter())
-                    .collect::<Vec<(TextData, &String)>>(),
-            )
-        }
-    } else {
-        None
-    };
-
-    let mut color_var = 255.0;
-    let dotted_stroke = if props.show_dotted_grid {
-        &"2px"
-    } else {
-        &"0px"
+    let Some(chart) = LineChartModel::new(&props) else {
+        return rsx!("Line chart error: empty series");
     };
 
-    let string_binding = String::new();
-    let vec_binding = vec![];
-
-    let series_rsx = props
-        .series
-        .iter()
-        .enumerate()
-        .zip(
-            props
-                .series_labels
-                .as_ref()
-                .unwrap_or(&vec_binding)
-                .iter()
-                .chain(std::iter::repeat(&string_binding)),
-        )
-        .map(|((i, a), label)| {
-            let mut commands = Vec::<String>::with_capacity(a.len());
-            let mut dots = Vec::<Rect>::with_capacity(a.len());
-            let mut text_point: Option<Point> = None;
-
-            color_var -= 75.0 * (1.0 / (i + 1) as f32);
-
-            for (index, v) in a.iter().enumerate() {
-                let point = grid.world_to_view(index as f32, *v, false);
-
-                if index == 0 {
-                    commands.push(format!("M{},{}", point.x, point.y));
-                } else {
-                    commands.push(format!("L{},{}", point.x, point.y));
-                }
-
-                if props.show_dots {
-                    dots.push(Rect::new(point.x, point.y, point.x + 0.1, point.y));
-                }
+    let series_rsx = chart.series.into_iter().enumerate().map(|(i, line)| {
+        let color = line.color;
 
-                if !label.is_empty() && index == (a.len() - 1) {
-                    text_point = Some(point);
-                }
-            }
-
-            let commands = commands.join(" ");
-
-            rsx! {
-                g {
-                    class: "{props.class_line}-{i}",
-                    path {
-                        d: "{commands}",
-                        class: "{props.class_line_path}",
-                        stroke: "rgb({color_var}, 40, 40)",
-                        stroke_width: "{props.line_width}",
+        rsx! {
+            g {
+                class: "{props.class_line}-{i}",
+                path {
+                    d: "{line.path}",
+                    class: "{props.class_line_path}",
+                    stroke: "{color}",
+                    stroke_width: "{props.line_width}",
+                    stroke_linecap: "round",
+                    fill: "transparent",
+                },
+                for d in line.dots {
+                    line {
+                        x1: "{d.min.x}",
+                        y1: "{d.min.y}",
+                        x2: "{d.max.x}",
+                        y2: "{d.max.y}",
+                        class: "{props.class_line_dot}",
+                        stroke: "{color}",
+                        stroke_width: "{props.dot_size}",
                         stroke_linecap: "round",
-                        fill: "transparent",
-                    },
-                    for d in dots {
-                        line {
-                            x1: "{d.min.x}",
-                            y1: "{d.min.y}",
-                            x2: "{d.max.x}",
-                            y2: "{d.max.y}",
-                            class: "{props.class_line_dot}",
-                            stroke: "rgb({color_var}, 40, 40)",
-                            stroke_width: "{props.dot_size}",
-                            stroke_linecap: "round",
-                        }
                     }
-                    for point in text_point {
-                        text {
-                            dx: format_args!("{}", point.x + 10.0),
-                            dy: "{point.y}",
-                            text_anchor: "start",
-                            color: "rgb({color_var}, 40, 40)",
-                            class: "{props.class_line_label}",
-                            "{label}"
-                        }
+                }
+                for (point, label) in line.label {
+                    text {
+                        dx: format_args!("{}", point.x + 10.0),
+                        dy: "{point.y}",
+                        text_anchor: "start",
+                        color: "{color}",
+                        class: "{props.class_line_label}",
+                        "{label}"
                     }
                 }
             }
-        });
+        }
+    });
 
     rsx! {
         div {
@@ -315,21 +237,21 @@ pub fn LineChart(props: LineChartProps) -> Element {
                 if props.show_grid {
                     g {
                         class: "{props.class_grid}",
-                        for line in lines {
+                        for line in chart.grid_lines {
                             line {
                                 x1: "{line.min.x}",
                                 y1: "{line.min.y}",
                                 x2: "{line.max.x}",
                                 y2: "{line.max.y}",
                                 class: "{props.class_grid_line}",
-                                stroke: "rgba(20, 20, 20, 0.8)",
-                                stroke_dasharray: "{dotted_stroke}",
+                                stroke: "{props.grid_color}",
+                                stroke_dasharray: "{chart.dotted_stroke}",
                             }
                         }
                     }
                 }
 
-                for labels in grid_labels {
+                for labels in chart.grid_labels {
                     g {
                         class: "{props.class_grid_labels}",
                         for (text, label) in labels {
@@ -350,3 +272,151 @@ pub fn LineChart(props: LineChartProps) -> Element {
         }
     }
 }
+
+/// One drawn line of a `LineChartModel`.
+pub struct SeriesModel {
+    /// The SVG path commands of the line.
+    pub path: String,
+    pub dots: Vec<Rect>,
+    /// Where to draw the series label, if the series has one.
+    pub label: Option<(Point, String)>,
+    pub color: String,
+}
+
+/// Everything `LineChart` draws, in view box coordinates. Kept separate from the `rsx!` so the
+/// same chart can be rendered outside of Dioxus (see `chart::svg`).
+pub struct LineChartModel {
+    pub grid_lines: Vec<Rect>,
+    pub grid_labels: Option<Vec<(TextData, String)>>,
+    pub dotted_stroke: &'static str,
+    pub series: Vec<SeriesModel>,
+}
+
+impl LineChartModel {
+    /// Lays out the chart described by `props`. Returns `None` if any of the series are empty.
+    pub fn new(props: &LineChartProps) -> Option<Self> {
+        if props.series.iter().any(|series| series.is_empty()) {
+            return None;
+        }
+
+        let view = Rect::new(
+            props.padding_left as f32,
+            props.padding_top as f32,
+            (props.viewbox_width - props.padding_right) as f32,
+            (props.viewbox_height - props.padding_bottom) as f32,
+        );
+
+        let max_ticks = props.max_ticks.max(3);
+        let max_points = props
+            .max_points
+            .unwrap_or(view.width().max(0.0) as usize)
+            .max(3);
+
+        let axis_x = Axis::builder()
+            .with_view(view)
+            .with_grid_ticks(props.show_grid_ticks)
+            .with_labels(props.labels.as_ref());
+
+        let axis_y = Axis::builder()
+            .with_view(view)
+            .with_max_ticks(max_ticks)
+            .with_grid_ticks(props.show_grid_ticks)
+            .with_series(&props.series)
+            .with_label_interpolation(props.label_interpolation)
+            .with_highest(props.highest)
+            .with_lowest(props.lowest);
+
+        let grid = Grid::new(axis_x, axis_y);
+        let generated_labels = grid.y.generated_labels();
+
+        let grid_labels = if props.show_labels {
+            if let Some(labels) = props.labels.as_ref() {
+                Some(
+                    grid.text_data(Some(labels.len()), Some(generated_labels.len()))
+                        .into_iter()
+                        .zip(labels.iter().chain(generated_labels.iter()).cloned())
+                        .collect::<Vec<(TextData, String)>>(),
+                )
+            } else {
+                Some(
+                    grid.y
+                        .text_data(generated_labels.len())
+                        .into_iter()
+                        .zip(generated_labels.iter().cloned())
+                        .collect::<Vec<(TextData, String)>>(),
+                )
+            }
+        } else {
+            None
+        };
+
+        let mut color_var = 255.0;
+        let dotted_stroke = if props.show_dotted_grid { "2px" } else { "0px" };
+
+        let string_binding = String::new();
+        let vec_binding = vec![];
+
+        let series = props
+            .series
+            .iter()
+            .enumerate()
+            .zip(
+                props
+                    .series_labels
+                    .as_ref()
+                    .unwrap_or(&vec_binding)
+                    .iter()
+                    .chain(std::iter::repeat(&string_binding)),
+            )
+            .map(|((i, a), label)| {
+                let points = lttb(a, max_points);
+                let mut commands = Vec::<String>::with_capacity(points.len());
+                let mut dots = Vec::<Rect>::with_capacity(points.len());
+                let mut text_point: Option<Point> = None;
+
+                color_var -= 75.0 * (1.0 / (i + 1) as f32);
+
+                for (index, v) in points {
+                    let point = grid.world_to_view(index as f32, v, false);
+
+                    if index == 0 {
+                        commands.push(format!("M{},{}", point.x, point.y));
+                    } else {
+                        commands.push(format!("L{},{}", point.x, point.y));
+                    }
+
+                    if props.show_dots {
+                        dots.push(Rect::new(point.x, point.y, point.x + 0.1, point.y));
+                    }
+
+                    if !label.is_empty() && index == (a.len() - 1) {
+                        text_point = Some(point);
+                    }
+                }
+
+                SeriesModel {
+                    path: commands.join(" "),
+                    dots,
+                    label: text_point.map(|point| (point, label.clone())),
+                    color: props
+                        .line_colors
+                        .as_ref()
+                        .filter(|colors| !colors.is_empty())
+                        .map(|colors| colors[i % colors.len()].clone())
+                        .unwrap_or_else(|| format!("rgb({color_var}, 40, 40)")),
+                }
+            })
+            .collect();
+
+        Some(Self {
+            grid_lines: if props.show_grid {
+                grid.lines()
+            } else {
+                vec![]
+            },
+            grid_labels,
+            dotted_stroke,
+            series,
+        })
+    }
+}
@@ -1,7 +1,9 @@
 // pulled from the dioxus charts crate found here: https://github.com/dioxus-community/dioxus-charts
 // the specific release: https://github.com/dioxus-community/dioxus-charts/tree/32b7d2d0dfa38b0da48a4ed13dc91effe9b693be
 
+pub mod downsample;
 pub mod grid;
 pub mod line;
+pub mod svg;
 pub mod types;
 pub mod utils;
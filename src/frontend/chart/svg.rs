@@ -0,0 +1,241 @@
+use std::fmt::{self, Display, Write};
+
+use super::line::{LineChartModel, LineChartProps};
+
+/// Why a chart snapshot couldn't be rendered.
+#[derive(Debug)]
+pub enum ChartRenderError {
+    EmptySeries,
+    #[cfg(feature = "chart_png")]
+    Svg(resvg::usvg::Error),
+    #[cfg(feature = "chart_png")]
+    Pixmap {
+        width: u32,
+        height: u32,
+    },
+    #[cfg(feature = "chart_png")]
+    Png(String),
+}
+
+impl Display for ChartRenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptySeries => write!(f, "line chart error: empty series"),
+            #[cfg(feature = "chart_png")]
+            Self::Svg(e) => write!(f, "failed to parse the chart svg: {e}"),
+            #[cfg(feature = "chart_png")]
+            Self::Pixmap { width, height } => {
+                write!(f, "can't make a {width}x{height} pixmap for the chart")
+            }
+            #[cfg(feature = "chart_png")]
+            Self::Png(e) => write!(f, "failed to encode the chart png: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ChartRenderError {}
+
+/// Escapes text for use in SVG text nodes & attribute values.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders the same chart as the `LineChart` component to a standalone SVG document, without
+/// needing Dioxus. The document's `width` & `height` are the view box size in pixels, since
+/// `props.width`/`props.height` are usually relative to a parent element that doesn't exist
+/// here.
+pub fn line_chart_svg(props: &LineChartProps) -> Result<String, ChartRenderError> {
+    let chart = LineChartModel::new(props).ok_or(ChartRenderError::EmptySeries)?;
+    let mut svg = String::new();
+
+    // writing to a `String` can't fail.
+    let _ = write_svg(&mut svg, props, chart);
+
+    Ok(svg)
+}
+
+fn write_svg(svg: &mut String, props: &LineChartProps, chart: LineChartModel) -> fmt::Result {
+    let (width, height) = (props.viewbox_width, props.viewbox_height);
+
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" class="{}" preserveAspectRatio="xMidYMid meet" viewBox="0 0 {width} {height}">"#,
+        escape(&props.class_chart_line),
+    )?;
+
+    if props.show_grid {
+        writeln!(svg, r#"<g class="{}">"#, escape(&props.class_grid))?;
+
+        for line in chart.grid_lines {
+            writeln!(
+                svg,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}" class="{}" stroke="{}" stroke-dasharray="{}"/>"#,
+                line.min.x,
+                line.min.y,
+                line.max.x,
+                line.max.y,
+                escape(&props.class_grid_line),
+                escape(&props.grid_color),
+                chart.dotted_stroke,
+            )?;
+        }
+
+        writeln!(svg, "</g>")?;
+    }
+
+    if let Some(labels) = chart.grid_labels {
+        writeln!(svg, r#"<g class="{}">"#, escape(&props.class_grid_labels))?;
+
+        for (text, label) in labels {
+            writeln!(
+                svg,
+                r#"<text dx="{}" dy="{}" text-anchor="{}" class="{}" alignment-baseline="{}">{}</text>"#,
+                text.x,
+                text.y,
+                text.anchor,
+                escape(&props.class_grid_label),
+                text.baseline,
+                escape(&label),
+            )?;
+        }
+
+        writeln!(svg, "</g>")?;
+    }
+
+    for (i, line) in chart.series.into_iter().enumerate() {
+        writeln!(svg, r#"<g class="{}-{i}">"#, escape(&props.class_line))?;
+        writeln!(
+            svg,
+            r#"<path d="{}" class="{}" stroke="{}" stroke-width="{}" stroke-linecap="round" fill="transparent"/>"#,
+            line.path,
+            escape(&props.class_line_path),
+            escape(&line.color),
+            escape(&props.line_width),
+        )?;
+
+        for dot in line.dots {
+            writeln!(
+                svg,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}" class="{}" stroke="{}" stroke-width="{}" stroke-linecap="round"/>"#,
+                dot.min.x,
+                dot.min.y,
+                dot.max.x,
+                dot.max.y,
+                escape(&props.class_line_dot),
+                escape(&line.color),
+                escape(&props.dot_size),
+            )?;
+        }
+
+        if let Some((point, label)) = line.label {
+            writeln!(
+                svg,
+                r#"<text dx="{}" dy="{}" text-anchor="start" color="{}" class="{}">{}</text>"#,
+                point.x + 10.0,
+                point.y,
+                escape(&line.color),
+                escape(&props.class_line_label),
+                escape(&label),
+            )?;
+        }
+
+        writeln!(svg, "</g>")?;
+    }
+
+    writeln!(svg, "</svg>")
+}
+
+/// Rasterises the chart to a PNG, `scale` times the size of the view box. Text is drawn with the
+/// system fonts.
+#[cfg(feature = "chart_png")]
+pub fn line_chart_png(props: &LineChartProps, scale: f32) -> Result<Vec<u8>, ChartRenderError> {
+    use resvg::{tiny_skia, usvg};
+
+    let svg = line_chart_svg(props)?;
+    let mut options = usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+
+    let tree = usvg::Tree::from_str(&svg, &options).map_err(ChartRenderError::Svg)?;
+    let size = tree.size().to_int_size().scale_by(scale);
+    let (width, height) = size
+        .map(|size| (size.width(), size.height()))
+        .unwrap_or_default();
+    let mut pixmap =
+        tiny_skia::Pixmap::new(width, height).ok_or(ChartRenderError::Pixmap { width, height })?;
+
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+
+    pixmap
+        .encode_png()
+        .map_err(|e| ChartRenderError::Png(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::*;
+
+    /// where the expected SVGs live. set `UPDATE_GOLDEN=1` to rewrite them after an intended
+    /// change to the chart layout, then check the diff.
+    const GOLDEN_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/frontend/chart/golden");
+
+    fn assert_golden(name: &str, svg: &str) {
+        let path = Path::new(GOLDEN_DIR).join(name);
+
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            fs::write(&path, svg).unwrap();
+        }
+
+        let golden = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("can't read {}: {e}", path.display()));
+
+        assert_eq!(svg, golden, "{name} doesn't match the golden svg");
+    }
+
+    #[test]
+    fn line_chart_matches_golden() {
+        let props = LineChartProps::builder()
+            .series(vec![
+                vec![0.0, 1.0, 4.0, 9.0, 16.0, 25.0],
+                vec![10.0, 8.0, 12.0, 6.0, 14.0, 4.0],
+            ])
+            .labels(vec![
+                "a".into(),
+                "b".into(),
+                "c".into(),
+                "d".into(),
+                "e".into(),
+                "f".into(),
+            ])
+            .series_labels(vec!["squares".into(), "<zigzag>".into()])
+            .viewbox_width(300)
+            .viewbox_height(200)
+            .padding_top(10)
+            .padding_bottom(20)
+            .padding_left(30)
+            .padding_right(40)
+            .line_colors(vec!["#a6e3a1".into(), "#f38ba8".into()])
+            .label_interpolation((|v| format!("{v:.0}")) as fn(f32) -> String)
+            .build();
+
+        assert_golden("line_chart.svg", &line_chart_svg(&props).unwrap());
+    }
+
+    #[test]
+    fn empty_series_are_an_error() {
+        let props = LineChartProps::builder().series(vec![vec![]]).build();
+
+        assert!(matches!(
+            line_chart_svg(&props),
+            Err(ChartRenderError::EmptySeries)
+        ));
+    }
+}
@@ -1,10 +1,43 @@
 use std::fmt;
 
-pub(crate) type Series = Vec<Vec<f32>>;
-pub(crate) type Labels = Vec<String>;
+use crate::backend::big_num::BigNum;
+
+pub type Series = Vec<Vec<f32>>;
+pub type Labels = Vec<String>;
+
+/// a value that can be plotted as one point of a `Series`.
+pub(crate) trait SeriesValue {
+    fn series_value(&self) -> f32;
+}
+
+impl SeriesValue for f32 {
+    fn series_value(&self) -> f32 {
+        *self
+    }
+}
+
+impl SeriesValue for f64 {
+    fn series_value(&self) -> f32 {
+        *self as f32
+    }
+}
+
+impl SeriesValue for BigNum {
+    fn series_value(&self) -> f32 {
+        self.to_f32()
+    }
+}
+
+/// converts raw values into one line of a `Series`.
+pub(crate) fn to_series<T: SeriesValue>(values: impl IntoIterator<Item = T>) -> Vec<f32> {
+    values
+        .into_iter()
+        .map(|value| value.series_value())
+        .collect()
+}
 
 #[derive(Clone, Copy, Default)]
-pub(crate) struct Point {
+pub struct Point {
     pub x: f32,
     pub y: f32,
 }
@@ -23,7 +56,7 @@ impl fmt::Display for Point {
 }
 
 #[derive(Clone, Copy, Default)]
-pub(crate) struct Rect {
+pub struct Rect {
     pub min: Point,
     pub max: Point,
 }
@@ -52,7 +85,7 @@ impl fmt::Display for Rect {
 }
 
 #[derive(Clone)]
-pub(crate) struct TextData {
+pub struct TextData {
     pub x: f32,
     pub y: f32,
     pub anchor: &'static str,
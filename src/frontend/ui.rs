@@ -1,94 +1,156 @@
-use std::{any::TypeId, ops::Deref, time::Duration};
+use std::{
+    any::TypeId,
+    fs,
+    ops::Deref,
+    path::{Path, PathBuf},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
 
 use crate::{
     backend::{
+        achievement_plugin::{AchievementCatalog, Achievements, Toasts},
+        action_plugin::{ActionMap, Input, Rebinding},
+        base_plugin::{GameStatus, UiCommand},
+        bestiary_plugin::{Bestiary, EnemyCatalog},
+        big_num::BigNum,
+        combat::CombatStats,
+        diagnostics_plugin::{DiagnosticsOverlay, PerfReport},
+        dungeon_plugin::view::DungeonView,
+        economy_plugin::{Currency, Generator, Income, OfflineProgress, Upgrade, ledger::Ledger},
+        export_plugin::{EXPORT_DIR, ExportFormat, ExportSettings},
         idle_time_plugin::{
-            AutomationSpeedSample, IDLE_SAMPLE_WINDOW, IdleTimeSample, WResolution,
+            AutomationSettings, AutomationSpeedHistory, IdleTimeHistory, WResolution,
+            history::{HistoryRange, SampleHistory, TimeSample},
+        },
+        inventory_plugin::{EquipSlot, Inventory, ItemCatalog, Loadouts},
+        party_plugin::{Party, PartyCard},
+        prestige_plugin::{
+            MIN_PRESTIGE_RENOWN, MetaUpgrade, PrestigePreview, PrestigeScreen, PrestigeState,
         },
+        stats_plugin::{LifetimeStats, SessionStats, Stats},
+        status_effect_plugin::{GlobalEffectSummary, effect::EffectSummary},
+        theme::{Palette, Theme},
         *,
     },
-    frontend::line::LineChart,
+    frontend::{
+        line::{LineChart, LineChartProps},
+        svg::line_chart_svg,
+        types::to_series,
+    },
 };
 use bevy_dioxus_hooks::{BevyValue, resource::hook::use_bevy_resource};
 use bevy_dioxus_sync::panels::DioxusElementMarker;
-use crossbeam::channel::Receiver;
+use crossbeam::channel::Sender;
 use dioxus::prelude::*;
 
 #[derive(Debug)]
 pub struct AppUi {
-    pub idle_time: Receiver<IdleTimeSample>,
-    pub automation_speed: Receiver<AutomationSpeedSample>,
+    pub commands: Sender<UiCommand>,
 }
 
 impl DioxusElementMarker for AppUi {
     fn element(&self) -> Element {
-        game_ui(self.idle_time.clone(), self.automation_speed.clone())
+        game_ui(self.commands.clone())
     }
 }
 
-pub fn game_ui(
-    idle_time: Receiver<IdleTimeSample>,
-    speed_samples: Receiver<AutomationSpeedSample>,
-) -> Element {
+pub fn game_ui(commands: Sender<UiCommand>) -> Element {
+    let build_start = Instant::now();
+    let theme_res = use_bevy_resource::<Theme>();
+    let dungeon_view_res = use_bevy_resource::<DungeonView>();
+    let action_map_res = use_bevy_resource::<ActionMap>();
+    let rebinding_res = use_bevy_resource::<Rebinding>();
+    let status_res = use_bevy_resource::<GameStatus>();
+    let overlay_res = use_bevy_resource::<DiagnosticsOverlay>();
+    let prestige_screen_res = use_bevy_resource::<PrestigeScreen>();
+    let automation_settings_res = use_bevy_resource::<AutomationSettings>();
+    let export_settings_res = use_bevy_resource::<ExportSettings>();
     let idle_time_res = use_bevy_resource::<CurrentIdleTimeSeconds>();
     let best_idle_time_res = use_bevy_resource::<LongestIdleTimeSeconds>();
     let window_size = use_bevy_resource::<WResolution>();
-    let mut max_idle_time: Signal<f32> = use_signal(|| 0.0);
-    let mut idle_times: Signal<Vec<_>> =
-        use_signal(|| vec![IdleTimeSample::new(0.0), IdleTimeSample::new(0.0)]);
-    let mut automation_speed_samples: Signal<Vec<_>> = use_signal(|| {
-        vec![
-            AutomationSpeedSample::new(0.0),
-            AutomationSpeedSample::new(0.0),
-        ]
-    });
-    let mut max_automation_speed: Signal<f32> = use_signal(|| 0.0);
-
-    let _idle_time_th = spawn(async move {
-        loop {
-            while let Ok(sample) = idle_time.try_recv() {
-                idle_times.write().push(sample);
-                idle_times
-                    .write()
-                    .retain(|time| time.when.elapsed() < IDLE_SAMPLE_WINDOW);
-
-                *max_idle_time.write() = idle_times
-                    .read()
-                    .iter()
-                    .max_by(|a, b| a.time.total_cmp(&b.time).then(std::cmp::Ordering::Less))
-                    .map(|time| time.time)
-                    .unwrap_or(1.0) as f32;
-            }
-
-            portable_async_sleep::async_sleep(Duration::from_secs_f32(0.25)).await;
-        }
-    });
+    let mut show_settings = use_signal(|| false);
+    let mut show_stats = use_signal(|| false);
+    let mut show_bestiary = use_signal(|| false);
+    let mut show_inventory = use_signal(|| false);
+    let mut show_economy = use_signal(|| false);
+    let mut show_achievements = use_signal(|| false);
 
-    let _speed_th = spawn(async move {
-        loop {
-            while let Ok(sample) = speed_samples.try_recv() {
-                automation_speed_samples.write().push(sample);
-                automation_speed_samples
-                    .write()
-                    .retain(|time| time.when.elapsed() < IDLE_SAMPLE_WINDOW);
+    let theme = theme_res
+        .read()
+        .deref()
+        .read_value()
+        .copied()
+        .unwrap_or_default();
+    let dungeon_view = dungeon_view_res
+        .read()
+        .deref()
+        .read_value()
+        .copied()
+        .unwrap_or_default();
+    let action_map = action_map_res
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+    let rebinding = rebinding_res
+        .read()
+        .deref()
+        .read_value()
+        .copied()
+        .unwrap_or_default();
+    let status = status_res
+        .read()
+        .deref()
+        .read_value()
+        .copied()
+        .unwrap_or_default();
+    let overlay = overlay_res
+        .read()
+        .deref()
+        .read_value()
+        .copied()
+        .unwrap_or_default();
+    let prestige_screen = prestige_screen_res
+        .read()
+        .deref()
+        .read_value()
+        .copied()
+        .unwrap_or_default();
+    let automation_settings = automation_settings_res
+        .read()
+        .deref()
+        .read_value()
+        .copied()
+        .unwrap_or_default();
+    let export_on_exit = export_settings_res
+        .read()
+        .deref()
+        .read_value()
+        .and_then(|settings| settings.on_exit);
+    let conflicts = action_map.conflicts();
+    let build_commands = commands.clone();
+    let inventory_commands = commands.clone();
+    let party_commands = commands.clone();
+    let economy_commands = commands.clone();
+    let prestige_commands = commands.clone();
+    let toast_commands = commands.clone();
+    let send = move |command: UiCommand| {
+        let commands = commands.clone();
 
-                *max_automation_speed.write() = idle_times
-                    .read()
-                    .iter()
-                    .max_by(|a, b| a.time.total_cmp(&b.time).then(std::cmp::Ordering::Less))
-                    .map(|time| time.time)
-                    .unwrap_or(1.0) as f32;
+        move |_| {
+            if let Err(e) = commands.send(command.clone()) {
+                log::error!("{e}");
             }
-
-            portable_async_sleep::async_sleep(Duration::from_secs_f32(0.25)).await;
         }
-    });
+    };
 
-    rsx! {
+    let element = rsx! {
         document::Stylesheet { href: asset!("src/frontend/ui.css") }
 
         main {
             progress_bar {
+                theme: theme,
                 curent_time: idle_time_res
                     .read()
                     .deref()
@@ -103,96 +165,345 @@ pub fn game_ui(
                     .unwrap_or(0.0)
             }
 
+            currency_bar { theme: theme }
+
+            // toolbar (game controls, session export, theme selection & dungeon view toggle).
             div {
                 style: "
-                width: 100%;
-                height: 20%;
                 display: flex;
                 flex-direction: row;
+                gap: 0.25rem;
+                margin-top: 0.25rem;
+                margin-bottom: 0.25rem;
                 ",
 
-                // skills seciton (skill order & known skills)
+                button {
+                    style: button_style(&theme, status.paused),
+                    onclick: send(UiCommand::TogglePause),
+
+                    if status.paused { "resume" } else { "pause" }
+                }
+
+                button {
+                    style: button_style(&theme, status.automating),
+                    onclick: send(UiCommand::ToggleAutomation),
+
+                    if status.automating { "stop automating" } else { "automate now" }
+                }
+
+                button {
+                    style: button_style(&theme, automation_settings.on_focus_loss),
+                    onclick: send(UiCommand::SetAutomateOnFocusLoss(!automation_settings.on_focus_loss)),
+
+                    "automate on focus loss"
+                }
+
+                for format in [ExportFormat::Csv, ExportFormat::Json] {
+                    button {
+                        style: button_style(&theme, false),
+                        onclick: send(UiCommand::Export(format)),
+
+                        "export {format}"
+                    }
+                }
+
+                button {
+                    style: button_style(&theme, export_on_exit.is_some()),
+                    onclick: send(UiCommand::SetExportOnExit(match export_on_exit {
+                        Some(_) => None,
+                        None => Some(ExportFormat::Json),
+                    })),
+
+                    "export json on exit"
+                }
+
+                for palette in Palette::ALL {
+                    button {
+                        style: button_style(&theme, theme.palette == palette),
+                        onclick: send(UiCommand::SetPalette(palette)),
+
+                        "{palette}"
+                    }
+                }
+
+                button {
+                    style: button_style(&theme, dungeon_view.enabled),
+                    onclick: send(UiCommand::ToggleDungeonView),
+
+                    "dungeon view"
+                }
+
+                button {
+                    style: button_style(&theme, show_settings()),
+                    onclick: move |_| show_settings.toggle(),
+
+                    "key bindings"
+                }
+
+                button {
+                    style: button_style(&theme, show_stats()),
+                    onclick: move |_| show_stats.toggle(),
+
+                    "stats"
+                }
+
+                button {
+                    style: button_style(&theme, show_bestiary()),
+                    onclick: move |_| show_bestiary.toggle(),
+
+                    "bestiary"
+                }
+
+                button {
+                    style: button_style(&theme, show_inventory()),
+                    onclick: move |_| show_inventory.toggle(),
+
+                    "inventory"
+                }
+
+                button {
+                    style: button_style(&theme, show_economy()),
+                    onclick: move |_| show_economy.toggle(),
+
+                    "economy"
+                }
+
+                button {
+                    style: button_style(&theme, show_achievements()),
+                    onclick: move |_| show_achievements.toggle(),
+
+                    "achievements"
+                }
+
+                button {
+                    style: button_style(&theme, prestige_screen.open),
+                    onclick: send(UiCommand::TogglePrestigeScreen),
+
+                    "prestige"
+                }
+
+                button {
+                    style: button_style(&theme, overlay.visible),
+                    onclick: send(UiCommand::ToggleDiagnostics),
+
+                    "diagnostics"
+                }
+            }
+
+            if overlay.visible {
+                diagnostics_panel { theme: theme }
+            }
+
+            if prestige_screen.open {
+                prestige_panel {
+                    theme: theme,
+                    on_command: move |command| {
+                        if let Err(e) = prestige_commands.send(command) {
+                            log::error!("{e}");
+                        }
+                    },
+                }
+            }
+
+            if show_stats() {
+                stats_panel { theme: theme }
+            }
+
+            if show_bestiary() {
+                bestiary_panel { theme: theme }
+            }
+
+            if show_achievements() {
+                achievements_panel { theme: theme }
+            }
+
+            if show_economy() {
+                economy_panel {
+                    theme: theme,
+                    on_command: move |command| {
+                        if let Err(e) = economy_commands.send(command) {
+                            log::error!("{e}");
+                        }
+                    },
+                }
+            }
+
+            if show_inventory() {
+                inventory_panel {
+                    theme: theme,
+                    on_command: move |command| {
+                        if let Err(e) = inventory_commands.send(command) {
+                            log::error!("{e}");
+                        }
+                    },
+                }
+            }
+
+            // key binding settings.
+            if show_settings() {
                 div {
                     style: "
-                    width: 50%;
-                    height: 100%;
+                    display: flex;
+                    flex-direction: column;
+                    gap: 0.25rem;
+                    margin-bottom: 0.25rem;
                     ",
 
-                    // TODO: battle skill order.
-                    div {
-                        style: "
-                        display: flex;
-                        flex-direction: row;
-                        ",
+                    for (action, binding) in action_map.iter().map(|(action, binding)| (*action, binding.clone())) {
+                        div {
+                            style: "
+                            display: flex;
+                            flex-direction: row;
+                            align-items: center;
+                            gap: 0.25rem;
+                            ",
+
+                            span {
+                                style: format!(
+                                    "width: 10rem; color: {};",
+                                    if conflicts.iter().any(|(_, actions)| actions.contains(&action)) {
+                                        theme.chart_line.to_hex()
+                                    } else {
+                                        theme.on_surface.to_hex()
+                                    },
+                                ),
+
+                                "{action}"
+                            }
 
-                        // TODO: battle skill 1
-                        // for battle_skill in battle_skills[1..] {
-                        // make seperator div,
-                        // make battle skill i
-                        // }
+                            button {
+                                style: button_style(&theme, *rebinding == Some(action)),
+                                onclick: send(UiCommand::Rebind(Some(action))),
 
+                                if *rebinding == Some(action) {
+                                    "press a key..."
+                                } else {
+                                    "{input_names(&binding.inputs)}"
+                                }
+                            }
+
+                            button {
+                                style: button_style(&theme, binding.counts_as_activity),
+                                onclick: send(UiCommand::SetCountsAsActivity(action, !binding.counts_as_activity)),
+
+                                "counts as activity"
+                            }
+                        }
+                    }
+
+                    for (input, actions) in conflicts.iter() {
+                        span {
+                            style: format!("color: {};", theme.chart_line.to_hex()),
+
+                            "{input} is bound to: {actions:?}"
+                        }
                     }
 
-                    // TODO: known battle skills.
                     div {
                         style: "
-                        display: grid;
-                        grid-template-columns: auto,
-                        grid-template-rows: auto,
-                        gap: 2rem,
+                        display: flex;
+                        flex-direction: row;
+                        gap: 0.25rem;
                         ",
 
-                        // TODO:
-                        // for battle_skill in known_battle_skills {
-                        // make battle skill i (grayed out if in order)
-                        // }
+                        if rebinding.is_some() {
+                            button {
+                                style: button_style(&theme, false),
+                                onclick: send(UiCommand::Rebind(None)),
+
+                                "cancel"
+                            }
+                        }
+
+                        button {
+                            style: button_style(&theme, false),
+                            onclick: send(UiCommand::ResetBindings),
 
+                            "reset to defaults"
+                        }
                     }
                 }
+            }
+
+            div {
+                style: "
+                width: 100%;
+                height: 20%;
+                display: flex;
+                flex-direction: row;
+                ",
+
+                // party cards, with each member's skill order & known skills.
+                party_cards {
+                    theme: theme,
+                    on_command: move |command| {
+                        if let Err(e) = party_commands.send(command) {
+                            log::error!("{e}");
+                        }
+                    },
+                }
 
                 // automation speed graph.
-                automation_speed_graph { speed_samples: automation_speed_samples, window_size: window_size }
+                automation_speed_graph { theme: theme, window_size: window_size }
 
                 // idle speed graph
-                idle_time_graph {
-                    heighest: max_idle_time,
-                    time_samples: idle_times,
-                    window_size: window_size,
-                }
+                idle_time_graph { theme: theme, window_size: window_size }
+            }
+
+            toast_stack {
+                theme: theme,
+                on_command: move |command| {
+                    if let Err(e) = toast_commands.send(command) {
+                        log::error!("{e}");
+                    }
+                },
             }
         }
+    };
+
+    // only reported while it's being looked at, so the command channel stays quiet otherwise.
+    if overlay.visible
+        && let Err(e) = build_commands.send(UiCommand::ReportBuildTime(build_start.elapsed()))
+    {
+        log::error!("{e}");
     }
+
+    element
 }
 
 #[component]
 fn automation_speed_graph(
-    speed_samples: ReadSignal<Vec<AutomationSpeedSample>>,
+    theme: Theme,
     window_size: Signal<BevyValue<WResolution, TypeId, ()>, SyncStorage>,
 ) -> Element {
-    let mut series = use_signal(|| Vec::new());
-
-    use_effect(move || {
-        *series.write() = speed_samples()
-            .iter()
-            .map(|time| time.time as f32)
-            .collect();
-    });
+    let history = use_bevy_resource::<AutomationSpeedHistory>();
+    let range = use_signal(HistoryRange::default);
+    let series = history
+        .read()
+        .deref()
+        .read_value()
+        .map(|history| range_series(history, range()))
+        .unwrap_or_else(|| vec![0.0, 0.0]);
 
     rsx! {
         div {
-            style: "
+            style: format!("
                 width: 25%;
                 height: 100%;
                 border: 5px solid black;
                 margin: 5;
                 border-radius: calc(1.5rem / 2);
                 margin-radius: calc(1.5rem / 2);
-                color: #11111bff;
-            ",
+                color: {};
+            ", theme.on_surface.to_hex()),
+
+            history_range_selector { theme: theme, selected: range,
+                save_chart_button { theme: theme, name: "automation-speed", series: series.clone() }
+            }
 
             LineChart {
                 width: "100%",
-                height: "100%",
+                height: "85%",
                 max_ticks: 8,
                 viewbox_width: {
                     window_size.read().read_value().map(|size| size.w as i32).unwrap_or(1920) / 4
@@ -211,12 +522,14 @@ fn automation_speed_graph(
                 show_dots: false,
                 show_lines: true,
                 lowest: Some(0.0),
-                highest: series().iter().max_by(|a, b| a.total_cmp(&b).then(std::cmp::Ordering::Less)).map(|f| *f),
+                highest: series.iter().copied().reduce(f32::max),
                 label_interpolation: (|_v| "".into()) as fn(f32) -> String,
+                line_colors: vec![theme.chart_line.to_hex()],
+                grid_color: theme.chart_grid.to_hex(),
                 series: vec![
-                    series(),
+                    series.clone(),
                 ],
-                labels: Some((0..speed_samples.len()).map(|_i| "".into()).collect()),
+                labels: Some((0..series.len()).map(|_i| "".into()).collect()),
             }
         }
     }
@@ -224,31 +537,37 @@ fn automation_speed_graph(
 
 #[component]
 fn idle_time_graph(
-    heighest: ReadSignal<f32>,
-    time_samples: ReadSignal<Vec<IdleTimeSample>>,
+    theme: Theme,
     window_size: Signal<BevyValue<WResolution, TypeId, ()>, SyncStorage>,
 ) -> Element {
-    let mut series = use_signal(|| Vec::new());
-
-    use_effect(move || {
-        *series.write() = time_samples().iter().map(|time| time.time as f32).collect();
-    });
+    let history = use_bevy_resource::<IdleTimeHistory>();
+    let range = use_signal(HistoryRange::default);
+    let series = history
+        .read()
+        .deref()
+        .read_value()
+        .map(|history| range_series(history, range()))
+        .unwrap_or_else(|| vec![0.0, 0.0]);
 
     rsx! {
         div {
-            style: "
+            style: format!("
                 width: 25%;
                 height: 100%;
                 border: 5px solid black;
                 margin: 5;
                 border-radius: calc(1.5rem / 2);
                 margin-radius: calc(1.5rem / 2);
-                color: #11111bff;
-            ",
+                color: {};
+            ", theme.on_surface.to_hex()),
+
+            history_range_selector { theme: theme, selected: range,
+                save_chart_button { theme: theme, name: "idle-time", series: series.clone() }
+            }
 
             LineChart {
                 width: "100%",
-                height: "100%",
+                height: "85%",
                 max_ticks: 8,
                 viewbox_width: {
                     window_size.read().read_value().map(|size| size.w as i32).unwrap_or(1920) / 4
@@ -267,10 +586,12 @@ fn idle_time_graph(
                 show_dots: false,
                 show_lines: true,
                 lowest: Some(0.0),
-                highest: Some(heighest.read().deref().to_owned()),
+                highest: series.iter().copied().reduce(f32::max),
                 label_interpolation: (|_v| "".into()) as fn(f32) -> String,
+                line_colors: vec![theme.chart_line.to_hex()],
+                grid_color: theme.chart_grid.to_hex(),
                 series: vec![
-                    series(),
+                    series.clone(),
                 ],
                 labels: Some((0..series.len()).map(|_i| "".into()).collect()),
             }
@@ -278,6 +599,1057 @@ fn idle_time_graph(
     }
 }
 
+/// the chart series for `range`, padded so the chart always has a line to draw.
+fn range_series<S>(history: &SampleHistory<S>, range: HistoryRange) -> Vec<f32>
+where
+    S: TimeSample + Send + Sync + 'static,
+{
+    let series = to_series(history.samples(range).iter().map(|sample| sample.value()));
+
+    if series.len() < 2 {
+        vec![0.0, 0.0]
+    } else {
+        series
+    }
+}
+
+/// the inputs of a binding, as shown in the key binding settings.
+fn input_names(inputs: &[Input]) -> String {
+    if inputs.is_empty() {
+        return "unbound".into();
+    }
+
+    inputs
+        .iter()
+        .map(Input::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// style of the small toolbar buttons, `selected` buttons are drawn in the accent color.
+fn button_style(theme: &Theme, selected: bool) -> String {
+    format!(
+        "
+        border: none;
+        border-radius: calc(1.5rem / 4);
+        background-color: {};
+        color: {};
+        ",
+        if selected {
+            theme.accent.to_hex()
+        } else {
+            theme.surface.to_hex()
+        },
+        theme.on_surface.to_hex(),
+    )
+}
+
+#[component]
+fn history_range_selector(
+    theme: Theme,
+    mut selected: Signal<HistoryRange>,
+    children: Element,
+) -> Element {
+    rsx! {
+        div {
+            style: "
+                height: 15%;
+                display: flex;
+                flex-direction: row;
+                gap: 0.25rem;
+            ",
+
+            for range in HistoryRange::ALL {
+                button {
+                    style: button_style(&theme, selected() == range),
+                    onclick: move |_| selected.set(range),
+
+                    "{range}"
+                }
+            }
+
+            {children}
+        }
+    }
+}
+
+/// saves the chart as a standalone svg in the export directory, so it can be shared.
+#[component]
+fn save_chart_button(theme: Theme, name: &'static str, series: Vec<f32>) -> Element {
+    let export_settings = use_bevy_resource::<ExportSettings>();
+    let dir = export_settings
+        .read()
+        .deref()
+        .read_value()
+        .map(|settings| settings.dir.clone())
+        .unwrap_or_else(|| PathBuf::from(EXPORT_DIR));
+
+    rsx! {
+        button {
+            style: button_style(&theme, false),
+            onclick: move |_| save_chart(&dir, name, series.clone(), &theme),
+
+            "save svg"
+        }
+    }
+}
+
+fn save_chart(dir: &Path, name: &str, series: Vec<f32>, theme: &Theme) {
+    let props = LineChartProps::builder()
+        .series(vec![series])
+        .lowest(0.0)
+        .show_dots(false)
+        .show_line_labels(false)
+        .line_colors(vec![theme.chart_line.to_hex()])
+        .grid_color(theme.chart_grid.to_hex())
+        .build();
+    let path = dir.join(format!(
+        "{name}-{}.svg",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_millis())
+            .unwrap_or_default()
+    ));
+    let saved = line_chart_svg(&props)
+        .map_err(|e| e.to_string())
+        .and_then(|svg| {
+            fs::create_dir_all(dir)
+                .and_then(|_| fs::write(&path, svg))
+                .map_err(|e| e.to_string())
+        });
+
+    match saved {
+        Ok(()) => log::info!("saved the {name} chart to {}", path.display()),
+        Err(e) => log::error!("failed to save the {name} chart: {e}"),
+    }
+}
+
+#[component]
+fn stats_panel(theme: Theme) -> Element {
+    let session = use_bevy_resource::<SessionStats>();
+    let lifetime = use_bevy_resource::<LifetimeStats>();
+    let session = session
+        .read()
+        .deref()
+        .read_value()
+        .map(|stats| stats.0)
+        .unwrap_or_default();
+    let lifetime = lifetime
+        .read()
+        .deref()
+        .read_value()
+        .map(|stats| stats.0)
+        .unwrap_or_default();
+    let rows = stat_rows(&session).into_iter().zip(stat_rows(&lifetime));
+
+    rsx! {
+        div {
+            style: format!("
+                display: flex;
+                flex-direction: row;
+                gap: 1rem;
+                margin-bottom: 0.25rem;
+                color: {};
+            ", theme.on_surface.to_hex()),
+
+            table {
+                tr {
+                    th {}
+                    th { "session" }
+                    th { "lifetime" }
+                }
+
+                for ((name, session), (_, lifetime)) in rows {
+                    tr {
+                        td { "{name}" }
+                        td { "{session}" }
+                        td { "{lifetime}" }
+                    }
+                }
+            }
+
+            bevy_fps {}
+        }
+    }
+}
+
+#[component]
+fn diagnostics_panel(theme: Theme) -> Element {
+    let report = use_bevy_resource::<PerfReport>();
+    let report = report
+        .read()
+        .deref()
+        .read_value()
+        .copied()
+        .unwrap_or_default();
+
+    rsx! {
+        div {
+            style: format!("
+                display: flex;
+                flex-direction: row;
+                gap: 1rem;
+                margin-bottom: 0.25rem;
+                color: {};
+            ", theme.on_surface.to_hex()),
+
+            table {
+                for (name, value) in perf_rows(&report) {
+                    tr {
+                        td { "{name}" }
+                        td { "{value}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// the rows of the diagnostics panel, as `(name, value)`.
+fn perf_rows(report: &PerfReport) -> [(&'static str, String); 10] {
+    [
+        ("fps", format!("{:.1}", report.fps)),
+        ("frame time p50", format!("{:.2} ms", report.frame_time_p50)),
+        ("frame time p95", format!("{:.2} ms", report.frame_time_p95)),
+        ("frame time p99", format!("{:.2} ms", report.frame_time_p99)),
+        ("ui build time", format!("{:.2} ms", report.ui_build_time)),
+        ("entities", format!("{:.0}", report.entities)),
+        ("input history", format!("{:.0}", report.input_history_len)),
+        (
+            "idle time history",
+            format!("{:.0}", report.idle_time_history_len),
+        ),
+        (
+            "automation speed history",
+            format!("{:.0}", report.automation_speed_history_len),
+        ),
+        (
+            "ui command queue",
+            format!("{:.0}", report.ui_command_queue),
+        ),
+    ]
+}
+
+#[component]
+fn bestiary_panel(theme: Theme) -> Element {
+    let catalog = use_bevy_resource::<EnemyCatalog>();
+    let bestiary = use_bevy_resource::<Bestiary>();
+    let catalog = catalog
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+    let bestiary = bestiary
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+    let encountered: Vec<_> = catalog
+        .iter()
+        .filter_map(|(id, enemy)| {
+            bestiary
+                .get(id)
+                .filter(|entry| entry.encountered > 0)
+                .map(|entry| (enemy.clone(), *entry))
+        })
+        .collect();
+
+    rsx! {
+        div {
+            style: format!("
+                display: flex;
+                flex-direction: column;
+                gap: 0.25rem;
+                margin-bottom: 0.25rem;
+                color: {};
+            ", theme.on_surface.to_hex()),
+
+            span { "{encountered.len()} of {catalog.len()} enemies discovered" }
+
+            table {
+                tr {
+                    th {}
+                    th { "enemy" }
+                    th { "killed" }
+                    th { "seen" }
+                    th { "hp" }
+                    th { "atk" }
+                    th { "def" }
+                    th { "spd" }
+                    th { "resistances" }
+                    th { "skills" }
+                }
+
+                for (enemy, entry) in encountered {
+                    tr {
+                        title: "{enemy.art_hint.description}",
+
+                        td {
+                            div {
+                                style: format!(
+                                    "width: 1rem; height: 1rem; border-radius: 50%; background-color: {};",
+                                    enemy.art_hint.color,
+                                ),
+                            }
+                        }
+                        td { "{enemy.name}" }
+                        td { "{entry.killed}" }
+                        td { "{entry.encountered}" }
+                        td { "{enemy.stats.hp}" }
+                        td { "{enemy.stats.attack}" }
+                        td { "{enemy.stats.defense}" }
+                        td { "{enemy.stats.speed}" }
+                        td {
+                            {
+                                enemy
+                                    .resistances
+                                    .iter()
+                                    .map(|(damage_type, value)| format!("{damage_type} {:+.0}%", value * 100.0))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            }
+                        }
+                        td { "{enemy.skills.join(\", \")}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn inventory_panel(theme: Theme, on_command: EventHandler<UiCommand>) -> Element {
+    let catalog = use_bevy_resource::<ItemCatalog>();
+    let inventory = use_bevy_resource::<Inventory>();
+    let loadouts = use_bevy_resource::<Loadouts>();
+    let catalog = catalog
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+    let inventory = inventory
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+    let loadouts = loadouts
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+    let stacks: Vec<_> = inventory
+        .iter()
+        .enumerate()
+        .filter_map(|(i, stack)| {
+            catalog
+                .get(&stack.item)
+                .map(|item| (i, stack.item.clone(), stack.count, item.clone()))
+        })
+        .collect();
+
+    rsx! {
+        div {
+            style: format!("
+                display: flex;
+                flex-direction: column;
+                gap: 0.25rem;
+                margin-bottom: 0.25rem;
+                color: {};
+            ", theme.on_surface.to_hex()),
+
+            span { "{inventory.len()} of {inventory.capacity()} slots used" }
+
+            table {
+                tr {
+                    th { "item" }
+                    th { "count" }
+                    th { "rarity" }
+                    th { "slot" }
+                    th { "effects" }
+                    th {}
+                }
+
+                for (i, id, count, item) in stacks {
+                    tr {
+                        title: "{item.description}",
+
+                        td {
+                            style: format!("color: {};", item.rarity.color(&theme).to_hex()),
+
+                            "{item.name}"
+                        }
+                        td { "{count}" }
+                        td { "{item.rarity}" }
+                        td {
+                            {item.slot.map(|slot| slot.to_string()).unwrap_or_default()}
+                        }
+                        td {
+                            {
+                                item.modifiers
+                                    .iter()
+                                    .map(ToString::to_string)
+                                    .chain(item.applies.iter().map(|effect| format!("applies {effect}")))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            }
+                        }
+                        td {
+                            if item.slot.is_some() {
+                                for loadout in loadouts.iter() {
+                                    button {
+                                        style: button_style(&theme, false),
+                                        onclick: {
+                                            let member = loadout.entity;
+                                            let item = id.clone();
+
+                                            move |_| on_command.call(UiCommand::Equip {
+                                                stack: i,
+                                                item: item.clone(),
+                                                member,
+                                            })
+                                        },
+
+                                        "equip on {loadout.name}"
+                                    }
+                                }
+                            }
+
+                            if item.applies.is_some() {
+                                button {
+                                    style: button_style(&theme, false),
+                                    onclick: {
+                                        let item = id.clone();
+
+                                        move |_| on_command.call(UiCommand::UseItem { stack: i, item: item.clone() })
+                                    },
+
+                                    "use"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if loadouts.is_empty() {
+                span { "no one can equip items yet" }
+            }
+
+            for loadout in loadouts.iter() {
+                div {
+                    style: "
+                        display: flex;
+                        flex-direction: row;
+                        gap: 1rem;
+                    ",
+
+                    span { "{loadout.name}" }
+
+                    for slot in EquipSlot::ALL {
+                        span {
+                            "{slot}: "
+
+                            if let Some(item) = loadout.equipment.get(&slot) {
+                                span {
+                                    style: format!(
+                                        "color: {};",
+                                        catalog
+                                            .get(item)
+                                            .map(|item| item.rarity.color(&theme))
+                                            .unwrap_or(theme.on_surface)
+                                            .to_hex(),
+                                    ),
+
+                                    "{item} "
+                                }
+                                button {
+                                    style: button_style(&theme, false),
+                                    onclick: {
+                                        let member = loadout.entity;
+
+                                        move |_| on_command.call(UiCommand::Unequip { member, slot })
+                                    },
+
+                                    "unequip"
+                                }
+                            } else {
+                                "empty"
+                            }
+                        }
+                    }
+
+                    span { "{combat_stats(&loadout.stats)}" }
+                }
+            }
+        }
+    }
+}
+
+/// every currency's balance & income, always shown under the idle time bar, along with the
+/// effects running on the whole game.
+#[component]
+fn currency_bar(theme: Theme) -> Element {
+    let ledger = use_bevy_resource::<Ledger>();
+    let income = use_bevy_resource::<Income>();
+    let global_effects = use_bevy_resource::<GlobalEffectSummary>();
+    let ledger = ledger
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+    let income = income
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+    let global_effects = global_effects
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+
+    rsx! {
+        div {
+            style: format!("
+                display: flex;
+                flex-direction: row;
+                gap: 1rem;
+                margin-top: 0.25rem;
+                color: {};
+            ", theme.on_surface.to_hex()),
+
+            for currency in Currency::ALL {
+                span {
+                    "{currency} {ledger.balance(currency)} "
+                    span {
+                        style: "opacity: 0.75;",
+
+                        "(+{income.get(&currency).copied().unwrap_or_default()}/s)"
+                    }
+                }
+            }
+
+            effect_list { theme, effects: global_effects.0 }
+        }
+    }
+}
+
+#[component]
+fn economy_panel(theme: Theme, on_command: EventHandler<UiCommand>) -> Element {
+    let ledger = use_bevy_resource::<Ledger>();
+    let offline = use_bevy_resource::<OfflineProgress>();
+    let ledger = ledger
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+    let offline = offline
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+    let offline_earned = offline
+        .earned
+        .iter()
+        .filter(|(_, earned)| !earned.is_zero())
+        .map(|(currency, earned)| format!("+{earned} {currency}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    rsx! {
+        div {
+            style: format!("
+                display: flex;
+                flex-direction: row;
+                gap: 1rem;
+                margin-bottom: 0.25rem;
+                color: {};
+            ", theme.on_surface.to_hex()),
+
+            table {
+                tr {
+                    th { "generator" }
+                    th { "owned" }
+                    th { "makes at 1 Hz" }
+                    th { "cost" }
+                    th {}
+                }
+
+                for generator in Generator::ALL {
+                    tr {
+                        td { "{generator}" }
+                        td { "{ledger.owned(generator)}" }
+                        td { "{ledger.output(generator, 1.0)} {generator.currency()}/s" }
+                        td { "{ledger.generator_cost(generator)} {generator.cost_currency()}" }
+                        td {
+                            button {
+                                style: button_style(
+                                    &theme,
+                                    ledger.balance(generator.cost_currency()) >= ledger.generator_cost(generator),
+                                ),
+                                onclick: move |_| on_command.call(UiCommand::BuyGenerator(generator)),
+
+                                "buy"
+                            }
+                        }
+                    }
+                }
+            }
+
+            table {
+                tr {
+                    th { "upgrade" }
+                    th { "effect" }
+                    th { "cost" }
+                    th {}
+                }
+
+                for upgrade in Upgrade::ALL {
+                    tr {
+                        td { "{upgrade}" }
+                        td { "{upgrade.effect()}" }
+                        td { "{upgrade.cost().1} {upgrade.cost().0}" }
+                        td {
+                            if ledger.upgrades.contains(&upgrade) {
+                                "bought"
+                            } else {
+                                button {
+                                    style: button_style(
+                                        &theme,
+                                        ledger.balance(upgrade.cost().0) >= upgrade.cost().1,
+                                    ),
+                                    onclick: move |_| on_command.call(UiCommand::BuyUpgrade(upgrade)),
+
+                                    "buy"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if offline.secs > 0.0 && !offline_earned.is_empty() {
+                span { "while you were away ({format_secs(offline.secs)}): {offline_earned}" }
+            }
+        }
+    }
+}
+
+/// the prestige confirmation screen, with the meta upgrades renown can buy.
+#[component]
+fn prestige_panel(theme: Theme, on_command: EventHandler<UiCommand>) -> Element {
+    let state = use_bevy_resource::<PrestigeState>();
+    let preview = use_bevy_resource::<PrestigePreview>();
+    let state = state
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+    let preview = preview
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+    let can_prestige = preview.renown >= BigNum::from(MIN_PRESTIGE_RENOWN);
+
+    rsx! {
+        div {
+            style: format!("
+                display: flex;
+                flex-direction: column;
+                gap: 0.25rem;
+                margin-bottom: 0.25rem;
+                color: {};
+            ", theme.on_surface.to_hex()),
+
+            span { "{state.renown} renown, {state.prestiges} prestiges so far" }
+
+            table {
+                tr {
+                    th { "meta upgrade" }
+                    th { "level" }
+                    th { "effect" }
+                    th { "cost" }
+                    th {}
+                }
+
+                for upgrade in MetaUpgrade::ALL {
+                    tr {
+                        td { "{upgrade}" }
+                        td { "{state.level(upgrade)} / {upgrade.max_level()}" }
+                        td { "{upgrade.effect(state.level(upgrade))}" }
+                        if state.level(upgrade) < upgrade.max_level() {
+                            td { "{upgrade.cost(state.level(upgrade))} renown" }
+                            td {
+                                button {
+                                    style: button_style(&theme, state.renown >= upgrade.cost(state.level(upgrade))),
+                                    onclick: move |_| on_command.call(UiCommand::BuyMetaUpgrade(upgrade)),
+
+                                    "buy"
+                                }
+                            }
+                        } else {
+                            td { "maxed" }
+                            td {}
+                        }
+                    }
+                }
+            }
+
+            span {
+                "prestiging now earns {preview.renown} renown "
+                "({preview.breakdown.depth:.1} from depth, {preview.breakdown.idle:.1} from idle time, "
+                "{preview.breakdown.currency:.1} from currency)"
+            }
+
+            div {
+                style: "
+                    display: flex;
+                    flex-direction: row;
+                    gap: 2rem;
+                ",
+
+                div {
+                    b { "kept" }
+                    ul {
+                        for kept in preview.kept.iter() {
+                            li { "{kept}" }
+                        }
+                    }
+                }
+
+                div {
+                    b { "lost" }
+                    ul {
+                        for lost in preview.lost.iter() {
+                            li { "{lost}" }
+                        }
+                    }
+                }
+            }
+
+            div {
+                style: "
+                    display: flex;
+                    flex-direction: row;
+                    gap: 0.25rem;
+                ",
+
+                button {
+                    style: button_style(&theme, can_prestige),
+                    disabled: !can_prestige,
+                    onclick: move |_| on_command.call(UiCommand::Prestige),
+
+                    "prestige"
+                }
+
+                button {
+                    style: button_style(&theme, false),
+                    onclick: move |_| on_command.call(UiCommand::TogglePrestigeScreen),
+
+                    "cancel"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn achievements_panel(theme: Theme) -> Element {
+    let catalog = use_bevy_resource::<AchievementCatalog>();
+    let achievements = use_bevy_resource::<Achievements>();
+    let catalog = catalog
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+    let achievements = achievements
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+
+    rsx! {
+        div {
+            style: format!("
+                display: flex;
+                flex-direction: column;
+                gap: 0.25rem;
+                margin-bottom: 0.25rem;
+                color: {};
+            ", theme.on_surface.to_hex()),
+
+            span { "{achievements.len()} / {catalog.len()} achievements unlocked" }
+
+            table {
+                tr {
+                    th { "achievement" }
+                    th { "goal" }
+                    th { "bonus" }
+                }
+
+                for (id, definition) in catalog.iter() {
+                    tr {
+                        style: if achievements.contains_key(id) { "" } else { "opacity: 0.5;" },
+
+                        td { title: "{definition.description}", "{definition.name}" }
+                        td { "{definition.goal}" }
+                        td {
+                            if let Some(bonus) = definition.bonus {
+                                "{bonus}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// notifications stacked in the bottom right corner, clicking one dismisses it.
+#[component]
+fn toast_stack(theme: Theme, on_command: EventHandler<UiCommand>) -> Element {
+    let toasts = use_bevy_resource::<Toasts>();
+    let toasts = toasts
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+
+    rsx! {
+        div {
+            style: "
+                position: fixed;
+                right: 0.5rem;
+                bottom: 0.5rem;
+                display: flex;
+                flex-direction: column;
+                gap: 0.25rem;
+            ",
+
+            for toast in toasts.iter().cloned() {
+                div {
+                    key: "{toast.id}",
+                    style: format!("
+                        display: flex;
+                        flex-direction: column;
+                        padding: 0.5rem;
+                        border-radius: 0.25rem;
+                        cursor: pointer;
+                        background-color: {};
+                        color: {};
+                        border-left: 0.25rem solid {};
+                    ", theme.surface.to_hex(), theme.on_surface.to_hex(), theme.accent.to_hex()),
+                    onclick: move |_| on_command.call(UiCommand::DismissToast(toast.id)),
+
+                    b { "{toast.title}" }
+                    if !toast.body.is_empty() {
+                        span { "{toast.body}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn party_cards(theme: Theme, on_command: EventHandler<UiCommand>) -> Element {
+    let party = use_bevy_resource::<Party>();
+    let party = party
+        .read()
+        .deref()
+        .read_value()
+        .cloned()
+        .unwrap_or_default();
+
+    rsx! {
+        div {
+            style: "
+                width: 50%;
+                height: 100%;
+                display: flex;
+                flex-direction: row;
+                gap: 0.5rem;
+                overflow-x: auto;
+            ",
+
+            for card in party.iter().cloned() {
+                party_card { theme: theme, card: card, on_command: on_command }
+            }
+        }
+    }
+}
+
+#[component]
+fn party_card(theme: Theme, card: PartyCard, on_command: EventHandler<UiCommand>) -> Element {
+    let member = card.entity;
+    let xp = match card.xp_to_next {
+        Some(xp_to_next) => format!("{} / {xp_to_next} xp", card.xp),
+        None => "max level".to_string(),
+    };
+    let empty_slots = card.skill_slots.saturating_sub(card.skill_order.len());
+
+    rsx! {
+        div {
+            style: format!("
+                display: flex;
+                flex-direction: column;
+                gap: 0.25rem;
+                padding: 0.5rem;
+                min-width: 12rem;
+                border-radius: calc(1.5rem / 2);
+                background-color: {};
+                color: {};
+            ", theme.mantle.to_hex(), theme.on_surface.to_hex()),
+
+            span {
+                b { "{card.name}" }
+                " lvl {card.level} {card.class}"
+            }
+            span { "{xp}" }
+            span { "{card.hp:.0} / {card.stats.hp:.0} hp" }
+            span { "{combat_stats(&card.stats)}" }
+            effect_list { theme, effects: card.effects }
+
+            // battle skill order, click a skill to take it out or the arrow to use it sooner.
+            div {
+                style: "
+                    display: flex;
+                    flex-direction: row;
+                    flex-wrap: wrap;
+                    align-items: center;
+                    gap: 0.25rem;
+                ",
+
+                for (i, skill) in card.skill_order.iter().enumerate() {
+                    if i > 0 {
+                        button {
+                            style: button_style(&theme, false),
+                            title: "use sooner",
+                            onclick: move |_| on_command.call(UiCommand::MoveSkill { member, from: i, to: i - 1 }),
+
+                            "‹"
+                        }
+                    }
+
+                    button {
+                        style: button_style(&theme, i == card.next_skill),
+                        title: "take out of the order",
+                        onclick: move |_| on_command.call(UiCommand::UnslotSkill { member, slot: i }),
+
+                        "{skill}"
+                    }
+                }
+
+                for _ in 0..empty_slots {
+                    span { style: "opacity: 0.5;", "empty" }
+                }
+            }
+
+            // known skills, the ones already in the order are grayed out.
+            div {
+                style: "
+                    display: grid;
+                    grid-template-columns: repeat(2, auto);
+                    gap: 0.25rem;
+                ",
+
+                for (i, skill) in card.known_skills.iter().enumerate() {
+                    button {
+                        style: format!(
+                            "{} opacity: {};",
+                            button_style(&theme, false),
+                            if card.skill_order.contains(skill) { 0.5 } else { 1.0 },
+                        ),
+                        title: "add to the order",
+                        onclick: {
+                            let name = skill.clone();
+
+                            move |_| on_command.call(UiCommand::SlotSkill { member, skill: i, name: name.clone() })
+                        },
+
+                        "{skill}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// status effects on one line, colored by whether they're buffs or debuffs.
+#[component]
+fn effect_list(theme: Theme, effects: Vec<EffectSummary>) -> Element {
+    rsx! {
+        span {
+            style: "
+                display: flex;
+                flex-direction: row;
+                flex-wrap: wrap;
+                gap: 0.5rem;
+            ",
+
+            for effect in effects {
+                span {
+                    style: format!("color: {};", effect.kind.color(&theme).to_hex()),
+                    title: "{effect.kind}",
+
+                    "{effect}"
+                }
+            }
+        }
+    }
+}
+
+/// a combatant's stats on one line.
+fn combat_stats(stats: &CombatStats) -> String {
+    format!(
+        "hp {:.0}, atk {:.1}, def {:.1}, spd {:.2}",
+        stats.hp, stats.attack, stats.defense, stats.speed
+    )
+}
+
+/// the rows of the stats panel, as `(name, value)`.
+fn stat_rows(stats: &Stats) -> [(&'static str, String); 8] {
+    [
+        ("play time", format_secs(stats.play_secs)),
+        ("active", format_secs(stats.active_secs)),
+        ("idle", format_secs(stats.idle_secs)),
+        ("paused", format_secs(stats.paused_secs)),
+        ("manual", format_secs(stats.manual_secs)),
+        ("automating", format_secs(stats.automation_secs)),
+        (
+            "inputs per minute",
+            format!("{:.1}", stats.inputs_per_minute()),
+        ),
+        (
+            "peak automation speed",
+            format!("{:.2} Hz", stats.peak_automation_speed),
+        ),
+    ]
+}
+
+/// formats a number of seconds as hours, minutes & seconds.
+fn format_secs(secs: f64) -> String {
+    let secs = secs as u64;
+
+    format!("{}h {:02}m {:02}s", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
 #[component]
 fn bevy_fps() -> Element {
     let fps = use_bevy_resource::<FPS>();
@@ -291,23 +1663,23 @@ fn bevy_fps() -> Element {
 
 #[component]
 // fn progress_bar(curent_time: f64, longest_time: f64) -> Element {
-fn progress_bar(curent_time: f32, longest_time: ReadSignal<f32>) -> Element {
+fn progress_bar(theme: Theme, curent_time: f32, longest_time: ReadSignal<f32>) -> Element {
     rsx! {
         div {
-            style: "
+            style: format!("
                 width: 100%;
                 height: 1.5rem;
-                background-color: #585b70ff;
+                background-color: {};
                 border-radius: calc(1.5rem / 2);
                 overflow: hidden;
-                color: #11111bff;
+                color: {};
                 text-justify: center;
                 align-content: center;
                 align-items: center;
                 display: flex;
                 flex-direction: row;
                 position: relative;
-            ",
+            ", theme.surface.to_hex(), theme.on_surface.to_hex()),
 
             div {
                 style: "
@@ -320,10 +1692,11 @@ fn progress_bar(curent_time: f32, longest_time: ReadSignal<f32>) -> Element {
                 div {
                     style: format!("
                         height: 100%;
-                        background-color: #a6e3a1ff;
+                        background-color: {};
                         width: {}%;
                         z-index: 1;
                     ",
+                        theme.accent.to_hex(),
                         if curent_time > 0.0 && longest_time() > 0.0 {
                             curent_time / longest_time() * 100.
                         } else {
@@ -334,13 +1707,13 @@ fn progress_bar(curent_time: f32, longest_time: ReadSignal<f32>) -> Element {
                 }
 
                 div {
-                    style: "
+                    style: format!("
                         height: 100%;
                         background-color: #00000000;
-                        color: #11111bff;
+                        color: {};
                         width: auto;
                         z-index: 2;
-                    ",
+                    ", theme.on_surface.to_hex()),
 
                     "{curent_time:.2} sec / {longest_time:.1} sec"
                 }
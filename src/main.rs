@@ -8,17 +8,20 @@ use bevy::{
 use bevy_dioxus_sync::{panels::DioxusPanel, plugins::DioxusPlugin};
 use crossbeam::channel::unbounded;
 
-use crate::{
+use idle_dungeoneer::{
     backend::{
-        base_plugin::BasePlugin, bevy_scene_plugin::BevyScenePlugin,
-        idle_time_plugin::IdleTimePlugin, sphere::SpherePlugin,
+        achievement_plugin::AchievementPlugin, action_plugin::ActionPlugin,
+        base_plugin::BasePlugin, bestiary_plugin::BestiaryPlugin,
+        bevy_scene_plugin::BevyScenePlugin, combat::CombatPlugin,
+        diagnostics_plugin::DiagnosticsPlugin, dungeon_plugin::DungeonPlugin,
+        economy_plugin::EconomyPlugin, export_plugin::ExportPlugin,
+        idle_time_plugin::IdleTimePlugin, inventory_plugin::InventoryPlugin,
+        party_plugin::PartyPlugin, prestige_plugin::PrestigePlugin, sphere::SpherePlugin,
+        stats_plugin::StatsPlugin, status_effect_plugin::StatusEffectPlugin, theme::ThemePlugin,
     },
     frontend::AppUi,
 };
 
-pub mod backend;
-pub mod frontend;
-
 pub fn main() {
     let filter = format!(
         // "info,{}=trace",
@@ -28,21 +31,26 @@ pub fn main() {
     );
     let level = Level::INFO;
 
-    let default_plugins = DefaultPlugins.set(LogPlugin {
-        // Set the default log level for everything
-        level,
-        // Or use a filter string for fine-grained control
-        filter: filter.clone(),
-        ..default()
-    });
+    let default_plugins = DefaultPlugins
+        .set(LogPlugin {
+            // Set the default log level for everything
+            level,
+            // Or use a filter string for fine-grained control
+            filter: filter.clone(),
+            ..default()
+        })
+        // hot-reloads data assets (enemy definitions, etc.) with the `dev` feature.
+        .set(AssetPlugin {
+            watch_for_changes_override: Some(cfg!(feature = "dev")),
+            ..default()
+        });
 
     #[cfg(feature = "headless_ci")]
     let default_plugins = default_plugins
         .disable::<bevy::window::WindowPlugin>()
         .disable::<bevy::render::RenderPlugin>();
 
-    let (idle_tx, idle_rx) = unbounded();
-    let (speed_tx, speed_rx) = unbounded();
+    let (ui_tx, ui_rx) = unbounded();
 
     App::new()
         .add_plugins((default_plugins, FrameTimeDiagnosticsPlugin::default()))
@@ -50,13 +58,24 @@ pub fn main() {
         .add_plugins(SpherePlugin)
         .add_plugins(DioxusPlugin {
             bevy_info_refresh_fps: 30,
-            main_window_ui: Some(DioxusPanel::new(AppUi {
-                idle_time: idle_rx,
-                automation_speed: speed_rx,
-            })),
+            main_window_ui: Some(DioxusPanel::new(AppUi { commands: ui_tx })),
         })
-        .add_plugins(BasePlugin)
-        .add_plugins(IdleTimePlugin { idle_tx, speed_tx })
+        .add_plugins(BasePlugin { ui_rx })
+        .add_plugins(ActionPlugin)
+        .add_plugins(ThemePlugin)
+        .add_plugins(IdleTimePlugin)
+        .add_plugins(ExportPlugin)
+        .add_plugins(DungeonPlugin)
+        .add_plugins(BestiaryPlugin)
+        .add_plugins(InventoryPlugin)
+        .add_plugins(PartyPlugin)
+        .add_plugins(CombatPlugin)
+        .add_plugins(StatusEffectPlugin)
+        .add_plugins(EconomyPlugin)
+        .add_plugins(PrestigePlugin)
+        .add_plugins(AchievementPlugin)
+        .add_plugins(StatsPlugin)
+        .add_plugins(DiagnosticsPlugin)
         // logs log level and filters
         .add_systems(Startup, move || {
             info!("default log level is: {level}");